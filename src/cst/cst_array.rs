@@ -0,0 +1,83 @@
+use crate::cst::cst_node::{drop_cst_nodes, EnumCstNode, EnumCstPiece};
+use crate::cst::cst_token::CstToken;
+
+/// A value inside of an array along with the ',' that followed it (if any).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstElement
+{
+    pub value: EnumCstNode,
+    pub comma: Option<CstToken>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstArray
+{
+    open: CstToken,
+    elements: Vec<CstElement>,
+    close: CstToken,
+}
+
+impl CstArray
+{
+    pub fn new(open: CstToken, elements: Vec<CstElement>, close: CstToken) -> Self
+    {
+        Self { open, elements, close }
+    }
+
+    pub fn get_open_mut(&mut self) -> &mut CstToken
+    {
+        return &mut self.open;
+    }
+
+    pub fn len(&self) -> usize
+    {
+        return self.elements.len();
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.elements.is_empty();
+    }
+
+    pub fn get(&self, index: usize) -> Option<&EnumCstNode>
+    {
+        return self.elements.get(index).map(|element| &element.value);
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut EnumCstNode>
+    {
+        return self.elements.get_mut(index).map(|element| &mut element.value);
+    }
+
+    /// Pushes what makes up the array for 'write_pieces', in reverse order.
+    pub fn push_pieces<'a>(&'a self, pieces: &mut Vec<EnumCstPiece<'a>>)
+    {
+        pieces.push(EnumCstPiece::TOKEN(&self.close));
+
+        for element in self.elements.iter().rev()
+        {
+            if let Some(comma) = &element.comma
+            {
+                pieces.push(EnumCstPiece::TOKEN(comma));
+            }
+
+            pieces.push(EnumCstPiece::NODE(&element.value));
+        }
+
+        pieces.push(EnumCstPiece::TOKEN(&self.open));
+    }
+
+    /// Moves out the values of the elements. See 'drop_cst_nodes'.
+    pub fn take_values(&mut self) -> Vec<EnumCstNode>
+    {
+        return std::mem::take(&mut self.elements).into_iter().map(|element| element.value).collect();
+    }
+}
+
+impl Drop for CstArray
+{
+    fn drop(&mut self)
+    {
+        drop_cst_nodes(self.take_values());
+    }
+}
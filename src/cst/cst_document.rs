@@ -0,0 +1,448 @@
+use crate::cst::cst_array::{CstArray, CstElement};
+use crate::cst::cst_node::EnumCstNode;
+use crate::cst::cst_object::{CstMember, CstObject};
+use crate::cst::cst_token::CstToken;
+use crate::parser::dialect::EnumDialect;
+use crate::parser::lexer::Lexer;
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::parse_checks::{check_depth, get_identifier_key};
+use crate::parser::token::{describe_token, is_symbol, EnumTokenType, TokenTrait};
+
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+/// A lossless concrete syntax tree of a whole document. Unlike the RNode tree, it keeps
+/// whitespace, comments, key order and the exact spelling of every token, so editing a
+/// value only changes the text of that value when written back out.
+///
+/// ```text
+/// let mut document = CstDocument::parse(&input, &ParserOptions::default())?;
+/// document.replace("/servers/0/port", "8080")?;
+/// std::fs::write(path, document.to_string())?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstDocument
+{
+    root: EnumCstNode,
+    /// Whitespace (and comments) after the root value.
+    trailing_trivia: String,
+    options: ParserOptions,
+}
+
+impl CstDocument
+{
+    pub fn parse(input: &str, options: &ParserOptions) -> Result<Self, ParseError>
+    {
+        let mut builder = CstBuilder::new(input, options);
+        let root = builder.parse_value()?;
+        let trailing_trivia = builder.expect_end_of_input()?;
+
+        return Ok(Self { root, trailing_trivia, options: options.clone() });
+    }
+
+    pub fn get_root(&self) -> &EnumCstNode
+    {
+        return &self.root;
+    }
+
+    /// Gets the value at a JSON pointer (ex. "/servers/0/port", or "" for the root).
+    pub fn get(&self, pointer: &str) -> Option<&EnumCstNode>
+    {
+        let mut node = &self.root;
+
+        for segment in split_pointer(pointer)?
+        {
+            node = node.get_child(&segment)?;
+        }
+
+        return Some(node);
+    }
+
+    pub fn get_mut(&mut self, pointer: &str) -> Option<&mut EnumCstNode>
+    {
+        let mut node = &mut self.root;
+
+        for segment in split_pointer(pointer)?
+        {
+            node = node.get_child_mut(&segment)?;
+        }
+
+        return Some(node);
+    }
+
+    /// Replaces the value at a JSON pointer with 'text', which must be a single value. The
+    /// whitespace before the old value is kept and everything else in the document is untouched.
+    /// Returns false when there is no value at the pointer.
+    pub fn replace(&mut self, pointer: &str, text: &str) -> Result<bool, ParseError>
+    {
+        let mut builder = CstBuilder::new(&String::from(text), &self.options);
+        let mut new_node = builder.parse_value()?;
+        builder.expect_end_of_input()?;
+
+        match self.get_mut(pointer)
+        {
+            Some(node) =>
+            {
+                let leading_trivia = node.get_first_token_mut().get_leading_trivia().clone();
+                new_node.get_first_token_mut().set_leading_trivia(leading_trivia);
+                *node = new_node;
+
+                return Ok(true);
+            },
+            None => { return Ok(false); },
+        }
+    }
+}
+
+impl Display for CstDocument
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let mut output = String::new();
+        self.root.write_to(&mut output);
+        output.push_str(&self.trailing_trivia);
+
+        write!(f, "{}", output)
+    }
+}
+
+/// Splits a JSON pointer (RFC 6901) into its unescaped segments ('~1' is a '/' and '~0' is a '~').
+fn split_pointer(pointer: &str) -> Option<Vec<String>>
+{
+    if pointer.is_empty()
+    {
+        return Some(Vec::new());
+    }
+
+    let segments = pointer.strip_prefix('/')?.split('/');
+    return Some(segments.map(|segment| segment.replace("~1", "/").replace("~0", "~")).collect());
+}
+
+/// Builds the tree from the Lexer's tokens. The text between tokens (which the Lexer skips) becomes
+/// the leading trivia of the next token.
+struct CstBuilder
+{
+    lexer: Lexer,
+    /// Byte offset of the end of the last token.
+    last_end: usize,
+}
+
+/// An array/object whose elements/members are still being read.
+enum EnumCstFrame
+{
+    ARRAY(CstToken, Vec<CstElement>),
+    /// Also holds the key (as written and unquoted) and ':' of the member whose value is being read.
+    OBJECT(CstToken, Vec<CstMember>, Option<(CstToken, String, CstToken)>),
+}
+
+impl EnumCstFrame
+{
+    fn is_empty(&self) -> bool
+    {
+        match self
+        {
+            EnumCstFrame::ARRAY(_, elements) => { return elements.is_empty(); },
+            EnumCstFrame::OBJECT(_, members, _) => { return members.is_empty(); },
+        }
+    }
+
+    fn get_close(&self) -> char
+    {
+        match self
+        {
+            EnumCstFrame::ARRAY(..) => { return ']'; },
+            EnumCstFrame::OBJECT(..) => { return '}'; },
+        }
+    }
+
+    fn add(&mut self, value: EnumCstNode, comma: Option<CstToken>)
+    {
+        match self
+        {
+            EnumCstFrame::ARRAY(_, elements) => { elements.push(CstElement { value, comma }); },
+            EnumCstFrame::OBJECT(_, members, pending) =>
+            {
+                let (key, name, colon) = pending.take().unwrap();
+                members.push(CstMember { key, name, colon, value, comma });
+            },
+        }
+    }
+
+    fn close(self, close: CstToken) -> EnumCstNode
+    {
+        match self
+        {
+            EnumCstFrame::ARRAY(open, elements) => { return EnumCstNode::ARRAY(CstArray::new(open, elements, close)); },
+            EnumCstFrame::OBJECT(open, members, _) => { return EnumCstNode::OBJECT(CstObject::new(open, members, close)); },
+        }
+    }
+}
+
+impl CstBuilder
+{
+    fn new(input: &str, options: &ParserOptions) -> Self
+    {
+        // Stringify so that string keys are unescaped, the token text itself comes straight from the input.
+        let lexer = Lexer::new_copy(input, &options.to_builder().stringify(true).build());
+
+        Self { lexer, last_end: 0 }
+    }
+
+    fn next_token(&mut self) -> Result<(Rc<dyn TokenTrait>, CstToken), ParseError>
+    {
+        let token = self.lexer.next_token()?;
+        let start = self.lexer.get_token_start().get_start_pos();
+        let end = self.lexer.get_position();
+
+        let input = self.lexer.get_input();
+        let cst_token = CstToken::new(input[self.last_end..start].to_string(), input[start..end].to_string());
+        self.last_end = end;
+
+        return Ok((token, cst_token));
+    }
+
+    /// Consumes the next token if it is the given symbol.
+    fn try_consume_symbol(&mut self, symbol: &str) -> Option<CstToken>
+    {
+        let snapshot = self.lexer.snap();
+        let last_end = self.last_end;
+
+        if let Ok((token, cst_token)) = self.next_token()
+        {
+            if is_symbol(&*token, symbol)
+            {
+                return Some(cst_token);
+            }
+        }
+
+        self.lexer.restore(&snapshot);
+        self.last_end = last_end;
+        return None;
+    }
+
+    fn expect_symbol(&mut self, symbol: &str, make_kind: fn(String) -> EnumParseErrorKind) -> Result<CstToken, ParseError>
+    {
+        let (token, cst_token) = self.next_token()?;
+
+        if !is_symbol(&*token, symbol)
+        {
+            return Err(self.lexer.token_error(make_kind(describe_token(&*token))));
+        }
+
+        return Ok(cst_token);
+    }
+
+    fn expect_end_of_input(&mut self) -> Result<String, ParseError>
+    {
+        if !self.lexer.is_at_end()
+        {
+            let start = self.lexer.snap();
+            let token = self.lexer.next_token()?;
+            return Err(ParseError::new(EnumParseErrorKind::TrailingData(describe_token(&*token)), &start, self.lexer.get_position()));
+        }
+
+        return Ok(self.lexer.get_input()[self.last_end..].to_string());
+    }
+
+    /// Reads a value, keeping the arrays/objects it is nested in on an explicit stack rather than
+    /// recursing, so deeply nested input can't overflow the stack.
+    fn parse_value(&mut self) -> Result<EnumCstNode, ParseError>
+    {
+        let mut stack = Vec::<EnumCstFrame>::new();
+
+        loop
+        {
+            let (token, cst_token) = self.next_token()?;
+
+            let mut node = match token.get_type()
+            {
+                EnumTokenType::BOOL | EnumTokenType::DOUBLE | EnumTokenType::NULL | EnumTokenType::STRING => EnumCstNode::SCALAR(cst_token),
+                EnumTokenType::SYMBOL if is_symbol(&*token, "[") || is_symbol(&*token, "{") =>
+                {
+                    check_depth(&self.lexer, stack.len())?;
+
+                    let frame = if is_symbol(&*token, "[") { EnumCstFrame::ARRAY(cst_token, Vec::new()) } else { EnumCstFrame::OBJECT(cst_token, Vec::new(), None) };
+                    stack.push(frame);
+
+                    match self.begin_entry(&mut stack)?
+                    {
+                        Some(node) => node,
+                        None => { continue; },
+                    }
+                },
+                _ => { return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedValue(describe_token(&*token)))); },
+            };
+
+            // Add the value to the array/object it is in, closing every array/object that ends after it.
+            loop
+            {
+                let frame = match stack.last_mut()
+                {
+                    Some(frame) => frame,
+                    None => { return Ok(node); },
+                };
+
+                let close = frame.get_close();
+                let (token, cst_token) = self.next_token()?;
+
+                if is_symbol(&*token, ",")
+                {
+                    frame.add(node, Some(cst_token));
+
+                    match self.begin_entry(&mut stack)?
+                    {
+                        Some(closed) => { node = closed; },
+                        None => { break; },
+                    }
+                }
+
+                else if is_symbol(&*token, &close.to_string())
+                {
+                    frame.add(node, None);
+                    node = stack.pop().unwrap().close(cst_token);
+                }
+
+                else
+                {
+                    return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedCommaOrEnd { close, found: describe_token(&*token) }));
+                }
+            }
+        }
+    }
+
+    /// Starts the next element/member of the innermost array/object, reading the key and ':' of a
+    /// member. Gives back the array/object instead when it ends here: right after it opens, or
+    /// (with JSON5) after a trailing ','.
+    fn begin_entry(&mut self, stack: &mut Vec<EnumCstFrame>) -> Result<Option<EnumCstNode>, ParseError>
+    {
+        let frame = stack.last_mut().unwrap();
+
+        if frame.is_empty() || self.lexer.get_options().get_dialect() == EnumDialect::JSON5
+        {
+            if let Some(close) = self.try_consume_symbol(&frame.get_close().to_string())
+            {
+                return Ok(Some(stack.pop().unwrap().close(close)));
+            }
+        }
+
+        if let EnumCstFrame::OBJECT(_, _, pending) = frame
+        {
+            let (token, key) = self.next_token()?;
+            let name = match token.as_string()
+            {
+                Ok(name) => name.clone(),
+                Err(_) if self.lexer.get_options().get_dialect() == EnumDialect::JSON5 && get_identifier_key(&*token).is_some() => get_identifier_key(&*token).unwrap(),
+                Err(_) => { return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedKey(describe_token(&*token)))); },
+            };
+
+            let colon = self.expect_symbol(":", EnumParseErrorKind::ExpectedColon)?;
+            *pending = Some((key, name, colon));
+        }
+
+        return Ok(None);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::cst::cst_document::CstDocument;
+    use crate::cst::cst_node::EnumCstNode;
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::parse_error::EnumParseErrorKind;
+    use crate::parser::parser_options::ParserOptions;
+
+    #[test]
+    fn cst_round_trips_exactly()
+    {
+        let inputs = [
+            "  {\n\t\"b\" :1.50E+2 ,  \"a\":[ true,null , \"x\\u0041\\n\" ],\"a\": {}\r\n}\n\n",
+            "[]",
+            " \"\\uD83D\\uDE00\" ",
+            "-0",
+        ];
+
+        for input in inputs
+        {
+            let document = CstDocument::parse(&String::from(input), &ParserOptions::default()).unwrap();
+            assert_eq!(document.to_string(), input);
+        }
+
+        let input = String::from("// header\n{\n  unquoted: 'single', /* inline */\n  hex: 0x1F,\n  list: [1, 2,],\n} // footer\n");
+        let document = CstDocument::parse(&input, &ParserOptions::builder().dialect(EnumDialect::JSON5).build()).unwrap();
+        assert_eq!(document.to_string(), input);
+    }
+
+    #[test]
+    fn cst_get_by_pointer()
+    {
+        let input = String::from("{\"a/b\": {\"c~d\": [10, 20.0]}, \"dup\": 1, \"dup\": 2}");
+        let document = CstDocument::parse(&input, &ParserOptions::default()).unwrap();
+
+        assert_eq!(document.get("/a~1b/c~0d/1").unwrap().to_string(), " 20.0");
+        assert_eq!(document.get("/dup").unwrap().to_string(), " 2");
+        assert_eq!(document.get("").unwrap(), document.get_root());
+        assert!(document.get("/a~1b/c~0d/2").is_none());
+        assert!(document.get("/missing").is_none());
+        assert!(document.get("no-slash").is_none());
+
+        match document.get("/a~1b").unwrap()
+        {
+            EnumCstNode::OBJECT(object) => { assert_eq!(object.get_members()[0].key.get_text(), "\"c~d\""); },
+            _ => { panic!("Expected an object"); },
+        }
+    }
+
+    #[test]
+    fn cst_replace_only_changes_the_edited_value()
+    {
+        let input = String::from("{\n  // The port to listen on\n  \"port\":   8000,\n  \"hosts\": [ \"a\",\"b\" ]  ,\n  \"ratio\": 1.50\n}\n");
+        let mut document = CstDocument::parse(&input, &ParserOptions::builder().dialect(EnumDialect::JSON5).build()).unwrap();
+
+        assert_eq!(document.replace("/port", "8080"), Ok(true));
+        assert_eq!(document.to_string(), input.replace("8000", "8080"));
+
+        assert_eq!(document.replace("/hosts/1", "{\"name\": \"c\"}"), Ok(true));
+        assert_eq!(document.to_string(), input.replace("8000", "8080").replace("\"b\"", "{\"name\": \"c\"}"));
+
+        // Leading whitespace of the new text is dropped in favour of the old value's.
+        assert_eq!(document.replace("/ratio", "  2"), Ok(true));
+        assert!(document.to_string().ends_with("\"ratio\": 2\n}\n"));
+
+        assert_eq!(document.replace("/missing", "1"), Ok(false));
+        assert_eq!(document.replace("/port", "[1,").unwrap_err().get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+        assert!(document.replace("/port", "1 2").is_err());
+    }
+
+    #[test]
+    fn cst_deeply_nested_without_limit()
+    {
+        // Building, writing and dropping the tree mustn't overflow the stack however deep it is.
+        let depth = 100000;
+        let input = format!("{}{}{}", "[{\"a\": ".repeat(depth), "1", "}]".repeat(depth));
+        let document = CstDocument::parse(&input, &ParserOptions::builder().max_depth(None).build()).unwrap();
+        assert_eq!(document.to_string(), input);
+
+        let pointer = "/0/a".repeat(depth);
+        assert_eq!(document.get(&pointer).unwrap().to_string(), " 1");
+    }
+
+    #[test]
+    fn cst_parse_errors()
+    {
+        let inputs = [ "", "[1,]", "{\"a\" 1}", "{a: 1}", "[1 2]", "1 2", "// comment\n1" ];
+
+        for input in inputs
+        {
+            assert!(CstDocument::parse(&String::from(input), &ParserOptions::default()).is_err(), "{}", input);
+        }
+
+        let error = CstDocument::parse(&"[".repeat(100000), &ParserOptions::builder().max_depth(Some(128)).build()).unwrap_err();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::DepthLimitExceeded { limit: 128 });
+
+        let error = CstDocument::parse(&String::from("[1] x"), &ParserOptions::default()).unwrap_err();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TrailingData(String::from("'x'")));
+        assert_eq!(error.get_start(), 4);
+    }
+}
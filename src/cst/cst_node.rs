@@ -0,0 +1,104 @@
+use crate::cst::cst_array::CstArray;
+use crate::cst::cst_object::CstObject;
+use crate::cst::cst_token::CstToken;
+
+use std::fmt::{self, Display};
+
+/// A value in the concrete syntax tree. Writing it out gives back the exact source text,
+/// including whitespace and comments. Writing and dropping use a loop rather than recursion,
+/// the derived comparing, cloning and debug printing still recurse.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnumCstNode
+{
+    /// A string, number, bool or null.
+    SCALAR(CstToken),
+    ARRAY(CstArray),
+    OBJECT(CstObject),
+}
+
+impl EnumCstNode
+{
+    /// Gets the first token of the value, which holds the trivia before the value.
+    pub fn get_first_token_mut(&mut self) -> &mut CstToken
+    {
+        match self
+        {
+            EnumCstNode::SCALAR(token) => { return token; },
+            EnumCstNode::ARRAY(array) => { return array.get_open_mut(); },
+            EnumCstNode::OBJECT(object) => { return object.get_open_mut(); },
+        }
+    }
+
+    /// Gets an array element (by index) or object member (by name).
+    pub fn get_child(&self, segment: &str) -> Option<&EnumCstNode>
+    {
+        match self
+        {
+            EnumCstNode::SCALAR(_) => { return None; },
+            EnumCstNode::ARRAY(array) => { return array.get(segment.parse::<usize>().ok()?); },
+            EnumCstNode::OBJECT(object) => { return object.get(segment); },
+        }
+    }
+
+    pub fn get_child_mut(&mut self, segment: &str) -> Option<&mut EnumCstNode>
+    {
+        match self
+        {
+            EnumCstNode::SCALAR(_) => { return None; },
+            EnumCstNode::ARRAY(array) => { return array.get_mut(segment.parse::<usize>().ok()?); },
+            EnumCstNode::OBJECT(object) => { return object.get_mut(segment); },
+        }
+    }
+
+    pub fn write_to(&self, output: &mut String)
+    {
+        write_pieces(vec![EnumCstPiece::NODE(self)], output);
+    }
+}
+
+/// A part of the tree still to be written by 'write_pieces'.
+pub enum EnumCstPiece<'a>
+{
+    TOKEN(&'a CstToken),
+    NODE(&'a EnumCstNode),
+}
+
+/// Writes the pieces, the last one first. Arrays and objects push their own pieces in place of
+/// themselves, so nesting doesn't recurse.
+pub fn write_pieces(mut pieces: Vec<EnumCstPiece>, output: &mut String)
+{
+    while let Some(piece) = pieces.pop()
+    {
+        match piece
+        {
+            EnumCstPiece::TOKEN(token) | EnumCstPiece::NODE(EnumCstNode::SCALAR(token)) => { token.write_to(output); },
+            EnumCstPiece::NODE(EnumCstNode::ARRAY(array)) => { array.push_pieces(&mut pieces); },
+            EnumCstPiece::NODE(EnumCstNode::OBJECT(object)) => { object.push_pieces(&mut pieces); },
+        }
+    }
+}
+
+/// Drops the nodes along with everything nested in them using a loop rather than recursion (see
+/// 'drop_nodes'). Arrays and objects call this when dropped.
+pub fn drop_cst_nodes(mut nodes: Vec<EnumCstNode>)
+{
+    while let Some(mut node) = nodes.pop()
+    {
+        match &mut node
+        {
+            EnumCstNode::SCALAR(_) => {},
+            EnumCstNode::ARRAY(array) => { nodes.append(&mut array.take_values()); },
+            EnumCstNode::OBJECT(object) => { nodes.append(&mut object.take_values()); },
+        }
+    }
+}
+
+impl Display for EnumCstNode
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let mut output = String::new();
+        self.write_to(&mut output);
+        write!(f, "{}", output)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::cst::cst_node::{drop_cst_nodes, EnumCstNode, EnumCstPiece};
+use crate::cst::cst_token::CstToken;
+
+/// A 'key: value' pair of an object along with the ',' that followed it (if any).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstMember
+{
+    pub key: CstToken,
+    /// The key with any quotes and escapes removed.
+    pub name: String,
+    pub colon: CstToken,
+    pub value: EnumCstNode,
+    pub comma: Option<CstToken>,
+}
+
+/// An object whose members are kept in their original order (duplicates included).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstObject
+{
+    open: CstToken,
+    members: Vec<CstMember>,
+    close: CstToken,
+}
+
+impl CstObject
+{
+    pub fn new(open: CstToken, members: Vec<CstMember>, close: CstToken) -> Self
+    {
+        Self { open, members, close }
+    }
+
+    pub fn get_open_mut(&mut self) -> &mut CstToken
+    {
+        return &mut self.open;
+    }
+
+    pub fn get_members(&self) -> &Vec<CstMember>
+    {
+        return &self.members;
+    }
+
+    /// Gets the value of the member with the given name. When the name appears more than once,
+    /// the last one wins (just like when parsing into an RNodeObject).
+    pub fn get(&self, name: &str) -> Option<&EnumCstNode>
+    {
+        return self.members.iter().rev().find(|member| member.name == name).map(|member| &member.value);
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut EnumCstNode>
+    {
+        return self.members.iter_mut().rev().find(|member| member.name == name).map(|member| &mut member.value);
+    }
+
+    /// Pushes what makes up the object for 'write_pieces', in reverse order.
+    pub fn push_pieces<'a>(&'a self, pieces: &mut Vec<EnumCstPiece<'a>>)
+    {
+        pieces.push(EnumCstPiece::TOKEN(&self.close));
+
+        for member in self.members.iter().rev()
+        {
+            if let Some(comma) = &member.comma
+            {
+                pieces.push(EnumCstPiece::TOKEN(comma));
+            }
+
+            pieces.push(EnumCstPiece::NODE(&member.value));
+            pieces.push(EnumCstPiece::TOKEN(&member.colon));
+            pieces.push(EnumCstPiece::TOKEN(&member.key));
+        }
+
+        pieces.push(EnumCstPiece::TOKEN(&self.open));
+    }
+
+    /// Moves out the values of the members. See 'drop_cst_nodes'.
+    pub fn take_values(&mut self) -> Vec<EnumCstNode>
+    {
+        return std::mem::take(&mut self.members).into_iter().map(|member| member.value).collect();
+    }
+}
+
+impl Drop for CstObject
+{
+    fn drop(&mut self)
+    {
+        drop_cst_nodes(self.take_values());
+    }
+}
@@ -0,0 +1,38 @@
+/// A token exactly as it was written in the input, along with the whitespace (and JSON5 comments)
+/// that came before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstToken
+{
+    leading_trivia: String,
+    text: String,
+}
+
+impl CstToken
+{
+    pub fn new(leading_trivia: String, text: String) -> Self
+    {
+        Self { leading_trivia, text }
+    }
+
+    pub fn get_leading_trivia(&self) -> &String
+    {
+        return &self.leading_trivia;
+    }
+
+    pub fn set_leading_trivia(&mut self, leading_trivia: String)
+    {
+        self.leading_trivia = leading_trivia;
+    }
+
+    /// The token's source text (ex. '"a\n"' including the quotes and escapes, or '1.50').
+    pub fn get_text(&self) -> &String
+    {
+        return &self.text;
+    }
+
+    pub fn write_to(&self, output: &mut String)
+    {
+        output.push_str(&self.leading_trivia);
+        output.push_str(&self.text);
+    }
+}
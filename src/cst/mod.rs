@@ -0,0 +1,5 @@
+pub mod cst_array;
+pub mod cst_document;
+pub mod cst_node;
+pub mod cst_object;
+pub mod cst_token;
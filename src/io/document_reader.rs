@@ -0,0 +1,145 @@
+use crate::parser::parse_error::ParseError;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+use crate::rnodes::rnode::RNode;
+
+use std::io::{BufRead, Read};
+use std::rc::Rc;
+
+/// Reads an input holding a sequence of documents (ex. NDJSON), yielding each document (or the
+/// error that stopped it) as it is read. Errors are positioned in the whole input.
+pub trait DocumentReader: Iterator<Item = Result<Rc<dyn RNode>, ParseError>>
+{
+    /// Skips documents that fail to parse instead of yielding their errors, which are kept as warnings.
+    fn set_skip_bad_documents(&mut self, skip_bad_documents: bool);
+
+    /// Takes the warnings (ex. allowed duplicate keys and the errors of skipped documents) since the last call.
+    fn take_warnings(&mut self) -> Vec<ParseError>;
+
+    /// Gets the text of the last document read (ex. for rendering its error or warnings), which starts
+    /// at the byte offset 'get_input_start'.
+    fn get_input(&self) -> &String;
+
+    /// Gets the byte offset (into the whole input) of the start of 'get_input'.
+    fn get_input_start(&self) -> usize;
+
+    fn get_file_path(&self) -> Option<&String>;
+}
+
+/// Reads the text of the next document up to (and including) 'delimiter', for the readers whose
+/// documents are delimited by a byte (ex. the '\n' of NDJSON). A document longer than the
+/// 'max_input_size' limit is cut one byte past the limit, so that the parser reports it, and the
+/// rest of it is skipped. Returns the text along with where the next document starts (given the
+/// document starts at 'start'), or None at the end of input.
+pub fn read_delimited(source: &mut dyn BufRead, delimiter: u8, options: &ParserOptions, start: &Snapshot) -> std::io::Result<Option<(Vec<u8>, Snapshot)>>
+{
+    let mut text = Vec::<u8>::new();
+    let max_bytes = options.get_max_input_size().map_or(u64::MAX, |limit| (limit as u64).saturating_add(1));
+    let size = source.take(max_bytes).read_until(delimiter, &mut text)?;
+
+    if size == 0
+    {
+        return Ok(None);
+    }
+
+    let mut next_start = advance(start, &text);
+
+    if !text.ends_with(&[ delimiter ]) && size as u64 == max_bytes
+    {
+        // Skip the rest of the document, keeping track of the lines in it.
+        loop
+        {
+            let buffer = source.fill_buf()?;
+
+            if buffer.is_empty()
+            {
+                break;
+            }
+
+            let (used, found) = match buffer.iter().position(|byte| *byte == delimiter)
+            {
+                Some(index) => (index + 1, true),
+                None => (buffer.len(), false),
+            };
+
+            next_start = advance(&next_start, &buffer[..used]);
+            source.consume(used);
+
+            if found
+            {
+                break;
+            }
+        }
+    }
+
+    return Ok(Some((text, next_start)));
+}
+
+/// Moves a position past some bytes of the input.
+pub fn advance(position: &Snapshot, bytes: &[u8]) -> Snapshot
+{
+    let (mut line, mut column) = (position.get_line(), position.get_column());
+
+    for byte in bytes
+    {
+        if *byte == b'\n'
+        {
+            line += 1;
+            column = 1;
+        }
+
+        // Columns count characters, so skip UTF-8 continuation bytes.
+        else if byte & 0xC0 != 0x80
+        {
+            column += 1;
+        }
+    }
+
+    return Snapshot::new(position.get_start_pos() + bytes.len(), line, column);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::io::document_reader::read_delimited;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::snapshot::Snapshot;
+
+    use std::io::{BufReader, Cursor};
+
+    /// Gets the text of each document along with where the next one starts (offset, line and column).
+    fn read_all(input: &[u8], delimiter: u8, options: &ParserOptions) -> Vec<(Vec<u8>, usize, usize, usize)>
+    {
+        // A tiny buffer, so that skipping the rest of a document takes several reads.
+        let mut source = BufReader::with_capacity(2, Cursor::new(input.to_vec()));
+        let mut start = Snapshot::new(0, 1, 1);
+        let mut documents = Vec::new();
+
+        while let Some((text, next_start)) = read_delimited(&mut source, delimiter, options, &start).unwrap()
+        {
+            documents.push((text, next_start.get_start_pos(), next_start.get_line(), next_start.get_column()));
+            start = next_start;
+        }
+
+        return documents;
+    }
+
+    #[test]
+    fn read_delimited_documents()
+    {
+        let options = ParserOptions::builder().max_input_size(Some(5)).build();
+        assert_eq!(read_all(b"[1]\n[1, 2,\n 3]\n\xC3\xA9\n", b'\n', &options), vec![
+            (b"[1]\n".to_vec(), 4, 2, 1),
+            (b"[1, 2,".to_vec(), 11, 3, 1),
+            (b" 3]\n".to_vec(), 15, 4, 1),
+            (b"\xC3\xA9\n".to_vec(), 18, 5, 1),
+        ]);
+
+        // Without a limit, and with the delimiter missing at the end of input.
+        assert_eq!(read_all(b"\x1E1\n\x1E\xC3\xA9", 0x1E, &ParserOptions::default()), vec![
+            (b"\x1E".to_vec(), 1, 1, 2),
+            (b"1\n\x1E".to_vec(), 4, 2, 2),
+            (b"\xC3\xA9".to_vec(), 6, 2, 3),
+        ]);
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+/// How the input is split into JSON documents.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumInputFormat
+{
+    /// A single document (or concatenated documents with '--stream').
+    #[default]
+    JSON,
+    /// Newline-delimited JSON (JSON Lines): one document per line. See 'NdjsonReader'.
+    NDJSON,
+    /// JSON text sequences (RFC 7464): each document after an RS (0x1E). See 'JsonSeqReader'.
+    JSON_SEQ,
+}
+
+pub fn get_input_format_from_string(string: &str) -> Result<EnumInputFormat, String>
+{
+    static LOOKUP_TABLE: std::sync::LazyLock<HashMap::<String, EnumInputFormat>> = std::sync::LazyLock::new(||
+    {
+        let mut table = HashMap::<String, EnumInputFormat>::new();
+        table.insert(String::from("JSON"), EnumInputFormat::JSON);
+        table.insert(String::from("JSONL"), EnumInputFormat::NDJSON);
+        table.insert(String::from("NDJSON"), EnumInputFormat::NDJSON);
+        table.insert(String::from("JSON-SEQ"), EnumInputFormat::JSON_SEQ);
+
+        return table;
+    });
+
+    let string_upper = string.to_uppercase();
+    let opt_result = LOOKUP_TABLE.get(&string_upper);
+
+    if let Some(result) = opt_result
+    {
+        return Ok(*result);
+    }
+
+    return Err(String::from("String is not a EnumInputFormat"));
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::io::input_format::*;
+
+    #[test]
+    fn input_format_from_string()
+    {
+        assert_eq!(get_input_format_from_string(&String::from("json")), Ok(EnumInputFormat::JSON));
+        assert_eq!(get_input_format_from_string(&String::from("NDJSON")), Ok(EnumInputFormat::NDJSON));
+        assert_eq!(get_input_format_from_string(&String::from("jsonl")), Ok(EnumInputFormat::NDJSON));
+        assert_eq!(get_input_format_from_string(&String::from("json-seq")), Ok(EnumInputFormat::JSON_SEQ));
+        assert!(get_input_format_from_string(&String::from("yaml")).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::io::document_reader::{advance, read_delimited, DocumentReader};
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parser::Parser;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+use crate::rnodes::rnode::RNode;
+
+use std::io::{BufRead, BufReader, Read};
+use std::rc::Rc;
+
+/// The ASCII record separator that starts each record of a JSON text sequence.
+pub const RECORD_SEPARATOR: char = '\u{1E}';
+
+/// Reads JSON text sequences (RFC 7464, 'application/json-seq'), yielding the document in each
+/// record as it is read. A record starts with an RS (0x1E) and normally ends with a line feed;
+/// empty records (ex. consecutive RS) are ignored. The parser limits apply to each record on its own.
+///
+/// A record that fails to parse (ex. one cut short by a producer that crashed) yields its error and
+/// reading resynchronises on the next RS, unless bad records are skipped (see 'set_skip_bad_documents').
+/// As the RFC asks, a record holding a number, bool or null that isn't followed by whitespace is
+/// reported as truncated, since it may have lost some of its digits (ex. '123' cut from '12345').
+pub struct JsonSeqReader
+{
+    source: Box<dyn BufRead>,
+    options: ParserOptions,
+    skip_bad_documents: bool,
+    /// Where the next record starts (just past its RS), or None before the first RS has been read.
+    next_record: Option<Snapshot>,
+    /// The text of the last record read (and its byte offset), for rendering diagnostics.
+    last_record: (String, usize),
+    warnings: Vec<ParseError>,
+    done: bool,
+    pub file_path: Option<String>,
+}
+
+impl JsonSeqReader
+{
+    pub fn from_file(path: &String, options: &ParserOptions) -> Self
+    {
+        let file = std::fs::File::open(path).expect("Failed to read input file");
+        let mut result = Self::from_reader(file, options);
+        result.file_path = Some(path.clone());
+        return result;
+    }
+
+    pub fn from_literal(input: &str, options: &ParserOptions) -> Self
+    {
+        return Self::from_reader(std::io::Cursor::new(input.as_bytes().to_vec()), options);
+    }
+
+    pub fn from_stdin(options: &ParserOptions) -> Self
+    {
+        return Self::from_reader(std::io::stdin(), options);
+    }
+
+    pub fn from_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
+    {
+        Self
+        {
+            source: Box::new(BufReader::new(source)), options: options.clone(), skip_bad_documents: false, next_record: None,
+            last_record: (String::new(), 0), warnings: Vec::new(), done: false, file_path: None,
+        }
+    }
+
+    /// Checks whether a record that parsed may still have been cut short, ie. it ends with the
+    /// last character of a number, bool or null rather than with whitespace.
+    fn is_truncated(record: &[u8]) -> bool
+    {
+        return record.last().is_some_and(|byte| !byte.is_ascii_whitespace() && !b"]}\"'/".contains(byte));
+    }
+
+    fn fail(&mut self, error: ParseError) -> Option<ParseError>
+    {
+        if self.skip_bad_documents
+        {
+            self.warnings.push(error);
+            return None;
+        }
+
+        return Some(error);
+    }
+}
+
+impl DocumentReader for JsonSeqReader
+{
+    fn set_skip_bad_documents(&mut self, skip_bad_documents: bool)
+    {
+        self.skip_bad_documents = skip_bad_documents;
+    }
+
+    fn take_warnings(&mut self) -> Vec<ParseError>
+    {
+        return std::mem::take(&mut self.warnings);
+    }
+
+    fn get_input(&self) -> &String
+    {
+        return &self.last_record.0;
+    }
+
+    fn get_input_start(&self) -> usize
+    {
+        return self.last_record.1;
+    }
+
+    fn get_file_path(&self) -> Option<&String>
+    {
+        return self.file_path.as_ref();
+    }
+}
+
+impl Iterator for JsonSeqReader
+{
+    type Item = Result<Rc<dyn RNode>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while !self.done
+        {
+            let before_first_record = self.next_record.is_none();
+            let record_start = self.next_record.clone().unwrap_or(Snapshot::new(0, 1, 1));
+            let (mut record, next_start) = match read_delimited(&mut self.source, RECORD_SEPARATOR as u8, &self.options, &record_start)
+            {
+                Ok(Some(record)) => record,
+                Ok(None) => { break; },
+                Err(error) =>
+                {
+                    self.done = true;
+                    return Some(Err(ParseError::new(EnumParseErrorKind::ReadFailed(error.to_string()), &record_start, record_start.get_start_pos())));
+                },
+            };
+
+            self.next_record = Some(next_start);
+
+            if record.ends_with(&[ RECORD_SEPARATOR as u8 ])
+            {
+                record.pop();
+            }
+
+            // Empty records are ignored (ex. consecutive RS).
+            let Some(content_start) = record.iter().position(|byte| !byte.is_ascii_whitespace()) else { continue; };
+
+            // Anything but whitespace before the first RS isn't part of any record.
+            if before_first_record
+            {
+                let error = ParseError::new(EnumParseErrorKind::MissingRecordSeparator, &advance(&record_start, &record[..content_start]), record.len());
+                self.last_record = (String::from_utf8_lossy(&record).into_owned(), 0);
+
+                match self.fail(error)
+                {
+                    Some(error) => { return Some(Err(error)); },
+                    None => { continue; },
+                }
+            }
+
+            let truncated = Self::is_truncated(&record);
+            let mut parser = Parser::new_bytes(record, &self.options);
+            let result = parser.parse();
+            self.warnings.extend(parser.take_warnings().into_iter().map(|warning| warning.relocate(&record_start)));
+            self.last_record = (parser.get_input().clone(), record_start.get_start_pos());
+
+            let error = match result
+            {
+                Ok(_) if truncated =>
+                {
+                    let input = parser.get_input();
+                    let content = advance(&Snapshot::new(0, 1, 1), &input.as_bytes()[..content_start]);
+                    ParseError::new(EnumParseErrorKind::TruncatedRecord, &content, input.len()).relocate(&record_start)
+                },
+                Ok(node) => { return Some(Ok(node)); },
+                Err(error) => error.relocate(&record_start),
+            };
+
+            if let Some(error) = self.fail(error)
+            {
+                return Some(Err(error));
+            }
+        }
+
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::io::document_reader::DocumentReader;
+    use crate::io::json_seq_reader::JsonSeqReader;
+    use crate::parser::parse_error::EnumParseErrorKind;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::utils::diagnostic::render_diagnostic;
+
+    fn node_types(reader: JsonSeqReader) -> Vec<String>
+    {
+        return reader.map(|result| result.map_or_else(|error| error.get_message(), |node| node.get_node_type().to_string())).collect();
+    }
+
+    #[test]
+    fn json_seq_reads_a_document_per_record()
+    {
+        let input = String::from("\u{1E}{\"a\": 1}\n\u{1E}\u{1E}[1,\n 2]\n\u{1E}\"text\"\n\u{1E} true \n\u{1E}\n");
+        assert_eq!(node_types(JsonSeqReader::from_literal(&input, &ParserOptions::default())), vec![ "OBJECT", "ARRAY", "STRING", "BOOL" ]);
+    }
+
+    #[test]
+    fn json_seq_resynchronises_after_truncated_records()
+    {
+        let input = String::from("\u{1E}{\"a\": [1, 2\u{1E}{\"b\": 2}\n\u{1E}123\u{1E}456\n\u{1E}null");
+        let mut reader = JsonSeqReader::from_literal(&input, &ParserOptions::default());
+
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+        assert_eq!((error.get_line(), error.get_column(), error.get_start()), (1, 13, 12));
+
+        assert!(reader.next().unwrap().is_ok());
+
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TruncatedRecord);
+        assert_eq!((error.get_line(), error.get_column(), error.get_start(), error.get_end()), (2, 2, 23, 26));
+
+        let diagnostic = render_diagnostic(reader.get_input(), reader.get_input_start(), None, &error);
+        assert!(diagnostic.contains("2 | 123\n  | ^^^\n"), "{}", diagnostic);
+
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.next().unwrap().err().unwrap().get_kind(), &EnumParseErrorKind::TruncatedRecord);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn json_seq_errors_have_line_numbers()
+    {
+        let input = String::from("  \n\u{1E}{\n  \"a\" 1}\n\u{1E}[]\n");
+        let mut reader = JsonSeqReader::from_literal(&input, &ParserOptions::default());
+
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedColon(String::from("number 1")));
+        assert_eq!((error.get_line(), error.get_column()), (3, 7));
+
+        let diagnostic = render_diagnostic(reader.get_input(), reader.get_input_start(), None, &error);
+        assert!(diagnostic.contains("3 |   \"a\" 1}\n  |       ^\n"), "{}", diagnostic);
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+
+        // Text before the first RS isn't a record.
+        let mut reader = JsonSeqReader::from_literal(&String::from(" {}\n\u{1E}1\n"), &ParserOptions::default());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::MissingRecordSeparator);
+        assert_eq!((error.get_line(), error.get_column(), error.get_start(), error.get_end()), (1, 2, 1, 4));
+        assert!(reader.next().unwrap().is_ok());
+    }
+
+    #[test]
+    fn json_seq_skips_bad_records()
+    {
+        let input = String::from("garbage\u{1E}[1, 2]\n\u{1E}[1,\u{1E}{\"a\": 1}\n\u{1E}7");
+        let mut reader = JsonSeqReader::from_literal(&input, &ParserOptions::default());
+        reader.set_skip_bad_documents(true);
+
+        assert_eq!(reader.by_ref().count(), 2);
+
+        let warnings: Vec<EnumParseErrorKind> = reader.take_warnings().iter().map(|warning| warning.get_kind().clone()).collect();
+        assert_eq!(warnings, vec![ EnumParseErrorKind::MissingRecordSeparator, EnumParseErrorKind::UnexpectedEOF, EnumParseErrorKind::TruncatedRecord ]);
+    }
+
+    #[test]
+    fn json_seq_limits_apply_to_each_record()
+    {
+        let options = ParserOptions::builder().max_input_size(Some(8)).build();
+        let input = String::from("\u{1E}[1, 2]\n\u{1E}[1,\n2,\n3,\n4]\n\u{1E}[3] @\n");
+        let mut reader = JsonSeqReader::from_literal(&input, &options);
+
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+        assert_eq!(error.get_line(), 2);
+
+        // The lines of the skipped part of the record are still counted.
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TrailingData(String::from("'@'")));
+        assert_eq!((error.get_line(), error.get_column()), (6, 6));
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod document_reader;
+pub mod input_format;
+pub mod json_seq_reader;
+pub mod ndjson_reader;
+pub mod non_finite_policy;
+pub mod output_format;
 pub mod reader;
 pub mod writer;
-
@@ -0,0 +1,230 @@
+use crate::io::document_reader::{read_delimited, DocumentReader};
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parser::Parser;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+use crate::rnodes::rnode::RNode;
+
+use std::io::{BufRead, BufReader, Read};
+use std::rc::Rc;
+
+/// Reads newline-delimited JSON (JSON Lines), yielding the document on each line as it is read.
+/// Blank lines are ignored and a line may end with "\r\n". The parser limits apply to each line on
+/// its own, so 'max_input_size' caps the length of a line.
+///
+/// Errors are positioned in the whole input (ex. at line 3 rather than line 1 of the third document).
+/// A bad line yields its error and reading carries on with the next line, unless bad lines are
+/// skipped (see 'set_skip_bad_documents').
+///
+/// ```text
+/// for result in NdjsonReader::from_file(&path, &options)
+/// {
+///     let node = result?;
+///     ...
+/// }
+/// ```
+pub struct NdjsonReader
+{
+    source: Box<dyn BufRead>,
+    options: ParserOptions,
+    skip_bad_documents: bool,
+    /// Where the next line starts.
+    next_line: Snapshot,
+    /// The text of the last line read (and its byte offset), for rendering diagnostics.
+    last_line: (String, usize),
+    warnings: Vec<ParseError>,
+    done: bool,
+    pub file_path: Option<String>,
+}
+
+impl NdjsonReader
+{
+    pub fn from_file(path: &String, options: &ParserOptions) -> Self
+    {
+        let file = std::fs::File::open(path).expect("Failed to read input file");
+        let mut result = Self::from_reader(file, options);
+        result.file_path = Some(path.clone());
+        return result;
+    }
+
+    pub fn from_literal(input: &str, options: &ParserOptions) -> Self
+    {
+        return Self::from_reader(std::io::Cursor::new(input.as_bytes().to_vec()), options);
+    }
+
+    pub fn from_stdin(options: &ParserOptions) -> Self
+    {
+        return Self::from_reader(std::io::stdin(), options);
+    }
+
+    pub fn from_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
+    {
+        Self
+        {
+            source: Box::new(BufReader::new(source)), options: options.clone(), skip_bad_documents: false, next_line: Snapshot::new(0, 1, 1),
+            last_line: (String::new(), 0), warnings: Vec::new(), done: false, file_path: None,
+        }
+    }
+}
+
+impl DocumentReader for NdjsonReader
+{
+    fn set_skip_bad_documents(&mut self, skip_bad_documents: bool)
+    {
+        self.skip_bad_documents = skip_bad_documents;
+    }
+
+    fn take_warnings(&mut self) -> Vec<ParseError>
+    {
+        return std::mem::take(&mut self.warnings);
+    }
+
+    fn get_input(&self) -> &String
+    {
+        return &self.last_line.0;
+    }
+
+    fn get_input_start(&self) -> usize
+    {
+        return self.last_line.1;
+    }
+
+    fn get_file_path(&self) -> Option<&String>
+    {
+        return self.file_path.as_ref();
+    }
+}
+
+impl Iterator for NdjsonReader
+{
+    type Item = Result<Rc<dyn RNode>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while !self.done
+        {
+            let line_start = self.next_line.clone();
+            let (mut line, next_line) = match read_delimited(&mut self.source, b'\n', &self.options, &line_start)
+            {
+                Ok(Some(line)) => line,
+                Ok(None) => { break; },
+                Err(error) =>
+                {
+                    self.done = true;
+                    return Some(Err(ParseError::new(EnumParseErrorKind::ReadFailed(error.to_string()), &line_start, line_start.get_start_pos())));
+                },
+            };
+
+            self.next_line = next_line;
+
+            if line.ends_with(b"\n")
+            {
+                line.pop();
+
+                if line.ends_with(b"\r")
+                {
+                    line.pop();
+                }
+            }
+
+            if line.iter().all(|byte| byte.is_ascii_whitespace())
+            {
+                continue;
+            }
+
+            let mut parser = Parser::new_bytes(line, &self.options);
+            let result = parser.parse();
+            self.warnings.extend(parser.take_warnings().into_iter().map(|warning| warning.relocate(&line_start)));
+            self.last_line = (parser.get_input().clone(), line_start.get_start_pos());
+
+            match result
+            {
+                Ok(node) => { return Some(Ok(node)); },
+                Err(error) =>
+                {
+                    let error = error.relocate(&line_start);
+
+                    if self.skip_bad_documents
+                    {
+                        self.warnings.push(error);
+                        continue;
+                    }
+
+                    return Some(Err(error));
+                },
+            }
+        }
+
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::io::document_reader::DocumentReader;
+    use crate::io::ndjson_reader::NdjsonReader;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::parse_error::EnumParseErrorKind;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::utils::diagnostic::render_diagnostic;
+
+    const INPUT: &str = "{\"id\": 1}\r\n\n  [1, 2]\n{\"id\" 3}\n\"text\"\n{\"id\": 4,}\n5";
+
+    #[test]
+    fn ndjson_reads_a_document_per_line()
+    {
+        let reader = NdjsonReader::from_literal(&String::from("{\"id\": 1}\r\n\n  [1, 2]  \ntrue\n\n"), &ParserOptions::default());
+        let types: Vec<String> = reader.map(|result| result.unwrap().get_node_type().to_string()).collect();
+        assert_eq!(types, vec![ "OBJECT", "ARRAY", "BOOL" ]);
+    }
+
+    #[test]
+    fn ndjson_errors_have_line_numbers()
+    {
+        let mut reader = NdjsonReader::from_literal(&String::from(INPUT), &ParserOptions::default());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedColon(String::from("number 3")));
+        assert_eq!((error.get_line(), error.get_column(), error.get_start()), (4, 7, 27));
+
+        let diagnostic = render_diagnostic(reader.get_input(), reader.get_input_start(), None, &error);
+        assert!(diagnostic.contains("4 | {\"id\" 3}\n  |       ^\n"), "{}", diagnostic);
+
+        // Reading carries on after a bad line.
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.next().unwrap().err().unwrap().get_line(), 6);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn ndjson_skips_bad_lines()
+    {
+        let options = ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::FIRST_WINS).build();
+        let mut reader = NdjsonReader::from_literal(&INPUT.replace("[1, 2]", "{\"a\": 1, \"a\": 2}"), &options);
+        reader.set_skip_bad_documents(true);
+
+        assert_eq!(reader.by_ref().count(), 4);
+
+        let warnings: Vec<(usize, String)> = reader.take_warnings().iter().map(|warning| (warning.get_line(), warning.get_message())).collect();
+        assert_eq!(warnings.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![ 3, 4, 6 ]);
+        assert!(warnings[0].1.contains("first defined at line 3, column 4"), "{}", warnings[0].1);
+    }
+
+    #[test]
+    fn ndjson_limits_apply_to_each_line()
+    {
+        let options = ParserOptions::builder().max_input_size(Some(8)).build();
+        let mut reader = NdjsonReader::from_literal(&String::from("[1, 2]\n[1, 2, 3, 4]\n[3]\n"), &options);
+
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+        assert_eq!(error.get_line(), 2);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+    }
+}
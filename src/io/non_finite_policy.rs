@@ -0,0 +1,28 @@
+/// How RusonWriter writes values that JSON can't represent (NaN and the infinities).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumNonFinitePolicy
+{
+    /// Fail the write with an error.
+    #[default]
+    ERROR,
+    /// Write a 'null' in place of the value.
+    NULL,
+    /// Write the value as a string, ie. "NaN", "Infinity" or "-Infinity".
+    STRING,
+}
+
+/// Gets the name of a non-finite value, as used by EnumNonFinitePolicy::STRING.
+pub fn get_non_finite_name(value: f64) -> &'static str
+{
+    if value.is_nan()
+    {
+        return "NaN";
+    }
+
+    else if value.is_sign_negative()
+    {
+        return "-Infinity";
+    }
+
+    return "Infinity";
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// How RusonWriter lays out the documents it writes.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumOutputFormat
+{
+    /// Indented documents, each followed by a blank line.
+    #[default]
+    JSON,
+    /// Newline-delimited JSON (JSON Lines): each document compact on a line of its own.
+    NDJSON,
+    /// JSON text sequences (RFC 7464): each document compact, after an RS (0x1E) and followed by a line feed.
+    JSON_SEQ,
+}
+
+pub fn get_output_format_from_string(string: &str) -> Result<EnumOutputFormat, String>
+{
+    static LOOKUP_TABLE: std::sync::LazyLock<HashMap::<String, EnumOutputFormat>> = std::sync::LazyLock::new(||
+    {
+        let mut table = HashMap::<String, EnumOutputFormat>::new();
+        table.insert(String::from("JSON"), EnumOutputFormat::JSON);
+        table.insert(String::from("JSONL"), EnumOutputFormat::NDJSON);
+        table.insert(String::from("NDJSON"), EnumOutputFormat::NDJSON);
+        table.insert(String::from("JSON-SEQ"), EnumOutputFormat::JSON_SEQ);
+
+        return table;
+    });
+
+    let string_upper = string.to_uppercase();
+    let opt_result = LOOKUP_TABLE.get(&string_upper);
+
+    if let Some(result) = opt_result
+    {
+        return Ok(*result);
+    }
+
+    return Err(String::from("String is not a EnumOutputFormat"));
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::io::output_format::*;
+
+    #[test]
+    fn output_format_from_string()
+    {
+        assert_eq!(get_output_format_from_string(&String::from("json")), Ok(EnumOutputFormat::JSON));
+        assert_eq!(get_output_format_from_string(&String::from("ndjson")), Ok(EnumOutputFormat::NDJSON));
+        assert_eq!(get_output_format_from_string(&String::from("JSON-SEQ")), Ok(EnumOutputFormat::JSON_SEQ));
+        assert!(get_output_format_from_string(&String::from("xml")).is_err());
+    }
+}
@@ -1,40 +1,79 @@
+use crate::parser::parse_error::ParseError;
 use crate::parser::parser::Parser;
+use crate::parser::parser_options::ParserOptions;
 use crate::rnodes::rnode::RNode;
 
-use std::cell::{RefCell, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::rc::Rc;
 
 pub struct RusonReader
 {
     parser: RefCell<Parser>,
-    #[allow(dead_code)]
     pub file_path: Option<String>,
 }
 
 impl RusonReader
 {
-    pub fn from_file(path: &String, stringify: bool) -> Self
+    /// Parses a file as it is read, rather than reading all of it up front.
+    pub fn from_file(path: &String, options: &ParserOptions) -> Self
     {
-        let input = std::fs::read_to_string(&path).expect("Failed to read input file");
-        Self { parser: RefCell::new(Parser::new_move(input, stringify)), file_path: Some(path.clone()) }
+        let file = std::fs::File::open(path).expect("Failed to read input file");
+        Self { parser: RefCell::new(Parser::new_reader(file, options)), file_path: Some(path.clone()) }
     }
 
-    #[allow(dead_code)]
-    pub fn from_literal(input: &String, stringify: bool) -> Self
+    pub fn from_literal(input: &str, options: &ParserOptions) -> Self
     {
-        Self { parser: RefCell::new(Parser::new_copy(&input, stringify)), file_path: None }
+        Self { parser: RefCell::new(Parser::new_copy(input, options)), file_path: None }
     }
 
-    pub fn from_stdin(stringify: bool) -> Self
+    /// Parses stdin as it is read (ex. from a pipe), rather than reading all of it up front.
+    pub fn from_stdin(options: &ParserOptions) -> Self
     {
-        let input = std::io::read_to_string(std::io::stdin()).expect("Failed to read from stdin");
-        Self { parser: RefCell::new(Parser::new_move(input, stringify)), file_path: None }
+        Self { parser: RefCell::new(Parser::new_reader(std::io::stdin(), options)), file_path: None }
     }
 
-    pub fn parse(&self) -> Result<Rc<dyn RNode>, String>
+    /// Takes the warnings (ex. allowed duplicate keys) from the last parse.
+    pub fn take_warnings(&self) -> Vec<ParseError>
+    {
+        return self.parser.borrow_mut().take_warnings();
+    }
+
+    /// Gets the text being parsed (ex. for rendering diagnostics). For a file or stdin this is only
+    /// the part still being held, which starts at the byte offset 'get_input_start'.
+    pub fn get_input(&self) -> Ref<'_, String>
+    {
+        return Ref::map(self.parser.borrow(), |parser| parser.get_input());
+    }
+
+    /// Gets the byte offset (into the whole input) of the start of 'get_input'.
+    pub fn get_input_start(&self) -> usize
+    {
+        return self.parser.borrow().get_input_start();
+    }
+
+    /// Gets the options the input is parsed with.
+    pub fn get_options(&self) -> ParserOptions
+    {
+        return self.parser.borrow().get_options().clone();
+    }
+
+    pub fn parse(&self) -> Result<Rc<dyn RNode>, ParseError>
     {
         let mut parser: RefMut<Parser> = self.parser.borrow_mut();
         parser.parse()
     }
-}
 
+    /// Parses the input as a stream of concatenated values. See 'Parser::parse_stream'.
+    pub fn parse_stream(&self) -> Result<Vec<Rc<dyn RNode>>, ParseError>
+    {
+        let mut parser: RefMut<Parser> = self.parser.borrow_mut();
+        parser.parse_stream()
+    }
+
+    /// Parses while recovering from errors. See 'Parser::parse_recovering'.
+    pub fn parse_recovering(&self) -> (Rc<dyn RNode>, Vec<ParseError>)
+    {
+        let mut parser: RefMut<Parser> = self.parser.borrow_mut();
+        parser.parse_recovering()
+    }
+}
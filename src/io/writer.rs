@@ -1,11 +1,18 @@
-use crate::log::logger::{ILogger, get_std_logger};
-use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::io::non_finite_policy::{EnumNonFinitePolicy, get_non_finite_name};
+use crate::io::output_format::EnumOutputFormat;
+use crate::io::json_seq_reader::RECORD_SEPARATOR;
+use crate::rnodes::rnode::RNode;
 use crate::rnodes::rnode_array::RNodeArray;
 use crate::rnodes::rnode_bool::RNodeBool;
+#[cfg(feature = "arbitrary_precision")]
+use crate::rnodes::rnode_decimal::RNodeDecimal;
 use crate::rnodes::rnode_double::RNodeDouble;
+use crate::rnodes::rnode_error::RNodeError;
 use crate::rnodes::rnode_null::RNodeNull;
+use crate::rnodes::rnode_number::{EnumNumber, RNodeNumber};
 use crate::rnodes::rnode_object::RNodeObject;
 use crate::rnodes::rnode_string::RNodeString;
+use crate::utils::float_utils::{format_f64, parse_f64};
 use crate::utils::string_utils::StringBuilder;
 use crate::visitor::visitor::Visitor;
 
@@ -21,26 +28,42 @@ pub struct RusonWriter
     stdout: Option<BufWriter<Stdout>>,
     indent: u32,
     cur_indent: Cell<u32>,
+    non_finite_policy: EnumNonFinitePolicy,
+    output_format: EnumOutputFormat,
+    sort_keys: bool,
+    /// The first error hit while visiting the nodes being written.
+    error: RefCell<Option<String>>,
     pub builder: RefCell<StringBuilder>,
 }
 
 impl RusonWriter
 {
-    #[allow(dead_code)]
     pub fn new_file(path: &String, indent: u32) -> std::io::Result<Self>
     {
-        let file_opt = BufWriter::new(OpenOptions::new().write(true).create(true).open(&path)?);
+        return Self::new_file_with(path, indent, OpenOptions::new().write(true).create(true).truncate(true));
+    }
+
+    /// Creates a writer that adds to the end of the file (creating it if needed) instead of replacing
+    /// it, ex. to add records to an NDJSON log.
+    pub fn new_file_append(path: &String, indent: u32) -> std::io::Result<Self>
+    {
+        return Self::new_file_with(path, indent, OpenOptions::new().append(true).create(true));
+    }
+
+    fn new_file_with(path: &String, indent: u32, open_options: &OpenOptions) -> std::io::Result<Self>
+    {
+        let file_opt = BufWriter::new(open_options.open(path)?);
         let writer = RusonWriter
            {
                writer: Some(file_opt), stdout: None,
                indent, cur_indent: Cell::new(0),
+               non_finite_policy: EnumNonFinitePolicy::default(), output_format: EnumOutputFormat::default(), sort_keys: false, error: RefCell::new(None),
                builder: RefCell::new(StringBuilder::new(4096)),
            };
 
         Ok(writer)
     }
 
-    #[allow(dead_code)]
     pub fn new_stdout(indent: u32) -> std::io::Result<Self>
     {
         let handle = std::io::stdout();
@@ -49,12 +72,37 @@ impl RusonWriter
            {
                writer: None, stdout: Some(file_opt),
                indent, cur_indent: Cell::new(0),
+               non_finite_policy: EnumNonFinitePolicy::default(), output_format: EnumOutputFormat::default(), sort_keys: false, error: RefCell::new(None),
                builder: RefCell::new(StringBuilder::new(4096)),
            };
 
         Ok(writer)
     }
 
+    /// Sets how NaN and the infinities are written (defaults to failing the write).
+    pub fn set_non_finite_policy(&mut self, policy: EnumNonFinitePolicy)
+    {
+        self.non_finite_policy = policy;
+    }
+
+    /// Sets how the documents are laid out (defaults to EnumOutputFormat::JSON).
+    pub fn set_output_format(&mut self, output_format: EnumOutputFormat)
+    {
+        self.output_format = output_format;
+    }
+
+    /// Whether documents are written without any whitespace (ex. one per line for NDJSON and json-seq).
+    fn is_compact(&self) -> bool
+    {
+        return self.output_format != EnumOutputFormat::JSON;
+    }
+
+    /// Writes object keys sorted instead of in their original order (off by default).
+    pub fn set_sort_keys(&mut self, sort_keys: bool)
+    {
+        self.sort_keys = sort_keys;
+    }
+
     fn decrement_indent(&self)
     {
         let cur_indent = self.cur_indent.get();
@@ -82,7 +130,6 @@ impl RusonWriter
         }
     }
 
-    #[allow(dead_code)]
     pub fn flush(&mut self) -> std::io::Result<bool>
     {
         let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
@@ -93,8 +140,8 @@ impl RusonWriter
             return Ok(false);
         }
 
-        // Make sure we always end with a second new line
-        if let Some(ch) = builder.get(builder.len() - 1)
+        // Make sure we always end with a second new line (each compact document already ends its line).
+        if let Some(ch) = builder.get(builder.len() - 1).filter(|_| !self.is_compact())
         {
             const NEWLINE: char = '\n';
 
@@ -141,63 +188,212 @@ impl RusonWriter
         return Ok(false);
     }
 
-    #[allow(dead_code)]
+    /// Writes a document (any kind of value) to the buffer, to be output on the next 'flush'. Nothing
+    /// is kept of a document that fails to be written.
     pub fn write(&mut self, node: Rc<dyn RNode>) -> std::io::Result<bool>
     {
-        match node.get_node_type()
+        let start = self.builder.borrow().len();
+
+        if self.output_format == EnumOutputFormat::JSON_SEQ
         {
-            EnumNodeType::ARRAY | EnumNodeType::DOUBLE | EnumNodeType::OBJECT =>
-            {
-                node.accept(self);
-            },
-            _ => { return Ok(false); }
+            self.builder.borrow_mut().append_char(RECORD_SEPARATOR);
+        }
+
+        else if self.output_format == EnumOutputFormat::JSON && start > 0
+        {
+            // Start each further document on a line of its own.
+            self.builder.borrow_mut().append_char('\n');
+        }
+
+        self.write_value(&*node);
+
+        if let Some(msg) = self.error.borrow_mut().take()
+        {
+            self.builder.borrow_mut().truncate(start);
+            self.cur_indent.set(0);
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg));
+        }
+
+        if self.is_compact()
+        {
+            self.builder.borrow_mut().append_char('\n');
         }
 
         Ok(true)
     }
-}
 
-impl Visitor for RusonWriter
-{
-    fn visit_array(&self, node: &RNodeArray)
+    /// Writes a string (or key) between quotes, escaping what JSON doesn't allow as is: '"', '\', the
+    /// control characters and lone surrogates (given as the byte offset of their placeholder and code unit).
+    fn write_string(&self, value: &str, lone_surrogates: &[(usize, u16)], unicode_escapes: &[usize])
     {
+        let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
+        let mut lone_surrogates = lone_surrogates.iter().peekable();
+        let mut unicode_escapes = unicode_escapes.iter().peekable();
+
+        builder.append_char('"');
+
+        for (offset, ch) in value.char_indices()
         {
-            let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-            builder.append_char('[');
+            if let Some((_, code_unit)) = lone_surrogates.next_if(|(start, _)| *start == offset)
+            {
+                builder.append_string(&format!("\\u{:04X}", code_unit));
+                continue;
+            }
+
+            match ch
+            {
+                '"' => { builder.append_str("\\\""); },
+                '\\' if unicode_escapes.next_if(|start| **start == offset).is_some() =>
+                {
+                    // An escape the parser kept, so write it back as is.
+                    builder.append_char('\\');
+                },
+                '\\' => { builder.append_str("\\\\"); },
+                '\x08' => { builder.append_str("\\b"); },
+                '\x0C' => { builder.append_str("\\f"); },
+                '\n' => { builder.append_str("\\n"); },
+                '\r' => { builder.append_str("\\r"); },
+                '\t' => { builder.append_str("\\t"); },
+                _ if (ch as u32) < 0x20 => { builder.append_string(&format!("\\u{:04X}", ch as u32)); },
+                _ => { builder.append_char(ch); },
+            }
         }
 
-        let len = node.len();
+        builder.append_char('"');
+    }
 
-        for i in 0..len
-        {
-            let opt_subnode: Option<Rc<dyn RNode>> = node.get(i);
+    /// Writes a value, keeping the arrays/objects being written on an explicit stack rather than
+    /// visiting them recursively, so deeply nested trees can't overflow the stack. Compact output
+    /// has no whitespace, otherwise arrays stay on one line and each member goes on its own line.
+    fn write_value(&self, root: &dyn RNode)
+    {
+        let mut stack = Vec::<EnumWriteFrame>::new();
+        let mut next: Option<&dyn RNode> = Some(root);
 
-            match opt_subnode
+        loop
+        {
+            if let Some(node) = next.take()
             {
-                Some(subnode) =>
+                if let Some(array) = node.downcast_ref::<RNodeArray>()
                 {
-                    subnode.clone().accept(self);
+                    self.builder.borrow_mut().append_char('[');
+                    stack.push(EnumWriteFrame::ARRAY(array.iter(), 0));
+                }
+
+                else if let Some(object) = node.downcast_ref::<RNodeObject>()
+                {
+                    let objmap = object.get_map();
+                    let entries: Vec<(&String, &Rc<dyn RNode>)> = if self.sort_keys { objmap.sorted() } else { objmap.iter().collect() };
 
-                    if i + 1 < len
+                    self.builder.borrow_mut().append_char('{');
+                    self.increment_indent();
+                    stack.push(EnumWriteFrame::OBJECT(entries.into_iter(), 0));
+                }
+
+                else
+                {
+                    node.accept(self);
+                }
+            }
+
+            // Move on to the next value of the innermost array/object, or close it once it has none left.
+            match stack.last_mut()
+            {
+                None => { return; },
+                Some(EnumWriteFrame::ARRAY(elements, count)) => match elements.next()
+                {
+                    Some(element) =>
                     {
-                        let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-                        builder.append_str(", ");
-                    }
+                        if *count > 0
+                        {
+                            self.builder.borrow_mut().append_str(if self.is_compact() { "," } else { ", " });
+                        }
+
+                        *count += 1;
+                        next = Some(&**element);
+                    },
+                    None =>
+                    {
+                        self.builder.borrow_mut().append_char(']');
+                        stack.pop();
+                    },
                 },
-                None =>
+                Some(EnumWriteFrame::OBJECT(entries, count)) => match entries.next()
                 {
-                    let logger_cell = get_std_logger().lock().unwrap();
-                    let mut logger = logger_cell.borrow_mut();
-                    logger.fatal(String::from("Critical error while retrieving a node from an RNodeArray"), Some(-3));
-                }
+                    Some((key, value)) =>
+                    {
+                        if *count > 0
+                        {
+                            self.builder.borrow_mut().append_char(',');
+                        }
+
+                        if !self.is_compact()
+                        {
+                            self.builder.borrow_mut().append_char('\n');
+                            self.insert_indent();
+                        }
+
+                        *count += 1;
+                        self.write_string(key, &[], &[]);
+                        self.builder.borrow_mut().append_str(if self.is_compact() { ":" } else { ": " });
+                        next = Some(&**value);
+                    },
+                    None =>
+                    {
+                        self.decrement_indent();
+
+                        if *count > 0 && !self.is_compact()
+                        {
+                            self.builder.borrow_mut().append_char('\n');
+                            self.insert_indent();
+                        }
+
+                        self.builder.borrow_mut().append_char('}');
+                        stack.pop();
+                    },
+                },
             }
         }
+    }
 
+    /// Writes an f64 in its shortest form that reads back as the same value.
+    fn write_f64(&self, value: f64)
+    {
+        if value.is_finite()
         {
-            let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-            builder.append_char(']');
+            self.builder.borrow_mut().append_string(&format_f64(value));
+            return;
+        }
+
+        match self.non_finite_policy
+        {
+            EnumNonFinitePolicy::ERROR =>
+            {
+                let mut error = self.error.borrow_mut();
+
+                if error.is_none()
+                {
+                    *error = Some(format!("{} can't be written as JSON", get_non_finite_name(value)));
+                }
+            },
+            EnumNonFinitePolicy::NULL => { self.builder.borrow_mut().append_str("null"); },
+            EnumNonFinitePolicy::STRING =>
+            {
+                let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
+                builder.append_char('"');
+                builder.append_str(get_non_finite_name(value));
+                builder.append_char('"');
+            },
         }
     }
+}
+
+impl Visitor for RusonWriter
+{
+    fn visit_array(&self, node: &RNodeArray)
+    {
+        self.write_value(node);
+    }
 
     fn visit_bool(&self, node: &RNodeBool)
     {
@@ -214,10 +410,24 @@ impl Visitor for RusonWriter
         }
     }
 
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_decimal(&self, node: &RNodeDecimal)
+    {
+        // Emit the digits exactly as they were read.
+        let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
+        builder.append_string(node.get_lexeme());
+    }
+
     fn visit_double(&self, node: &RNodeDouble)
     {
+        self.write_f64(node.value);
+    }
+
+    fn visit_error(&self, _node: &RNodeError)
+    {
+        // Values that failed to parse are written as a 'null' so the output is still valid JSON.
         let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-        builder.append_string(&node.value.to_string());
+        builder.append_str("null");
     }
 
     fn visit_null(&self, _node: &RNodeNull)
@@ -226,76 +436,64 @@ impl Visitor for RusonWriter
         builder.append_str("null");
     }
 
-    fn visit_object(&self, node: &RNodeObject)
+    fn visit_number(&self, node: &RNodeNumber)
     {
-        self.insert_indent();
-        self.increment_indent();
-
-        {
-            let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-            builder.append_str("{\n");
-        }
-
+        // Non-finite numbers can only be created in code (a number read from JSON such as '1e999'
+        // has a lexeme to write).
+        if let EnumNumber::F64(value) = node.get_value()
         {
-            let objmap = node.get_map();
-            let len: usize = objmap.len();
-            let mut count: usize = 0;
-
-            for (key, value) in objmap
+            if !value.is_finite() && parse_f64(node.get_lexeme()).is_none()
             {
-                self.insert_indent();
-
-                {
-                    let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-                    builder.append_char('"');
-                    builder.append_string(&key);
-                    builder.append_str("\": ");
-                }
-
-                value.accept(self);
-                count += 1;
-
-                {
-                    let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-
-                    if count < len
-                    {
-                        builder.append_str(",\n");
-                    }
-
-                    else
-                    {
-                        builder.append_char('\n');
-                    }
-                }
+                self.write_f64(value);
+                return;
             }
         }
 
-        {
-            let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-            builder.append_str("}\n");
-        }
+        // Write the number exactly as it was read so no precision is lost.
+        let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
+        builder.append_string(node.get_lexeme());
+    }
 
-        self.decrement_indent();
+    fn visit_object(&self, node: &RNodeObject)
+    {
+        self.write_value(node);
     }
 
     fn visit_string(&self, node: &RNodeString)
     {
-        let mut builder: RefMut<StringBuilder> = self.builder.borrow_mut();
-        builder.append_char('"');
-        builder.append_string(&node.get_value());
-        builder.append_char('"');
+        self.write_string(node.get_value(), node.get_lone_surrogates(), node.get_unicode_escapes());
     }
 }
 
+/// An array/object being written by 'write_value', with how many of its values were started.
+enum EnumWriteFrame<'a>
+{
+    ARRAY(std::slice::Iter<'a, Rc<dyn RNode>>, usize),
+    OBJECT(std::vec::IntoIter<(&'a String, &'a Rc<dyn RNode>)>, usize),
+}
+
+/// Checks if the text starts with a '\uXXXX' escape.
 #[cfg(test)]
 mod tests
 {
     use crate::rnodes::rnode::RNode;
     use crate::rnodes::rnode_array::RNodeArray;
     use crate::rnodes::rnode_bool::RNodeBool;
+    use crate::io::non_finite_policy::EnumNonFinitePolicy;
+    use crate::io::json_seq_reader::JsonSeqReader;
+    use crate::io::ndjson_reader::NdjsonReader;
+    use crate::io::output_format::EnumOutputFormat;
+    use crate::parser::number_mode::EnumNumberMode;
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
     use crate::rnodes::rnode_double::RNodeDouble;
+    use crate::rnodes::rnode_null::RNodeNull;
+    use crate::rnodes::rnode_number::RNodeNumber;
+    use crate::rnodes::rnode_object::RNodeObject;
     use crate::rnodes::rnode_string::RNodeString;
+    use crate::utils::float_utils::parse_f64;
+    use crate::utils::ordered_map::OrderedMap;
     use super::RusonWriter;
 
     use std::rc::Rc;
@@ -339,10 +537,7 @@ mod tests
         let writer_result = RusonWriter::new_stdout(DEFAULT_INDENT);
         assert!(writer_result.is_ok());
 
-        let mut vec: Vec<Rc<dyn RNode>> = Vec::new();
-        vec.push(Rc::new(RNodeDouble::new(1.0)));
-        vec.push(Rc::new(RNodeString::new_move(String::from("Hello, world!"))));
-        vec.push(Rc::new(RNodeBool::new(false)));
+        let vec: Vec<Rc<dyn RNode>> = vec![ Rc::new(RNodeDouble::new(1.0)), Rc::new(RNodeString::new_move(String::from("Hello, world!"))), Rc::new(RNodeBool::new(false)) ];
 
         let node_array = Rc::new(RNodeArray::new(vec));
         let mut writer = writer_result.unwrap();
@@ -382,5 +577,295 @@ mod tests
         let result = writer.flush();
         assert!(result.is_ok());
     }
-}
 
+    #[test]
+    fn write_exact_numbers_losslessly()
+    {
+        let input = String::from("[9007199254740993, -0.0, 1.50E-300, 18446744073709551616]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::EXACT).build());
+
+        let node = parser.parse().unwrap();
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        let result = writer.write(node);
+        assert!(result.is_ok());
+        assert_eq!(writer.builder.borrow().to_string(), input);
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn write_decimal_numbers_unchanged()
+    {
+        let input = String::from("[12345678901234567890.123456789, -0.000000000000000000000000000001, 1.000E+400]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::DECIMAL).build());
+
+        let node = parser.parse().unwrap();
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        let result = writer.write(node);
+        assert!(result.is_ok());
+        assert_eq!(writer.builder.borrow().to_string(), input);
+    }
+
+    fn write_object(input: &str, sort_keys: bool) -> String
+    {
+        let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::default());
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_sort_keys(sort_keys);
+        writer.write(parser.parse().unwrap()).unwrap();
+
+        return writer.builder.borrow().to_string();
+    }
+
+    /// Gets the keys in the order they were written.
+    fn written_keys(output: &str) -> Vec<&str>
+    {
+        return output.split('"').skip(1).step_by(2).collect();
+    }
+
+    #[test]
+    fn write_object_keys_in_original_order()
+    {
+        let output = write_object("{\"zebra\": 1, \"apple\": 2, \"mango\": {\"b\": 3, \"a\": 4}}", false);
+        assert_eq!(written_keys(&output), vec![ "zebra", "apple", "mango", "b", "a" ]);
+    }
+
+    #[test]
+    fn write_object_keys_sorted()
+    {
+        let output = write_object("{\"zebra\": 1, \"apple\": 2, \"mango\": {\"b\": 3, \"a\": 4}}", true);
+        assert_eq!(written_keys(&output), vec![ "apple", "mango", "a", "b", "zebra" ]);
+    }
+
+    fn write_doubles(values: &[f64], policy: EnumNonFinitePolicy) -> std::io::Result<String>
+    {
+        let vec: Vec<Rc<dyn RNode>> = values.iter().map(|value| Rc::new(RNodeDouble::new(*value)) as Rc<dyn RNode>).collect();
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT)?;
+        writer.set_non_finite_policy(policy);
+        writer.write(Rc::new(RNodeArray::new(vec)))?;
+
+        return Ok(writer.builder.borrow().to_string());
+    }
+
+    #[test]
+    fn write_double_shortest_round_trip()
+    {
+        let values = [ 0.1, 0.1 + 0.2, -0.0, 1e21, 1.5e-7, 123.45E10, f64::MAX, 5e-324 ];
+        let output = write_doubles(&values, EnumNonFinitePolicy::ERROR).unwrap();
+        assert_eq!(output, "[0.1, 0.30000000000000004, -0, 1e21, 1.5e-7, 1234500000000, 1.7976931348623157e308, 5e-324]");
+
+        let parsed: Vec<f64> = output[1..output.len() - 1].split(", ").map(|value| parse_f64(value).unwrap()).collect();
+        assert_eq!(parsed.iter().map(|value| value.to_bits()).collect::<Vec<u64>>(), values.iter().map(|value| value.to_bits()).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn write_non_finite_doubles()
+    {
+        let values = [ 1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY ];
+
+        let error = write_doubles(&values, EnumNonFinitePolicy::ERROR).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "NaN can't be written as JSON");
+
+        assert_eq!(write_doubles(&values, EnumNonFinitePolicy::NULL).unwrap(), "[1, null, null, null]");
+        assert_eq!(write_doubles(&values, EnumNonFinitePolicy::STRING).unwrap(), "[1, \"NaN\", \"Infinity\", \"-Infinity\"]");
+    }
+
+    #[test]
+    fn write_non_finite_numbers()
+    {
+        let vec: Vec<Rc<dyn RNode>> = vec![ Rc::new(RNodeNumber::new_f64(f64::INFINITY)), Rc::new(RNodeNumber::new_f64(0.5)) ];
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_non_finite_policy(EnumNonFinitePolicy::NULL);
+        assert!(writer.write(Rc::new(RNodeArray::new(vec))).is_ok());
+        assert_eq!(writer.builder.borrow().to_string(), "[null, 0.5]");
+
+        // Numbers read from JSON are written as is, even if they overflow an f64.
+        let input = String::from("[1e999]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::EXACT).build());
+
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        assert!(writer.write(parser.parse().unwrap()).is_ok());
+        assert_eq!(writer.builder.borrow().to_string(), input);
+    }
+
+    #[test]
+    fn write_ndjson_a_document_per_line()
+    {
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_output_format(EnumOutputFormat::NDJSON);
+
+        for input in [ "{\"a\": [1, 2, {}], \"b\": {\"c\": null}}", "\"text\"", "[]", "true" ]
+        {
+            let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::default());
+            assert!(writer.write(parser.parse().unwrap()).unwrap());
+        }
+
+        assert_eq!(writer.builder.borrow().to_string(), "{\"a\":[1,2,{}],\"b\":{\"c\":null}}\n\"text\"\n[]\ntrue\n");
+    }
+
+    #[test]
+    fn write_ndjson_appends_to_file()
+    {
+        let path = String::from("test_append.ndjson");
+        let _ = std::fs::remove_file(&path);
+
+        for value in [ 1.0, 2.0 ]
+        {
+            let mut writer = RusonWriter::new_file_append(&path, DEFAULT_INDENT).unwrap();
+            writer.set_output_format(EnumOutputFormat::NDJSON);
+            writer.write(Rc::new(RNodeArray::new(vec![ Rc::new(RNodeDouble::new(value)) ]))).unwrap();
+            assert!(writer.flush().unwrap());
+        }
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[1]\n[2]\n");
+
+        // Clean-up after our test.
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn write_json_seq_records()
+    {
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_output_format(EnumOutputFormat::JSON_SEQ);
+
+        for input in [ "{\"a\": [1, 2]}", "3" ]
+        {
+            let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::default());
+            assert!(writer.write(parser.parse().unwrap()).unwrap());
+        }
+
+        assert_eq!(writer.builder.borrow().to_string(), "\u{1E}{\"a\":[1,2]}\n\u{1E}3\n");
+    }
+
+    /// A tree with strings and keys that need escaping, including a lone surrogate.
+    fn create_escaping_tree() -> Rc<dyn RNode>
+    {
+        let object = RNodeObject::new(OrderedMap::new())
+            .add_move(String::from("quote\"back\\slash"), Rc::new(RNodeString::new_move(String::from("line\nfeed\ttab\u{1}\u{1F}\u{7F}é😀"))))
+            .add_move(String::from("new\nline"), Rc::new(RNodeString::new_with_lone_surrogates(String::from("a\u{FFFD}\u{FFFD}"), vec![ (1, 0xDFFF), (4, 0xD800) ])))
+            .add_move(String::from("u"), Rc::new(RNodeString::new_move(String::from("\\u0041 \u{FFFD}"))));
+
+        return Rc::new(RNodeArray::new(vec![ Rc::new(object), Rc::new(RNodeString::new_move(String::from("\"\r\u{8}\u{C}"))) ]));
+    }
+
+    fn write_tree(node: Rc<dyn RNode>, output_format: EnumOutputFormat) -> String
+    {
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_output_format(output_format);
+        assert!(writer.write(node).unwrap());
+
+        return writer.builder.borrow().to_string();
+    }
+
+    #[test]
+    fn write_deeply_nested()
+    {
+        // Writing (like parsing and dropping) mustn't overflow the stack however deep the tree is.
+        let depth = 100000;
+        let input = format!("{}{}{}", "[{\"a\":".repeat(depth), "1", "}]".repeat(depth));
+        let root = Parser::new_copy(&input, &ParserOptions::builder().max_depth(None).build()).parse().unwrap();
+        assert_eq!(write_tree(root, EnumOutputFormat::NDJSON), input.clone() + "\n");
+
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let root = Parser::new_copy(&input, &ParserOptions::builder().max_depth(None).build()).parse().unwrap();
+        assert_eq!(write_tree(root, EnumOutputFormat::JSON), input);
+    }
+
+    #[test]
+    fn write_indented_nested_objects()
+    {
+        let root = Parser::new_copy("{\"a\": {\"b\": [1, {\"c\": {}}]}, \"d\": 2}", &ParserOptions::default()).parse().unwrap();
+        let expected = "{\n    \"a\": {\n        \"b\": [1, {\n            \"c\": {}\n        }]\n    },\n    \"d\": 2\n}";
+        assert_eq!(write_tree(root, EnumOutputFormat::JSON), expected);
+    }
+
+    #[test]
+    fn write_escaped_strings_and_keys()
+    {
+        let output = write_tree(create_escaping_tree(), EnumOutputFormat::NDJSON);
+        let expected = "[{\"quote\\\"back\\\\slash\":\"line\\nfeed\\ttab\\u0001\\u001F\u{7F}é😀\",\"new\\nline\":\"a\\uDFFF\\uD800\",\"u\":\"\\\\u0041 \u{FFFD}\"},\"\\\"\\r\\b\\f\"]\n";
+        assert_eq!(output, expected);
+
+        // Without stringify, the '\uXXXX' escapes the parser kept in a value are written back as is (a key has them decoded).
+        let input = "{\"k\\u00e9\\\"\": \"\\u00E9\\\\\\t\"}";
+        let root = Parser::new_copy(input, &ParserOptions::default()).parse().unwrap();
+        assert_eq!(write_tree(root, EnumOutputFormat::NDJSON), "{\"ké\\\"\":\"\\u00E9\\\\\\t\"}\n");
+    }
+
+    #[test]
+    fn write_round_trips_escaped_backslashes()
+    {
+        // An escaped '\' followed by 'uXXXX' is text, not an escape the parser kept.
+        let input = "[\"\\\\u0041\",\"\\\\uD800\",\"a\\\\b\",\"\\u0041\\\\u0041\",{\"\\\\u0041\":\"\\uD83D\\uDE00\"}]";
+
+        for options in [ ParserOptions::default(), ParserOptions::builder().stringify(true).surrogate_policy(EnumSurrogatePolicy::PRESERVE).build() ]
+        {
+            let root = Parser::new_copy(input, &options).parse().unwrap();
+            let output = write_tree(root, EnumOutputFormat::NDJSON);
+
+            let root = NdjsonReader::from_literal(&output, &options).next().unwrap().unwrap();
+            assert_eq!(write_tree(root, EnumOutputFormat::NDJSON), output);
+
+            let values = Parser::new_copy(&output, &ParserOptions::builder().stringify(true).build()).parse().unwrap();
+            let values = values.downcast_ref::<RNodeArray>().unwrap();
+            let value_at = |index: usize| values.get(index).unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap().get_value().clone();
+            assert_eq!(value_at(0), "\\u0041");
+            assert_eq!(value_at(1), "\\uD800");
+            assert_eq!(value_at(2), "a\\b");
+            assert_eq!(value_at(3), "A\\u0041");
+
+            let object = values.get(4).unwrap().downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+            assert_eq!(object.get("\\u0041").unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap().get_value(), "😀");
+        }
+    }
+
+    #[test]
+    fn write_round_trips_through_the_readers()
+    {
+        let options = ParserOptions::builder().stringify(true).surrogate_policy(EnumSurrogatePolicy::PRESERVE).build();
+
+        for output_format in [ EnumOutputFormat::JSON, EnumOutputFormat::NDJSON, EnumOutputFormat::JSON_SEQ ]
+        {
+            let output = write_tree(create_escaping_tree(), output_format);
+
+            let root = match output_format
+            {
+                EnumOutputFormat::JSON => { Parser::new_copy(&output, &options).parse().unwrap() },
+                EnumOutputFormat::NDJSON => { NdjsonReader::from_literal(&output, &options).next().unwrap().unwrap() },
+                EnumOutputFormat::JSON_SEQ => { JsonSeqReader::from_literal(&output, &options).next().unwrap().unwrap() },
+            };
+
+            // Reading it back gives the same tree, so it's written the same.
+            assert_eq!(write_tree(root.clone(), output_format), output, "{:?}", output_format);
+
+            let object = root.downcast_ref::<RNodeArray>().unwrap().get(0).unwrap().downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+            let value = object.get("new\nline").unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
+            assert_eq!(value.to_utf16(), vec![ 0x61, 0xDFFF, 0xD800 ]);
+            assert!(object.get("quote\"back\\slash").is_some());
+        }
+    }
+
+    #[test]
+    fn write_root_scalars()
+    {
+        let nodes: Vec<Rc<dyn RNode>> = vec![ Rc::new(RNodeString::new_move(String::from("a\"b"))), Rc::new(RNodeBool::new(true)), Rc::new(RNodeNull::new()) ];
+
+        for (node, expected) in nodes.into_iter().zip([ "\"a\\\"b\"", "true", "null" ])
+        {
+            assert_eq!(write_tree(node.clone(), EnumOutputFormat::JSON), expected);
+            assert_eq!(write_tree(node, EnumOutputFormat::JSON_SEQ), format!("\u{1E}{}\n", expected));
+        }
+    }
+
+    #[test]
+    fn write_nothing_of_a_failed_document()
+    {
+        let mut writer = RusonWriter::new_stdout(DEFAULT_INDENT).unwrap();
+        writer.set_output_format(EnumOutputFormat::JSON_SEQ);
+
+        let vec: Vec<Rc<dyn RNode>> = vec![ Rc::new(RNodeDouble::new(1.0)), Rc::new(RNodeDouble::new(f64::NAN)) ];
+        assert!(writer.write(Rc::new(RNodeArray::new(vec))).is_err());
+        assert!(writer.write(Rc::new(RNodeDouble::new(2.0))).unwrap());
+        assert_eq!(writer.builder.borrow().to_string(), "\u{1E}2\n");
+    }
+}
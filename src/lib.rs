@@ -1,2 +1,13 @@
-// This file is intentionally empty (for now at least).
+//! Parses, edits and writes JSON (and JSON5), from whole documents held in memory down to streams
+//! read as they arrive. The 'ruson' binary is a command line front end to this library.
 
+#[macro_use]
+extern crate downcast_rs;
+
+pub mod cst;
+pub mod io;
+pub mod log;
+pub mod parser;
+pub mod rnodes;
+pub mod utils;
+pub mod visitor;
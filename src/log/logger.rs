@@ -11,7 +11,6 @@ pub enum EnumLogLevel
 
 pub trait ILogger
 {
-    #[allow(dead_code)]
     fn debug(&mut self, msg: String);
     fn error(&mut self, msg: String);
     fn fatal(&mut self, msg: String, opt_error_code: Option<i32>);
@@ -152,7 +151,6 @@ impl STDLogger
     }
 }
 
-#[allow(dead_code)]
 pub fn get_std_logger() -> &'static Mutex<RefCell<STDLogger>>
 {
     return &STD_LOGGER;
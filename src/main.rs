@@ -1,22 +1,21 @@
-mod io;
-mod log;
-mod parser;
-mod rnodes;
-mod utils;
-mod visitor;
+use ruson::io::document_reader::DocumentReader;
+use ruson::io::input_format::EnumInputFormat;
+use ruson::io::json_seq_reader::JsonSeqReader;
+use ruson::io::ndjson_reader::NdjsonReader;
+use ruson::io::reader::RusonReader;
+use ruson::io::output_format::EnumOutputFormat;
+use ruson::io::writer::RusonWriter;
+use ruson::log;
+use ruson::log::logger::ILogger;
+use ruson::parser::dialect::EnumDialect;
+use ruson::parser::json_scanner::JsonScanner;
+use ruson::parser::number_mode::EnumNumberMode;
+use ruson::parser::parser_options::ParserOptions;
+use ruson::rnodes::rnode::RNode;
+use ruson::utils::cli_args::CLIArgs;
+use ruson::utils::diagnostic::{render_diagnostic, render_warning};
 
-use log::logger::ILogger;
-use utils::cli_args::CLIArgs;
-
-use crate::io::reader::RusonReader;
-
-#[allow(unused_imports)]
-use crate::io::writer::RusonWriter;
-
-extern crate chrono;
-
-#[macro_use]
-extern crate downcast_rs;
+use std::rc::Rc;
 
 fn main()
 {
@@ -24,10 +23,8 @@ fn main()
     let mut cli_args = CLIArgs::new();
     let opt_cli_result: Option<(i32, String)> = cli_args.parse(&args);
 
-    if opt_cli_result.is_some()
+    if let Some((err_code, msg)) = opt_cli_result
     {
-        let (err_code, msg) = opt_cli_result.unwrap();
-
         if err_code == 0
         {
             println!("{}", msg);
@@ -42,27 +39,115 @@ fn main()
         }
     }
 
+    let options = ParserOptions::builder()
+        .dialect(if cli_args.json5 { EnumDialect::JSON5 } else { EnumDialect::JSON })
+        .lenient_numbers(cli_args.lenient_numbers)
+        .duplicate_key_policy(cli_args.duplicate_key_policy)
+        .stringify(cli_args.stringify)
+        .surrogate_policy(cli_args.surrogate_policy)
+        .number_mode(get_number_mode(&cli_args))
+        .max_depth(cli_args.max_depth)
+        .max_string_length(cli_args.max_string_length)
+        .max_members(cli_args.max_members)
+        .max_elements(cli_args.max_elements)
+        .max_input_size(cli_args.max_input_size)
+        .build();
+
+    if cli_args.extract
+    {
+        extract_documents(&cli_args, &options);
+        return;
+    }
+
+    let mut output: Option<RusonWriter> = cli_args.output_format.map(|output_format|
+    {
+        let mut writer = RusonWriter::new_stdout(4).expect("Failed to open std output");
+        writer.set_output_format(output_format);
+        return writer;
+    });
+
+    if cli_args.input_format != EnumInputFormat::JSON
+    {
+        let mut document_reader: Box<dyn DocumentReader> = match (cli_args.input_format, &cli_args.input_file) {
+            (EnumInputFormat::NDJSON, Some(input_file)) => { Box::new(NdjsonReader::from_file(input_file, &options)) },
+            (EnumInputFormat::NDJSON, None) => { Box::new(NdjsonReader::from_stdin(&options)) },
+            (_, Some(input_file)) => { Box::new(JsonSeqReader::from_file(input_file, &options)) },
+            (_, None) => { Box::new(JsonSeqReader::from_stdin(&options)) },
+        };
+
+        document_reader.set_skip_bad_documents(cli_args.skip_bad_lines);
+        read_documents(document_reader.as_mut(), &mut output);
+        flush_output(&mut output);
+        return;
+    }
+
     let ruson_reader: RusonReader = match cli_args.input_file {
-        Some(input_file) => { RusonReader::from_file(&input_file, cli_args.stringify) },
-        None => { RusonReader::from_stdin(cli_args.stringify) }
+        Some(input_file) => { RusonReader::from_file(&input_file, &options) },
+        None => { RusonReader::from_stdin(&options) }
     };
 
+    if cli_args.recover
+    {
+        let (root_node, errors) = ruson_reader.parse_recovering();
+        report_warnings(&ruson_reader);
+
+        for error in &errors
+        {
+            let diagnostic = render_diagnostic(&ruson_reader.get_input(), ruson_reader.get_input_start(), ruson_reader.file_path.as_ref(), error);
+            eprint!("{}", diagnostic);
+        }
+
+        if !errors.is_empty()
+        {
+            eprintln!("error: found {} error(s) in the input", errors.len());
+            std::process::exit(1);
+        }
+
+        output_document(&mut output, root_node);
+        flush_output(&mut output);
+        return;
+    }
+
+    if cli_args.stream
+    {
+        let result = ruson_reader.parse_stream();
+        report_warnings(&ruson_reader);
+
+        match result
+        {
+            Ok(nodes) =>
+            {
+                for node in nodes
+                {
+                    output_document(&mut output, node);
+                }
+
+                flush_output(&mut output);
+            },
+            Err(error) =>
+            {
+                eprint!("{}", render_diagnostic(&ruson_reader.get_input(), ruson_reader.get_input_start(), ruson_reader.file_path.as_ref(), &error));
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
     let root_node_result = ruson_reader.parse();
+    report_warnings(&ruson_reader);
 
     match root_node_result
     {
         Ok(root_node) =>
         {
-            let enum_type = root_node.get_node_type();
-            let logger_cell = log::logger::get_std_logger().lock().unwrap();
-            let mut logger = logger_cell.borrow_mut();
-            logger.info(enum_type.to_string());
+            output_document(&mut output, root_node);
+            flush_output(&mut output);
         },
-        Err(msg) =>
+        Err(error) =>
         {
-            let logger_cell = log::logger::get_std_logger().lock().unwrap();
-            let mut logger = logger_cell.borrow_mut();
-            logger.error(msg);
+            let diagnostic = render_diagnostic(&ruson_reader.get_input(), ruson_reader.get_input_start(), ruson_reader.file_path.as_ref(), &error);
+            eprint!("{}", diagnostic);
 
             // "Normal" error in parsing will be >0
             std::process::exit(1);
@@ -70,3 +155,107 @@ fn main()
     }
 }
 
+/// Reads a sequence of documents (see '--input-format'), stopping at the first bad one unless bad
+/// documents are skipped.
+fn read_documents(document_reader: &mut dyn DocumentReader, output: &mut Option<RusonWriter>)
+{
+    while let Some(result) = document_reader.next()
+    {
+        report_document_warnings(document_reader);
+
+        match result
+        {
+            Ok(node) => { output_document(output, node); },
+            Err(error) =>
+            {
+                flush_output(output);
+                eprint!("{}", render_diagnostic(document_reader.get_input(), document_reader.get_input_start(), document_reader.get_file_path(), &error));
+                std::process::exit(1);
+            }
+        }
+    }
+
+    report_document_warnings(document_reader);
+}
+
+fn report_document_warnings(document_reader: &mut dyn DocumentReader)
+{
+    for warning in document_reader.take_warnings()
+    {
+        eprint!("{}", render_warning(document_reader.get_input(), document_reader.get_input_start(), document_reader.get_file_path(), &warning));
+    }
+}
+
+/// Gets which RNode numbers are parsed into, '--decimal-numbers' taking precedence over '--exact-numbers'.
+fn get_number_mode(cli_args: &CLIArgs) -> EnumNumberMode
+{
+    #[cfg(feature = "arbitrary_precision")]
+    if cli_args.decimal_numbers
+    {
+        return EnumNumberMode::DECIMAL;
+    }
+
+    return if cli_args.exact_numbers { EnumNumberMode::EXACT } else { EnumNumberMode::DOUBLE };
+}
+
+/// Writes a document with the '--output-format' writer, or logs its type when there is no output.
+fn output_document(output: &mut Option<RusonWriter>, node: Rc<dyn RNode>)
+{
+    match output
+    {
+        Some(writer) =>
+        {
+            if let Err(error) = writer.write(node)
+            {
+                eprintln!("error: {}", error);
+                std::process::exit(1);
+            }
+        },
+        None =>
+        {
+            let logger_cell = log::logger::get_std_logger().lock().unwrap();
+            let mut logger = logger_cell.borrow_mut();
+            logger.info(node.get_node_type().to_string());
+        }
+    }
+}
+
+fn flush_output(output: &mut Option<RusonWriter>)
+{
+    if let Some(writer) = output
+    {
+        let _ = writer.flush();
+    }
+}
+
+/// Writes the JSON objects and arrays embedded in the input text (see '--extract'), reporting where each one was found on stderr.
+fn extract_documents(cli_args: &CLIArgs, options: &ParserOptions)
+{
+    // The text is searched as it is read, so a log can be piped in without holding all of it.
+    let scanner = match &cli_args.input_file {
+        Some(input_file) => { JsonScanner::new_reader(std::fs::File::open(input_file).expect("Failed to read input file"), options) },
+        None => { JsonScanner::new_reader(std::io::stdin(), options) },
+    };
+
+    let mut writer = RusonWriter::new_stdout(4).expect("Failed to open std output");
+    writer.set_output_format(cli_args.output_format.unwrap_or(EnumOutputFormat::NDJSON));
+    let mut output = Some(writer);
+
+    for found in scanner
+    {
+        // The locations go to stderr so they don't mix with the documents on stdout.
+        eprintln!("found {} at line {}, column {} (bytes {}..{})", found.node.get_node_type(), found.line, found.column, found.start, found.end);
+        output_document(&mut output, found.node);
+    }
+
+    flush_output(&mut output);
+}
+
+/// Prints the problems that didn't stop the parse (ex. duplicate keys) as warnings.
+fn report_warnings(ruson_reader: &RusonReader)
+{
+    for warning in ruson_reader.take_warnings()
+    {
+        eprint!("{}", render_warning(&ruson_reader.get_input(), ruson_reader.get_input_start(), ruson_reader.file_path.as_ref(), &warning));
+    }
+}
@@ -0,0 +1,13 @@
+/// What a 'JsonHandler' wants the parse to do after one of its callbacks.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumControl
+{
+    /// Carry on with the next callback.
+    #[default]
+    CONTINUE,
+    /// Skip the array/object that was just started (or the value of the key just read) without any
+    /// callbacks for it. Anywhere else this is the same as CONTINUE.
+    SKIP,
+    /// Stop parsing right away.
+    STOP,
+}
@@ -0,0 +1,11 @@
+/// The flavour of JSON accepted by the parser.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumDialect
+{
+    /// Strict JSON as described by RFC 8259.
+    #[default]
+    JSON,
+    /// JSON5 (https://spec.json5.org), which adds comments, trailing commas, single-quoted strings,
+    /// unquoted keys, hexadecimal numbers, 'Infinity', 'NaN' and multi-line strings.
+    JSON5,
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+/// How an object with the same key more than once (ex. '{"a": 1, "a": 2}') is handled.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumDuplicateKeyPolicy
+{
+    /// Report the duplicate as a parse error (pointing at both occurrences).
+    ERROR,
+    /// Keep the value of the first occurrence.
+    FIRST_WINS,
+    /// Keep the value of the last occurrence.
+    #[default]
+    LAST_WINS,
+    /// Keep every value, retrievable with 'RNodeObject::get_all' (the last one is what 'get' returns).
+    KEEP_ALL,
+}
+
+pub fn get_duplicate_key_policy_from_string(string: &str) -> Result<EnumDuplicateKeyPolicy, String>
+{
+    static LOOKUP_TABLE: std::sync::LazyLock<HashMap::<String, EnumDuplicateKeyPolicy>> = std::sync::LazyLock::new(||
+    {
+        let mut table = HashMap::<String, EnumDuplicateKeyPolicy>::new();
+        table.insert(String::from("ERROR"), EnumDuplicateKeyPolicy::ERROR);
+        table.insert(String::from("FIRST-WINS"), EnumDuplicateKeyPolicy::FIRST_WINS);
+        table.insert(String::from("KEEP-ALL"), EnumDuplicateKeyPolicy::KEEP_ALL);
+        table.insert(String::from("LAST-WINS"), EnumDuplicateKeyPolicy::LAST_WINS);
+
+        return table;
+    });
+
+    let string_upper = string.to_uppercase().replace('_', "-");
+    let opt_result = LOOKUP_TABLE.get(&string_upper);
+
+    if let Some(result) = opt_result
+    {
+        return Ok(*result);
+    }
+
+    return Err(String::from("String is not a EnumDuplicateKeyPolicy"));
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::duplicate_key_policy::*;
+
+    #[test]
+    fn duplicate_key_policy_from_string()
+    {
+        assert_eq!(get_duplicate_key_policy_from_string(&String::from("error")), Ok(EnumDuplicateKeyPolicy::ERROR));
+        assert_eq!(get_duplicate_key_policy_from_string(&String::from("first-wins")), Ok(EnumDuplicateKeyPolicy::FIRST_WINS));
+        assert_eq!(get_duplicate_key_policy_from_string(&String::from("LAST_WINS")), Ok(EnumDuplicateKeyPolicy::LAST_WINS));
+        assert_eq!(get_duplicate_key_policy_from_string(&String::from("Keep-All")), Ok(EnumDuplicateKeyPolicy::KEEP_ALL));
+        assert!(get_duplicate_key_policy_from_string(&String::from("ignore")).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use crate::rnodes::rnode::RNode;
+
+use std::rc::Rc;
+
+/// A JSON object or array found by 'JsonScanner' in some text.
+pub struct EmbeddedJson
+{
+    pub node: Rc<dyn RNode>,
+    /// The byte offset of its opening '{' or '['.
+    pub start: usize,
+    /// The byte offset one past its closing '}' or ']'.
+    pub end: usize,
+    /// 1-based line number of its start.
+    pub line: usize,
+    /// 1-based column (in characters) of its start.
+    pub column: usize,
+}
@@ -0,0 +1,26 @@
+use crate::rnodes::rnode::RNode;
+
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+/// What 'PushParser::feed' made of the input fed so far.
+#[allow(non_camel_case_types)]
+pub enum EnumFeedResult
+{
+    /// The root value isn't complete yet.
+    NEED_MORE_DATA,
+    /// The root value is complete. Anything fed after it may only be whitespace.
+    VALUE(Rc<dyn RNode>),
+}
+
+impl Debug for EnumFeedResult
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            EnumFeedResult::NEED_MORE_DATA => write!(f, "NEED_MORE_DATA"),
+            EnumFeedResult::VALUE(node) => write!(f, "VALUE({})", node.get_node_type()),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::rnodes::rnode::RNode;
+
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+/// A piece of a JSON document as yielded by 'JsonEvents', in the order it appears in the input.
+#[allow(non_camel_case_types)]
+pub enum EnumJsonEvent
+{
+    /// The '{' of an object.
+    START_OBJECT,
+    /// The key of the next object member, whose value follows as the next event(s).
+    KEY(String),
+    /// The '[' of an array.
+    START_ARRAY,
+    /// A bool, number, null or string (numbers are parsed according to the 'number_mode' option).
+    SCALAR(Rc<dyn RNode>),
+    /// The ']' of an array.
+    END_ARRAY,
+    /// The '}' of an object.
+    END_OBJECT,
+}
+
+impl Debug for EnumJsonEvent
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            EnumJsonEvent::START_OBJECT => write!(f, "START_OBJECT"),
+            EnumJsonEvent::KEY(key) => write!(f, "KEY({:?})", key),
+            EnumJsonEvent::START_ARRAY => write!(f, "START_ARRAY"),
+            EnumJsonEvent::SCALAR(node) => write!(f, "SCALAR({})", node.get_node_type()),
+            EnumJsonEvent::END_ARRAY => write!(f, "END_ARRAY"),
+            EnumJsonEvent::END_OBJECT => write!(f, "END_OBJECT"),
+        }
+    }
+}
@@ -0,0 +1,642 @@
+use crate::parser::control::EnumControl;
+use crate::parser::dialect::EnumDialect;
+use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+use crate::parser::json_event::EnumJsonEvent;
+use crate::parser::json_handler::JsonHandler;
+use crate::parser::lexer::Lexer;
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parse_checks::{check_depth, check_input_size, expect_end_of_input, get_duplicate_key_error, get_element_limit_error, get_member_limit_error, read_key};
+use crate::parser::parser::create_scalar;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+
+use super::token::{describe_token, is_symbol, TokenTrait};
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::rc::Rc;
+
+/// What 'JsonEvents' expects next from the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+enum EnumExpect
+{
+    /// A value (the root value, an array element or the value of a member).
+    VALUE,
+    /// The first element of an array or its ']'.
+    FIRST_ELEMENT,
+    /// The first key of an object or its '}'.
+    FIRST_KEY,
+    /// The key of the next member.
+    KEY,
+    /// A ',' or the closing bracket of the enclosing array/object (or the end of input after the root value).
+    COMMA_OR_END,
+    /// Nothing more, after the end of input or an error.
+    DONE,
+}
+
+/// An array or object that was started but not yet ended.
+struct Container
+{
+    is_object: bool,
+    /// How many elements/members were started so far.
+    count: usize,
+    /// Where each key was first written, only kept to report duplicates with EnumDuplicateKeyPolicy::ERROR.
+    first_keys: HashMap<String, Snapshot>,
+}
+
+/// A pull parser yielding the events of a single root value (see 'EnumJsonEvent') without building
+/// an RNode tree, so only the arrays/objects enclosing the current position are held in memory.
+///
+/// The structure is validated as it goes, so the events always nest properly until an error is
+/// yielded, after which the iterator ends. The same options as the Parser apply, except that
+/// duplicate keys are passed along as they are unless the policy is EnumDuplicateKeyPolicy::ERROR.
+///
+/// ```text
+/// for event in JsonEvents::new_move(input, &ParserOptions::default())
+/// {
+///     match event?
+///     {
+///         EnumJsonEvent::KEY(key) => { ... },
+///         ...
+///     }
+/// }
+/// ```
+pub struct JsonEvents
+{
+    lexer: Lexer,
+    containers: Vec<Container>,
+    expect: EnumExpect,
+    started: bool,
+    /// Whether the last 'next' ran out of fed input (see 'new_push').
+    waiting: bool,
+    /// The depth 'skip_value' is skipping back to, when it ran out of fed input before getting there.
+    skip_to: Option<usize>,
+}
+
+impl JsonEvents
+{
+    pub fn new_copy(input: &str, options: &ParserOptions) -> Self
+    {
+        return Self::new_move(String::from(input), options);
+    }
+
+    /// Creates the events from raw bytes, which are expected to be UTF-8.
+    pub fn new_bytes(input: Vec<u8>, options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_bytes(input, options));
+    }
+
+    pub fn new_move(input: String, options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_move(input, options));
+    }
+
+    /// Creates the events from input read from 'source' as it goes (see 'Lexer::new_reader'), so
+    /// that a huge input can be handled with bounded memory.
+    pub fn new_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_reader(source, options));
+    }
+
+    fn new_lexer(lexer: Lexer) -> Self
+    {
+        Self { lexer, containers: Vec::new(), expect: EnumExpect::VALUE, started: false, waiting: false, skip_to: None }
+    }
+
+    /// Creates the events for input that is handed over in chunks with 'feed', followed by 'end_input'.
+    /// When the input fed so far runs out, 'next' returns None with 'needs_input' set, and the events
+    /// continue once more is fed. Nothing is yielded for a token until it is complete.
+    pub fn new_push(options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_push(options));
+    }
+
+    /// Adds the next chunk of input (see 'new_push'), which can end anywhere.
+    pub fn feed(&mut self, chunk: &[u8])
+    {
+        self.lexer.feed(chunk);
+    }
+
+    /// Marks the end of the fed input (see 'new_push').
+    pub fn end_input(&mut self)
+    {
+        self.lexer.end_input();
+    }
+
+    /// Whether the last 'next' returned None because it needs more fed input, rather than at the end.
+    pub fn needs_input(&self) -> bool
+    {
+        return self.waiting;
+    }
+
+    pub fn get_options(&self) -> &ParserOptions
+    {
+        return self.lexer.get_options();
+    }
+
+    /// Gets the text being parsed (ex. for rendering diagnostics). For a streamed input this is
+    /// only the part still being held, which starts at the byte offset 'get_input_start'.
+    pub fn get_input(&self) -> &String
+    {
+        return self.lexer.get_input();
+    }
+
+    /// Gets the byte offset (into the whole input) of the start of 'get_input'.
+    pub fn get_input_start(&self) -> usize
+    {
+        return self.lexer.get_input_start();
+    }
+
+    /// How many arrays/objects enclose the position of the last event.
+    pub fn get_depth(&self) -> usize
+    {
+        return self.containers.len();
+    }
+
+    /// Skips what is being started without yielding its events: the whole value after a KEY event (or
+    /// before the root value), the rest of the array/object after a START_ARRAY/START_OBJECT event.
+    /// What is skipped is still validated.
+    pub fn skip_value(&mut self) -> Result<(), ParseError>
+    {
+        let target_depth = self.skip_to.take().unwrap_or(if self.expect == EnumExpect::VALUE { self.containers.len() } else { self.containers.len().saturating_sub(1) });
+
+        loop
+        {
+            match self.next()
+            {
+                Some(Ok(_)) if self.containers.len() > target_depth => {},
+                Some(Err(error)) => { return Err(error); },
+                // Carry on skipping once more input is fed.
+                None if self.waiting => { self.skip_to = Some(target_depth); return Ok(()); },
+                _ => { return Ok(()); },
+            }
+        }
+    }
+
+    /// Parses the rest of the input, calling the handler for each event. Returns false when
+    /// the handler stopped the parse early (see 'EnumControl').
+    ///
+    /// With fed input (see 'new_push'), this returns true with 'needs_input' set once the input fed
+    /// so far runs out, and can be called again to continue after feeding more.
+    pub fn drive(&mut self, handler: &mut dyn JsonHandler) -> Result<bool, ParseError>
+    {
+        if self.skip_to.is_some()
+        {
+            self.skip_value()?;
+        }
+
+        while let Some(event) = self.next()
+        {
+            let control = match event?
+            {
+                EnumJsonEvent::START_OBJECT => handler.on_object_start(),
+                EnumJsonEvent::KEY(key) => handler.on_key(&key),
+                EnumJsonEvent::START_ARRAY => handler.on_array_start(),
+                EnumJsonEvent::SCALAR(node) => handler.on_value(node),
+                EnumJsonEvent::END_ARRAY => handler.on_array_end(),
+                EnumJsonEvent::END_OBJECT => handler.on_object_end(),
+            };
+
+            match control
+            {
+                EnumControl::CONTINUE => {},
+                // Only something that was just started (a key's value or an array/object) can be skipped.
+                EnumControl::SKIP if self.expect != EnumExpect::COMMA_OR_END => { self.skip_value()?; },
+                EnumControl::SKIP => {},
+                EnumControl::STOP => { return Ok(false); },
+            }
+        }
+
+        return Ok(true);
+    }
+
+    fn next_event(&mut self) -> Result<Option<EnumJsonEvent>, ParseError>
+    {
+        if !self.started
+        {
+            self.started = true;
+            check_input_size(&self.lexer)?;
+        }
+
+        self.waiting = false;
+
+        loop
+        {
+            // Each step only changes the state once all of its tokens were lexed, so running out of
+            // fed input can go back to the start of the step to try it again later.
+            let snapshot = self.lexer.snap();
+            let result = self.step();
+
+            if self.lexer.take_starved()
+            {
+                self.lexer.restore(&snapshot);
+                self.waiting = true;
+                return Ok(None);
+            }
+
+            match result?
+            {
+                Some(event) => { return Ok(Some(event)); },
+                None if self.expect == EnumExpect::DONE => { return Ok(None); },
+                None => {},
+            }
+        }
+    }
+
+    /// Parses what is expected next, which may not yield an event (ex. a ',').
+    fn step(&mut self) -> Result<Option<EnumJsonEvent>, ParseError>
+    {
+        match self.expect
+        {
+            EnumExpect::VALUE => { return self.start_value().map(Some); },
+            EnumExpect::FIRST_ELEMENT =>
+            {
+                if self.try_consume_symbol("]")?
+                {
+                    return Ok(Some(self.end_container()));
+                }
+
+                self.begin_element()?;
+            },
+            EnumExpect::FIRST_KEY =>
+            {
+                if self.try_consume_symbol("}")?
+                {
+                    return Ok(Some(self.end_container()));
+                }
+
+                self.expect = EnumExpect::KEY;
+            },
+            EnumExpect::KEY => { return self.parse_key().map(Some); },
+            EnumExpect::COMMA_OR_END => { return self.parse_comma_or_end(); },
+            EnumExpect::DONE => {},
+        }
+
+        return Ok(None);
+    }
+
+    fn start_value(&mut self) -> Result<EnumJsonEvent, ParseError>
+    {
+        let token = self.next_token()?;
+
+        if let Some(node) = create_scalar(&self.lexer, &*token)?
+        {
+            self.expect = EnumExpect::COMMA_OR_END;
+            return Ok(EnumJsonEvent::SCALAR(node));
+        }
+
+        let is_object = is_symbol(&*token, "{");
+
+        if is_object || is_symbol(&*token, "[")
+        {
+            check_depth(&self.lexer, self.containers.len())?;
+
+            self.containers.push(Container { is_object, count: 0, first_keys: HashMap::new() });
+
+            if is_object
+            {
+                self.expect = EnumExpect::FIRST_KEY;
+                return Ok(EnumJsonEvent::START_OBJECT);
+            }
+
+            self.expect = EnumExpect::FIRST_ELEMENT;
+            return Ok(EnumJsonEvent::START_ARRAY);
+        }
+
+        return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedValue(describe_token(&*token))));
+    }
+
+    /// Counts the next element of the enclosing array, which must not pass the 'max_elements' limit.
+    fn begin_element(&mut self) -> Result<(), ParseError>
+    {
+        let count = self.containers.last().expect("An element should be inside of an array").count;
+
+        if let Some(kind) = get_element_limit_error(self.get_options(), count)
+        {
+            return Err(self.next_token_error(kind));
+        }
+
+        self.containers.last_mut().unwrap().count += 1;
+        self.expect = EnumExpect::VALUE;
+        return Ok(());
+    }
+
+    /// Parses the '"key":' of the next member.
+    fn parse_key(&mut self) -> Result<EnumJsonEvent, ParseError>
+    {
+        let count = self.containers.last().expect("A key should be inside of an object").count;
+
+        if let Some(kind) = get_member_limit_error(self.get_options(), count)
+        {
+            return Err(self.next_token_error(kind));
+        }
+
+        let token = self.next_token()?;
+        let key = read_key(&self.lexer, &*token)?;
+        let key_start = self.lexer.get_token_start().clone();
+        let check_duplicates = self.get_options().get_duplicate_key_policy() == EnumDuplicateKeyPolicy::ERROR;
+
+        if let Some(first_start) = self.containers.last().unwrap().first_keys.get(&key).filter(|_| check_duplicates)
+        {
+            return Err(get_duplicate_key_error(&key, first_start, &key_start, self.lexer.get_position()));
+        }
+
+        let token = self.next_token()?;
+
+        if !is_symbol(&*token, ":")
+        {
+            return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedColon(describe_token(&*token))));
+        }
+
+        let container = self.containers.last_mut().unwrap();
+        container.count += 1;
+
+        if check_duplicates
+        {
+            container.first_keys.insert(key.clone(), key_start);
+        }
+
+        self.expect = EnumExpect::VALUE;
+        return Ok(EnumJsonEvent::KEY(key));
+    }
+
+    /// Parses what follows a value: a ',' (yielding no event) or the end of the enclosing array/object.
+    /// After the root value, only the end of input may follow.
+    fn parse_comma_or_end(&mut self) -> Result<Option<EnumJsonEvent>, ParseError>
+    {
+        let is_object = match self.containers.last()
+        {
+            Some(container) => container.is_object,
+            None =>
+            {
+                expect_end_of_input(&mut self.lexer)?;
+                self.expect = EnumExpect::DONE;
+                return Ok(None);
+            },
+        };
+
+        let close = if is_object { '}' } else { ']' };
+        let token = self.next_token()?;
+
+        if is_symbol(&*token, ",")
+        {
+            // JSON5 allows a trailing ','.
+            if self.get_options().get_dialect() == EnumDialect::JSON5 && self.try_consume_symbol(&close.to_string())?
+            {
+                return Ok(Some(self.end_container()));
+            }
+
+            if is_object
+            {
+                self.expect = EnumExpect::KEY;
+            }
+
+            else
+            {
+                self.begin_element()?;
+            }
+
+            return Ok(None);
+        }
+
+        if is_symbol(&*token, &close.to_string())
+        {
+            return Ok(Some(self.end_container()));
+        }
+
+        return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedCommaOrEnd { close, found: describe_token(&*token) }));
+    }
+
+    fn end_container(&mut self) -> EnumJsonEvent
+    {
+        let container = self.containers.pop().expect("Only a started array/object can end");
+        self.expect = EnumExpect::COMMA_OR_END;
+
+        return if container.is_object { EnumJsonEvent::END_OBJECT } else { EnumJsonEvent::END_ARRAY };
+    }
+
+    /// Lexes the next token, failing when the input fed so far ran out (see 'new_push') as the token
+    /// may not be complete. Failing before the state is changed lets the step be tried again later.
+    fn next_token(&mut self) -> Result<Rc<dyn TokenTrait>, ParseError>
+    {
+        let result = self.lexer.next_token();
+
+        if self.lexer.is_starved()
+        {
+            return Err(self.lexer.token_error(EnumParseErrorKind::UnexpectedEOF));
+        }
+
+        return result;
+    }
+
+    /// Consumes the next token if it is the given symbol (failing like 'next_token').
+    fn try_consume_symbol(&mut self, symbol: &str) -> Result<bool, ParseError>
+    {
+        let snapshot = self.lexer.snap();
+
+        match self.next_token()
+        {
+            Ok(token) if is_symbol(&*token, symbol) => { return Ok(true); },
+            Err(error) if self.lexer.is_starved() => { return Err(error); },
+            _ => {},
+        }
+
+        self.lexer.restore(&snapshot);
+        return Ok(false);
+    }
+
+    /// Creates an error spanning the next token (ex. the value that passes a limit).
+    fn next_token_error(&mut self, kind: EnumParseErrorKind) -> ParseError
+    {
+        let _ = self.lexer.next_token();
+        return self.lexer.token_error(kind);
+    }
+}
+
+impl Iterator for JsonEvents
+{
+    type Item = Result<EnumJsonEvent, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        match self.next_event()
+        {
+            Ok(event) => { return event.map(Ok); },
+            Err(error) =>
+            {
+                self.expect = EnumExpect::DONE;
+                return Some(Err(error));
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::json_event::EnumJsonEvent;
+    use crate::parser::json_events::JsonEvents;
+    use crate::parser::number_mode::EnumNumberMode;
+    use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::rnodes::rnode_number::RNodeNumber;
+    use crate::rnodes::rnode_string::RNodeString;
+
+    /// Collects the events (formatted with Debug) up to the first error, if any.
+    fn collect_events(input: &str, options: &ParserOptions) -> (Vec<String>, Option<ParseError>)
+    {
+        let mut events = Vec::<String>::new();
+
+        for event in JsonEvents::new_copy(&String::from(input), options)
+        {
+            match event
+            {
+                Ok(event) => { events.push(format!("{:?}", event)); },
+                Err(error) => { return (events, Some(error)); },
+            }
+        }
+
+        return (events, None);
+    }
+
+    #[test]
+    fn events_of_document()
+    {
+        let (events, error) = collect_events("{\"a\": [1, true, null, \"x\"], \"b\": {}, \"c\": []}", &ParserOptions::default());
+        assert!(error.is_none());
+        assert_eq!(events, vec![
+            "START_OBJECT", "KEY(\"a\")", "START_ARRAY", "SCALAR(DOUBLE)", "SCALAR(BOOL)", "SCALAR(NULL)", "SCALAR(STRING)", "END_ARRAY",
+            "KEY(\"b\")", "START_OBJECT", "END_OBJECT", "KEY(\"c\")", "START_ARRAY", "END_ARRAY", "END_OBJECT",
+        ]);
+
+        let (events, error) = collect_events(" 42 ", &ParserOptions::default());
+        assert!(error.is_none());
+        assert_eq!(events, vec![ "SCALAR(DOUBLE)" ]);
+    }
+
+    #[test]
+    fn events_scalar_values()
+    {
+        let options = ParserOptions::builder().number_mode(EnumNumberMode::EXACT).stringify(true).build();
+        let events: Vec<EnumJsonEvent> = JsonEvents::new_copy(&String::from("[12345678901234567890, \"\\u00e9\"]"), &options).map(|event| event.unwrap()).collect();
+        assert_eq!(events.len(), 4);
+
+        match (&events[1], &events[2])
+        {
+            (EnumJsonEvent::SCALAR(number), EnumJsonEvent::SCALAR(string)) =>
+            {
+                assert_eq!(number.downcast_ref::<RNodeNumber>().unwrap().as_u64().ok(), Some(12345678901234567890));
+                assert_eq!(string.downcast_ref::<RNodeString>().unwrap().get_value(), "é");
+            },
+            _ => { panic!("Expected two scalars but found {:?}", events); },
+        }
+    }
+
+    #[test]
+    fn events_validate_structure()
+    {
+        let cases: Vec<(&str, usize, EnumParseErrorKind)> = vec![
+            ("[1, 2}", 3, EnumParseErrorKind::ExpectedCommaOrEnd { close: ']', found: String::from("'}'") }),
+            ("{\"a\" 1}", 1, EnumParseErrorKind::ExpectedColon(String::from("number 1"))),
+            ("{1: 2}", 1, EnumParseErrorKind::ExpectedKey(String::from("number 1"))),
+            ("[1,]", 2, EnumParseErrorKind::ExpectedValue(String::from("']'"))),
+            ("[1] 2", 3, EnumParseErrorKind::TrailingData(String::from("number 2"))),
+            ("[[1]", 4, EnumParseErrorKind::UnexpectedEOF),
+        ];
+
+        for (input, event_count, kind) in cases
+        {
+            let (events, error) = collect_events(input, &ParserOptions::default());
+            assert_eq!(events.len(), event_count, "{}", input);
+            assert_eq!(error.expect(input).get_kind(), &kind, "{}", input);
+        }
+
+        // Nothing follows an error.
+        let mut events = JsonEvents::new_copy(&String::from("[@, 1]"), &ParserOptions::default());
+        assert!(events.next().unwrap().is_ok());
+        assert!(events.next().unwrap().is_err());
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn events_json5()
+    {
+        let options = ParserOptions::builder().dialect(EnumDialect::JSON5).build();
+        let (events, error) = collect_events("{a: [1, 2,], 'b': 3, // comment\n}", &options);
+        assert!(error.is_none());
+        assert_eq!(events, vec![ "START_OBJECT", "KEY(\"a\")", "START_ARRAY", "SCALAR(DOUBLE)", "SCALAR(DOUBLE)", "END_ARRAY", "KEY(\"b\")", "SCALAR(DOUBLE)", "END_OBJECT" ]);
+    }
+
+    #[test]
+    fn events_duplicate_keys()
+    {
+        let input = "{\"a\": 1, \"a\": 2}";
+        let (events, error) = collect_events(input, &ParserOptions::default());
+        assert!(error.is_none());
+        assert_eq!(events.iter().filter(|event| *event == "KEY(\"a\")").count(), 2);
+
+        let (_, error) = collect_events(input, &ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR).build());
+        let error = error.unwrap();
+        assert!(matches!(error.get_kind(), EnumParseErrorKind::DuplicateKey { first_start: 1, .. }));
+        assert_eq!(error.get_start(), 9);
+    }
+
+    #[test]
+    fn events_limits()
+    {
+        let (events, error) = collect_events(&"[".repeat(100000), &ParserOptions::builder().max_depth(Some(128)).build());
+        assert_eq!(events.len(), 128);
+        assert_eq!(error.unwrap().get_kind(), &EnumParseErrorKind::DepthLimitExceeded { limit: 128 });
+
+        let options = ParserOptions::builder().max_elements(Some(2)).max_members(Some(1)).max_input_size(Some(16)).build();
+
+        let (_, error) = collect_events("[1, 2, 3]", &options);
+        assert_eq!(error.unwrap().get_kind(), &EnumParseErrorKind::TooManyElements { limit: 2 });
+
+        let (_, error) = collect_events("{\"a\": 1, \"b\": 2}", &options);
+        assert_eq!(error.unwrap().get_kind(), &EnumParseErrorKind::TooManyMembers { limit: 1 });
+
+        let (events, error) = collect_events("[1, 2, 3, 4, 5, 6]", &options);
+        assert!(events.is_empty());
+        assert_eq!(error.unwrap().get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 16 });
+    }
+
+    #[test]
+    fn events_same_errors_as_parser()
+    {
+        let options = ParserOptions::builder().max_elements(Some(2)).max_members(Some(2)).max_depth(Some(3)).max_string_length(Some(4))
+            .duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR).build();
+        let json5 = ParserOptions::builder().dialect(EnumDialect::JSON5).max_string_length(Some(4)).build();
+        let inputs = [
+            ("[1, 2, 3]", &options), ("{\"a\": 1, \"b\": 2, \"c\": 3}", &options), ("[[[[]]]]", &options), ("{\"a\": 1, \"a\": 2}", &options),
+            ("[1] @", &options), ("[1] 2", &options), ("{1: 2}", &options), ("{\"a\" 1}", &options), ("{abcde: 1}", &json5), ("{\"abcde\": 1}", &json5),
+        ];
+
+        for (input, options) in inputs
+        {
+            let expected = Parser::new_copy(input, options).parse().err().unwrap();
+            assert_eq!(collect_events(input, options).1.unwrap(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn events_deeply_nested_without_limit()
+    {
+        let depth = 200000;
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let mut events = JsonEvents::new_move(input, &ParserOptions::builder().max_depth(None).build());
+        let mut max_depth = 0;
+
+        while let Some(event) = events.next()
+        {
+            event.unwrap();
+            max_depth = max_depth.max(events.get_depth());
+        }
+
+        assert_eq!(max_depth, depth);
+    }
+}
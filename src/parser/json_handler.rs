@@ -0,0 +1,178 @@
+use crate::parser::control::EnumControl;
+use crate::rnodes::rnode::RNode;
+
+use std::rc::Rc;
+
+/// Callbacks for each piece of a JSON document, in the order they appear in the input. See
+/// 'JsonEvents::drive'. Every callback defaults to doing nothing and returning EnumControl::CONTINUE.
+///
+/// An array/object that is skipped gets no callbacks at all, including its 'on_*_end'.
+pub trait JsonHandler
+{
+    fn on_object_start(&mut self) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+
+    /// The key of the next member, whose value follows. Returning EnumControl::SKIP skips the value.
+    fn on_key(&mut self, _key: &str) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+
+    /// A bool, number, null or string (numbers are parsed according to the 'number_mode' option).
+    fn on_value(&mut self, _value: Rc<dyn RNode>) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_object_end(&mut self) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_array_start(&mut self) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_array_end(&mut self) -> EnumControl
+    {
+        return EnumControl::CONTINUE;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::control::EnumControl;
+    use crate::parser::json_events::JsonEvents;
+    use crate::parser::json_handler::JsonHandler;
+    use crate::parser::parse_error::EnumParseErrorKind;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::rnodes::rnode::RNode;
+
+    use std::rc::Rc;
+
+    /// Records every callback, skipping the value of 'skip_key' (and every array with 'skip_arrays') and
+    /// stopping at the first string with 'stop_at_string'.
+    #[derive(Default)]
+    struct RecordingHandler
+    {
+        calls: Vec<String>,
+        skip_key: Option<&'static str>,
+        skip_arrays: bool,
+        stop_at_string: bool,
+    }
+
+    impl JsonHandler for RecordingHandler
+    {
+        fn on_object_start(&mut self) -> EnumControl
+        {
+            self.calls.push(String::from("{"));
+            return EnumControl::CONTINUE;
+        }
+
+        fn on_key(&mut self, key: &str) -> EnumControl
+        {
+            self.calls.push(format!("{}:", key));
+            return if self.skip_key == Some(key) { EnumControl::SKIP } else { EnumControl::CONTINUE };
+        }
+
+        fn on_value(&mut self, value: Rc<dyn RNode>) -> EnumControl
+        {
+            self.calls.push(value.get_node_type().to_string());
+            return if self.stop_at_string && value.get_node_type().to_string() == "STRING" { EnumControl::STOP } else { EnumControl::SKIP };
+        }
+
+        fn on_object_end(&mut self) -> EnumControl
+        {
+            self.calls.push(String::from("}"));
+            return EnumControl::CONTINUE;
+        }
+
+        fn on_array_start(&mut self) -> EnumControl
+        {
+            self.calls.push(String::from("["));
+            return if self.skip_arrays { EnumControl::SKIP } else { EnumControl::CONTINUE };
+        }
+
+        fn on_array_end(&mut self) -> EnumControl
+        {
+            self.calls.push(String::from("]"));
+            return EnumControl::CONTINUE;
+        }
+    }
+
+    fn drive(input: &str, handler: &mut RecordingHandler) -> bool
+    {
+        return JsonEvents::new_copy(&String::from(input), &ParserOptions::default()).drive(handler).unwrap();
+    }
+
+    const INPUT: &str = "{\"a\": [1, {\"b\": true}], \"c\": {\"d\": [null]}, \"e\": \"x\", \"f\": 2}";
+
+    #[test]
+    fn handler_gets_every_callback()
+    {
+        let mut handler = RecordingHandler::default();
+        assert!(drive(INPUT, &mut handler));
+        assert_eq!(handler.calls.join(" "), "{ a: [ DOUBLE { b: BOOL } ] c: { d: [ NULL ] } e: STRING f: DOUBLE }");
+    }
+
+    #[test]
+    fn handler_skips_subtrees()
+    {
+        let mut handler = RecordingHandler { skip_key: Some("c"), ..Default::default() };
+        assert!(drive(INPUT, &mut handler));
+        assert_eq!(handler.calls.join(" "), "{ a: [ DOUBLE { b: BOOL } ] c: e: STRING f: DOUBLE }");
+
+        let mut handler = RecordingHandler { skip_arrays: true, ..Default::default() };
+        assert!(drive(INPUT, &mut handler));
+        assert_eq!(handler.calls.join(" "), "{ a: [ c: { d: [ } e: STRING f: DOUBLE }");
+
+        // A skipped scalar is simply handled.
+        let mut handler = RecordingHandler { skip_key: Some("e"), ..Default::default() };
+        assert!(drive(INPUT, &mut handler));
+        assert_eq!(handler.calls.join(" "), "{ a: [ DOUBLE { b: BOOL } ] c: { d: [ NULL ] } e: f: DOUBLE }");
+    }
+
+    #[test]
+    fn handler_stops_early()
+    {
+        let mut handler = RecordingHandler { stop_at_string: true, ..Default::default() };
+        assert!(!drive(INPUT, &mut handler));
+        assert_eq!(handler.calls.join(" "), "{ a: [ DOUBLE { b: BOOL } ] c: { d: [ NULL ] } e: STRING");
+
+        // Nothing after the stop is even validated.
+        let mut handler = RecordingHandler { stop_at_string: true, ..Default::default() };
+        assert!(!drive("[\"x\" @", &mut handler));
+    }
+
+    #[test]
+    fn handler_skipped_subtree_is_still_validated()
+    {
+        let mut handler = RecordingHandler { skip_key: Some("a"), ..Default::default() };
+        let error = JsonEvents::new_copy(&String::from("{\"a\": [1 2], \"b\": 3}"), &ParserOptions::default()).drive(&mut handler).unwrap_err();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: ']', found: String::from("number 2") });
+        assert_eq!(handler.calls.join(" "), "{ a:");
+    }
+
+    #[test]
+    fn handler_skips_across_fed_chunks()
+    {
+        let mut handler = RecordingHandler { skip_key: Some("c"), ..Default::default() };
+        let mut events = JsonEvents::new_push(&ParserOptions::default());
+
+        for chunk in INPUT.as_bytes().chunks(1)
+        {
+            events.feed(chunk);
+            assert!(events.drive(&mut handler).unwrap());
+            assert!(events.needs_input());
+        }
+
+        events.end_input();
+        assert!(events.drive(&mut handler).unwrap());
+        assert!(!events.needs_input());
+        assert_eq!(handler.calls.join(" "), "{ a: [ DOUBLE { b: BOOL } ] c: e: STRING f: DOUBLE }");
+    }
+}
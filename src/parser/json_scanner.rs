@@ -0,0 +1,240 @@
+use crate::parser::embedded_json::EmbeddedJson;
+use crate::parser::lexer::Lexer;
+use crate::parser::parser::Parser;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+
+use std::collections::BTreeSet;
+use std::io::{Cursor, Read};
+
+/// Finds the JSON objects and arrays embedded in arbitrary text (ex. 'payload={"id": 1} done' in a
+/// log line), yielding each one along with where it is. Every '{' and '[' is tried as the start of
+/// a value; the text of a value that is found isn't searched again, so nested values aren't yielded
+/// on their own. Scalars are ignored, since almost any text has numbers and words in it.
+///
+/// ```text
+/// for found in JsonScanner::new_reader(std::io::stdin(), &options)
+/// {
+///     println!("line {}: {}..{}", found.line, found.start, found.end);
+/// }
+/// ```
+///
+/// A value must be valid according to the options (ex. a duplicate key is only accepted when the
+/// duplicate key policy allows it). When a '{' or '[' turns out not to start a value, the search
+/// carries on from the next character, so the values before the point it failed at are still found.
+/// The arrays/objects it left open would fail at that same point, so they are remembered and skipped
+/// rather than parsed again, which keeps the search linear. That includes the ones around a value
+/// nested deeper than 'max_depth', so only the complete values inside of them are found.
+pub struct JsonScanner
+{
+    parser: Parser,
+    /// Where to look for the next '{' or '['.
+    position: Snapshot,
+    /// Where the arrays/objects ahead of 'position' that are known to fail start.
+    failed_starts: BTreeSet<usize>,
+}
+
+impl JsonScanner
+{
+    pub fn new_copy(text: &str, options: &ParserOptions) -> Self
+    {
+        return Self::new_move(String::from(text), options);
+    }
+
+    /// Creates a scanner from raw bytes. Any invalid UTF-8 (which can't be part of a value) is
+    /// replaced byte for byte, so the offsets still match the original bytes.
+    pub fn new_bytes(text: Vec<u8>, options: &ParserOptions) -> Self
+    {
+        return Self::new_reader(Cursor::new(text), options);
+    }
+
+    pub fn new_move(text: String, options: &ParserOptions) -> Self
+    {
+        return Self::new_parser(Parser::new_move(text, options));
+    }
+
+    /// Creates a scanner that reads the text from 'source' as it goes, only holding on to the part
+    /// being searched rather than all of it (ex. for a log piped to std input). Any invalid UTF-8 is
+    /// replaced like with 'new_bytes'.
+    pub fn new_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
+    {
+        return Self::new_parser(Parser::new_lexer(Lexer::new_reader_lossy(source, options)));
+    }
+
+    fn new_parser(parser: Parser) -> Self
+    {
+        Self { parser, position: Snapshot::new(0, 1, 1), failed_starts: BTreeSet::new() }
+    }
+
+    /// Gets the text being searched. For a streamed text this is only the part still being held,
+    /// which starts at the byte offset 'Parser::get_input_start'.
+    pub fn get_text(&self) -> &String
+    {
+        return self.parser.get_input();
+    }
+}
+
+impl Iterator for JsonScanner
+{
+    type Item = EmbeddedJson;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while let Some(candidate) = self.parser.find_from(&self.position, |ch| ch == '{' || ch == '[')
+        {
+            let start = candidate.get_start_pos();
+            // The '{' and '[' are a single byte, so the next character is just past it.
+            self.position = Snapshot::new(start + 1, candidate.get_line(), candidate.get_column() + 1);
+
+            if self.failed_starts.remove(&start)
+            {
+                continue;
+            }
+
+            // Hold on to the text from here on, since the search goes back to it when the value fails.
+            self.parser.hold_from(Some(start));
+            let result = self.parser.parse_value_at(&candidate);
+            self.parser.hold_from(None);
+
+            match result
+            {
+                Ok(node) =>
+                {
+                    self.position = self.parser.snap();
+                    self.failed_starts = self.failed_starts.split_off(&self.position.get_start_pos());
+
+                    return Some(EmbeddedJson
+                    {
+                        node, start, end: self.position.get_start_pos(), line: candidate.get_line(), column: candidate.get_column(),
+                    });
+                },
+                // Not a value after all, so carry on from the next character (skipping what was left open).
+                Err(_) => { self.failed_starts.extend(self.parser.get_open_starts().iter().filter(|open_start| **open_start > start)); },
+            }
+        }
+
+        self.position = self.parser.snap();
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::json_scanner::JsonScanner;
+    use crate::parser::parser_options::ParserOptions;
+
+    use std::io::Cursor;
+
+    /// Gets the text of each value found along with its line and column.
+    fn scan(text: &str, options: &ParserOptions) -> Vec<(String, usize, usize)>
+    {
+        let scanner = JsonScanner::new_copy(&String::from(text), options);
+        let text = scanner.get_text().clone();
+        return scanner.map(|found| (String::from(&text[found.start..found.end]), found.line, found.column)).collect();
+    }
+
+    #[test]
+    fn scanner_finds_embedded_values()
+    {
+        let text = "2024-01-01 INFO payload={\"id\":1,\"tags\":[\"a\",\"b\"]} done\n\
+                    2024-01-01 WARN retrying [attempt 2] after {\"error\": {\"code\": 503}}\n\
+                    caf\u{e9} [1, 2,\n 3] {not json} [] {\"unclosed\": [1, 2}";
+
+        assert_eq!(scan(text, &ParserOptions::default()), vec![
+            (String::from("{\"id\":1,\"tags\":[\"a\",\"b\"]}"), 1, 25),
+            (String::from("{\"error\": {\"code\": 503}}"), 2, 44),
+            (String::from("[1, 2,\n 3]"), 3, 6),
+            (String::from("[]"), 4, 16),
+        ]);
+    }
+
+    #[test]
+    fn scanner_byte_ranges()
+    {
+        let text = String::from("\u{e9}t\u{e9} {\"k\": \"{[\"} x");
+        let found: Vec<(usize, usize, usize)> = JsonScanner::new_copy(&text, &ParserOptions::default()).map(|found| (found.start, found.end, found.column)).collect();
+        assert_eq!(found, vec![ (6, 17, 5) ]);
+
+        // Invalid UTF-8 is replaced without moving anything after it.
+        let mut bytes = b"\xFF\xFElog ".to_vec();
+        bytes.extend_from_slice(b"[true] \xC3 {}");
+        let found: Vec<(usize, usize)> = JsonScanner::new_bytes(bytes, &ParserOptions::default()).map(|found| (found.start, found.end)).collect();
+        assert_eq!(found, vec![ (6, 12), (15, 17) ]);
+    }
+
+    #[test]
+    fn scanner_uses_options()
+    {
+        let text = "first {\"a\": 1, \"a\": 2} then {a: [1, 2,]}";
+        let options = ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR).build();
+        let found: Vec<String> = scan(text, &options).into_iter().map(|(found, _, _)| found).collect();
+        assert!(found.is_empty(), "{:?}", found);
+
+        let options = ParserOptions::builder().dialect(EnumDialect::JSON5).build();
+        let found: Vec<String> = scan(text, &options).into_iter().map(|(found, _, _)| found).collect();
+        assert_eq!(found, vec![ "{\"a\": 1, \"a\": 2}", "{a: [1, 2,]}" ]);
+    }
+
+    #[test]
+    fn scanner_skips_the_text_of_failed_values()
+    {
+        // The search carries on from where a value failed, so each '[' isn't parsed up to the end again.
+        let depth = 200000;
+        let text = format!("{}{}", "[".repeat(depth), " x [1]");
+        let found: Vec<String> = scan(&text, &ParserOptions::builder().max_depth(None).build()).into_iter().map(|(found, _, _)| found).collect();
+        assert_eq!(found, vec![ "[1]" ]);
+
+        let text = format!("{}{}", "{\"a\": [".repeat(depth), "\n{}");
+        assert_eq!(scan(&text, &ParserOptions::default()), vec![ (String::from("{}"), 2, 1) ]);
+
+        // A value nested in one that fails after it is still found.
+        let found: Vec<String> = scan("[[1], x] {\"b\": [2]}", &ParserOptions::default()).into_iter().map(|(found, _, _)| found).collect();
+        assert_eq!(found, vec![ "[1]", "{\"b\": [2]}" ]);
+    }
+
+    #[test]
+    fn scanner_finds_values_inside_of_failed_ones()
+    {
+        // The '{' in the quotes starts a string that runs into the payload before failing.
+        assert_eq!(scan("msg=\"{\" payload={\"id\":1}\n", &ParserOptions::default()), vec![ (String::from("{\"id\":1}"), 1, 17) ]);
+        assert_eq!(scan("x {\"a\":{\"b\":1},\"c\":", &ParserOptions::default()), vec![ (String::from("{\"b\":1}"), 1, 8) ]);
+
+        // Even when the failed value holds more text than is read at a time.
+        let mut text = String::from("[");
+
+        for i in 0..20000
+        {
+            text.push_str(&format!("{{\"id\": {}}}, ", i));
+        }
+
+        text.push_str("x] [true]");
+        let found: Vec<String> = JsonScanner::new_reader(Cursor::new(text.into_bytes()), &ParserOptions::default()).map(|found| found.node.get_node_type().to_string()).collect();
+        assert_eq!(found.len(), 20001);
+        assert_eq!(found.last().unwrap(), "ARRAY");
+    }
+
+    #[test]
+    fn scanner_reads_streamed_text()
+    {
+        let mut text = String::new();
+
+        for i in 0..20000
+        {
+            text.push_str(&format!("{} caf\u{e9} [{}, \"{{\"] {{\"id\": {}}} [x\n", i, i, i));
+        }
+
+        let expected: Vec<(usize, usize, usize, usize)> = JsonScanner::new_copy(&text, &ParserOptions::default()).map(|found| (found.start, found.end, found.line, found.column)).collect();
+        assert_eq!(expected.len(), 40000);
+
+        let mut scanner = JsonScanner::new_reader(Cursor::new(text.into_bytes()), &ParserOptions::default());
+        let found: Vec<(usize, usize, usize, usize)> = scanner.by_ref().map(|found| (found.start, found.end, found.line, found.column)).collect();
+        assert_eq!(found, expected);
+
+        // Only the end of the text is still held.
+        assert!(scanner.parser.get_input_start() > 0);
+        assert!(scanner.next().is_none());
+    }
+}
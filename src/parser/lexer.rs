@@ -1,57 +1,215 @@
-#[allow(unused_imports)]
+use crate::parser::dialect::EnumDialect;
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parser_options::ParserOptions;
 use crate::parser::snapshot::Snapshot;
+use crate::parser::surrogate_policy::{combine_surrogates, is_high_surrogate, is_low_surrogate, EnumSurrogatePolicy};
 use crate::parser::token::TokenTrait;
 use crate::parser::token_bool::TokenBool;
 use crate::parser::token_double::TokenDouble;
 use crate::parser::token_string::TokenString;
 use crate::parser::token_symbol::TokenSymbol;
+use crate::utils::float_utils::{format_f64, parse_f64};
 use crate::utils::string_utils::StringBuilder;
 
 use std::collections::hash_map::HashMap;
+use std::io::{ErrorKind, Read};
 use std::rc::Rc;
 
 use super::token_null::TokenNull;
 
+/// How many bytes are read from a streamed input at a time.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// What each byte of invalid UTF-8 becomes with 'new_reader_lossy' (an ASCII control character, so a byte for a byte).
+const SUBSTITUTE_CHAR: char = '\u{1A}';
+
+/// How far a string was lexed before running out of fed input: the position after the last complete
+/// character (or escape) along with what was unescaped up to there.
+struct StringResume
+{
+    token_start: usize,
+    position: Snapshot,
+    buffer: StringBuilder,
+    byte_len: usize,
+    lone_surrogates: Vec<(usize, u16)>,
+    unicode_escapes: Vec<usize>,
+}
+
+/// Lexes the token starting with the given (already consumed) character.
+type TokenHandler = fn(&mut Lexer, char) -> Result<Rc<dyn TokenTrait>, ParseError>;
+
 pub struct Lexer
 {
+    /// The text being lexed. For a streamed input this is only a window of it, starting at the
+    /// byte offset 'window_start' (positions are always offsets into the whole input).
     input: String,
-    stringify: bool,
+    window_start: usize,
+    /// The streamed input, until it has been read completely.
+    source: Option<Box<dyn Read>>,
+    /// Bytes read from 'source' that don't form a complete UTF-8 sequence yet.
+    pending: Vec<u8>,
+    /// The position where the last 'next_token' started, before which a streamed input can be discarded.
+    keep_from: usize,
+    /// A position before 'keep_from' whose text is still needed (see 'hold_from').
+    held_from: Option<usize>,
+    /// More input can still be fed (see 'feed'), so running out of it isn't the end of the input.
+    awaiting_input: bool,
+    /// Whether the lexer ran out of fed input since the last 'take_starved'.
+    starved: bool,
+    /// A string that ran out of fed input, to continue from once it is lexed again.
+    string_resume: Option<StringResume>,
+    /// Byte offset and length of the first invalid UTF-8 sequence in the original input (if any).
+    /// Only the valid prefix is kept in 'input'.
+    invalid_utf8: Option<(usize, usize)>,
+    /// Whether invalid UTF-8 read from 'source' is replaced rather than ending the input (see 'new_reader_lossy').
+    replace_invalid_utf8: bool,
+    /// Why a streamed input stopped short of its end (ex. a read failure), reported once it is reached.
+    end_error: Option<ParseError>,
+    /// The size (in bytes) of the original input, including any invalid UTF-8 (for a streamed
+    /// input, the bytes read so far).
+    input_size: usize,
+    options: ParserOptions,
+    /// The quote that opened the string being lexed (a '\'' is only possible with JSON5).
+    string_quote: char,
     index: usize,
-    lookup_table: HashMap<char, fn(&mut Lexer, char) -> Result<Rc<dyn TokenTrait>, String>>,
+    line: usize,
+    column: usize,
+    token_start: Snapshot,
+    lookup_table: HashMap<char, TokenHandler>,
     escape_char_table: HashMap<char, char>,
     buffer: StringBuilder,
+    /// The size (in bytes) of the string being lexed so far, checked against 'max_string_length' as it grows.
+    string_byte_len: usize,
+    /// The lone surrogates preserved in the string being lexed, as (index in 'buffer', code unit).
+    lone_surrogates: Vec<(usize, u16)>,
+    /// Where the '\uXXXX' escapes kept in the string being lexed (when not stringifying) start in 'buffer'.
+    unicode_escapes: Vec<usize>,
 }
 
 impl Lexer
 {
-    pub fn new_copy(input: &String, stringify: bool) -> Self
+    pub fn new_copy(input: &str, options: &ParserOptions) -> Self
+    {
+        return Self::new_move(String::from(input), options);
+    }
+
+    /// Creates a Lexer from raw bytes. Invalid UTF-8 is reported as an error once the lexer reaches it.
+    pub fn new_bytes(input: Vec<u8>, options: &ParserOptions) -> Self
+    {
+        match String::from_utf8(input)
+        {
+            Ok(input) => { return Self::new_move(input, options); },
+            Err(error) =>
+            {
+                let valid_up_to = error.utf8_error().valid_up_to();
+                let error_len = error.utf8_error().error_len().unwrap_or(error.as_bytes().len() - valid_up_to);
+                let input_size = error.as_bytes().len();
+                let mut bytes = error.into_bytes();
+                bytes.truncate(valid_up_to);
+
+                let mut result = Self::new_move(String::from_utf8(bytes).expect("Prefix should be valid UTF-8"), options);
+                result.invalid_utf8 = Some((valid_up_to, error_len));
+                result.input_size = input_size;
+                return result;
+            },
+        }
+    }
+
+    pub fn new_move(input: String, options: &ParserOptions) -> Self
     {
+        let input_size = input.len();
         let mut result = Self
         {
-            input: input.clone(), stringify: stringify, index: 0,
+            input, window_start: 0, source: None, pending: Vec::new(), keep_from: 0, held_from: None, awaiting_input: false, starved: false, string_resume: None,
+            invalid_utf8: None, replace_invalid_utf8: false, end_error: None, input_size, options: options.clone(), string_quote: '"', index: 0, line: 1, column: 1,
+            token_start: Snapshot::new(0, 1, 1),
             lookup_table: HashMap::new(), escape_char_table: HashMap::new(),
-            buffer: StringBuilder::new(4096)
+            buffer: StringBuilder::new(4096), string_byte_len: 0, lone_surrogates: Vec::new(), unicode_escapes: Vec::new()
         };
 
         result.init_table();
 
+        if options.get_dialect() == EnumDialect::JSON5
+        {
+            result.init_json5_table();
+        }
+
         return result;
     }
 
-    pub fn new_move(input: String, stringify: bool) -> Self
+    /// Creates a Lexer that reads its input from 'source' as it goes, only holding on to the text
+    /// of the last couple of tokens rather than the whole input (ex. for a pipe or a huge file).
+    /// Invalid UTF-8 and read failures are reported as errors once the lexer reaches them.
+    pub fn new_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
     {
-        let mut result = Self
-        {
-            input: input, stringify: stringify, index: 0,
-            lookup_table: HashMap::new(), escape_char_table: HashMap::new(),
-            buffer: StringBuilder::new(4096)
-        };
+        let mut result = Self::new_move(String::new(), options);
+        result.source = Some(Box::new(source));
 
-        result.init_table();
+        // Reading ahead means an input that fits in one chunk is already known to be over the 'max_input_size' limit.
+        result.fill();
+        return result;
+    }
+
+    /// Same as 'new_reader', except that each byte of invalid UTF-8 is replaced by a substitute
+    /// character ('\u{1A}') instead of ending the input, so the offsets of everything after it still
+    /// match the original bytes (ex. to scan a log for JSON, see 'JsonScanner').
+    pub fn new_reader_lossy(source: impl Read + 'static, options: &ParserOptions) -> Self
+    {
+        let mut result = Self::new_move(String::new(), options);
+        result.source = Some(Box::new(source));
+        result.replace_invalid_utf8 = true;
+
+        result.fill();
+        return result;
+    }
 
+    /// Creates a Lexer whose input is handed to it in chunks with 'feed', followed by 'end_input'.
+    /// Until then, running out of input marks the lexer as starved (see 'take_starved') instead of
+    /// being the end of the input.
+    pub fn new_push(options: &ParserOptions) -> Self
+    {
+        let mut result = Self::new_move(String::new(), options);
+        result.awaiting_input = true;
         return result;
     }
 
+    /// Adds the next chunk of input. A chunk can end anywhere, including in the middle of a
+    /// token or a UTF-8 sequence.
+    pub fn feed(&mut self, chunk: &[u8])
+    {
+        if !self.awaiting_input
+        {
+            return;
+        }
+
+        self.discard_consumed();
+        self.pending.extend_from_slice(chunk);
+        self.decode_pending(chunk.len());
+    }
+
+    /// Marks the end of the fed input.
+    pub fn end_input(&mut self)
+    {
+        if self.awaiting_input
+        {
+            self.awaiting_input = false;
+            self.end_pending();
+        }
+    }
+
+    /// Checks whether the lexer ran out of fed input since the last 'take_starved' (so that what was
+    /// lexed since then may be incomplete).
+    pub fn is_starved(&self) -> bool
+    {
+        return self.starved;
+    }
+
+    /// Same as 'is_starved', but also clears it for the next attempt.
+    pub fn take_starved(&mut self) -> bool
+    {
+        return std::mem::take(&mut self.starved);
+    }
+
     fn init_table(&mut self)
     {
         self.lookup_table.insert('.', handle_number);
@@ -64,12 +222,12 @@ impl Lexer
         self.lookup_table.insert('[', handle_single_char_symbol);
         self.lookup_table.insert(']', handle_single_char_symbol);
 
-        for ch in 'A'..'Z'
+        for ch in 'A'..='Z'
         {
             self.lookup_table.insert(ch, handle_symbol);
         }
 
-        for ch in 'a'..'z'
+        for ch in 'a'..='z'
         {
             self.lookup_table.insert(ch, handle_symbol);
         }
@@ -89,41 +247,142 @@ impl Lexer
         self.escape_char_table.insert('t', '\t');
     }
 
-    #[allow(dead_code)]
+    /// The extra characters JSON5 accepts on top of 'init_table'.
+    fn init_json5_table(&mut self)
+    {
+        self.lookup_table.insert('\'', handle_string);
+        self.lookup_table.insert('+', handle_number);
+        self.lookup_table.insert('/', handle_bad_comment);
+        self.lookup_table.insert('$', handle_symbol);
+        self.lookup_table.insert('_', handle_symbol);
+
+        self.escape_char_table.insert('\'', '\'');
+        self.escape_char_table.insert('v', '\x0B');
+    }
+
+    pub fn get_options(&self) -> &ParserOptions
+    {
+        return &self.options;
+    }
+
+    /// Gets the text being lexed. For a streamed input this is only the part still being held,
+    /// which starts at the byte offset 'get_input_start'.
     pub fn get_input(&self) -> &String
     {
         return &self.input;
     }
 
-    #[allow(dead_code)]
-    fn get_position(&self) -> usize
+    /// Gets the byte offset (into the whole input) of the start of 'get_input'.
+    pub fn get_input_start(&self) -> usize
+    {
+        return self.window_start;
+    }
+
+    /// Gets the size (in bytes) of the original input, including any invalid UTF-8 cut from 'get_input'.
+    /// For a streamed input, this is the size of what has been read so far.
+    pub fn get_input_size(&self) -> usize
+    {
+        return self.input_size;
+    }
+
+    pub fn get_position(&self) -> usize
     {
         return self.index;
     }
 
-    pub fn next_token(&mut self) -> Result<Rc<dyn TokenTrait>, String>
+    /// Gets the position of the start of the last token returned (or attempted) by 'next_token'.
+    pub fn get_token_start(&self) -> &Snapshot
+    {
+        return &self.token_start;
+    }
+
+    /// Creates a ParseError spanning from the start of the last token to the current position.
+    pub fn token_error(&self, kind: EnumParseErrorKind) -> ParseError
+    {
+        return ParseError::new(kind, &self.token_start, self.index);
+    }
+
+    /// Moves up to the next character 'is_target' accepts (without consuming it), letting a streamed
+    /// input discard the text skipped. Returns false when the input ends first.
+    pub fn skip_until(&mut self, is_target: fn(char) -> bool) -> bool
+    {
+        while let Some(ch) = self.peek_char()
+        {
+            if is_target(ch)
+            {
+                return true;
+            }
+
+            self.advance(ch);
+            self.keep_from = self.index;
+        }
+
+        return false;
+    }
+
+    /// Keeps the text of a streamed input from 'position' on while lexing past it (ex. so it can be
+    /// restored to once a value fails to parse), until called again with None.
+    pub fn hold_from(&mut self, position: Option<usize>)
+    {
+        self.held_from = position;
+    }
+
+    /// Skips any whitespace and checks whether the whole input has been consumed.
+    pub fn is_at_end(&mut self) -> bool
+    {
+        let _ = self.skip_whitespace();
+        return self.peek_char().is_none() && !self.awaiting_input && self.invalid_utf8.is_none() && self.end_error.is_none();
+    }
+
+    pub fn next_token(&mut self) -> Result<Rc<dyn TokenTrait>, ParseError>
     {
-        if self.index < self.input.len()
+        self.keep_from = self.index;
+
+        if self.peek_char().is_some()
         {
             return self.next_token_internal();
         }
 
-        return Err("Out of tokens".to_string());
+        self.token_start = self.snap();
+        return Err(self.eof_error(EnumParseErrorKind::UnexpectedEOF));
+    }
+
+    /// Creates the error for running out of input, which is an InvalidUtf8 error when the input
+    /// was cut short by an invalid UTF-8 sequence.
+    fn eof_error(&self, kind: EnumParseErrorKind) -> ParseError
+    {
+        if let Some((start, len)) = self.invalid_utf8
+        {
+            return ParseError::new(EnumParseErrorKind::InvalidUtf8, &self.snap(), start + len);
+        }
+
+        if let Some(error) = &self.end_error
+        {
+            return error.clone();
+        }
+
+        return self.token_error(kind);
     }
 
+    /// Goes back to a position taken by 'snap'. With a streamed input, the text before the start of
+    /// the last 'next_token' may have been discarded, so the position can't be any earlier than that.
     pub fn restore(&mut self, snapshot: &Snapshot)
     {
+        debug_assert!(snapshot.get_start_pos() >= self.window_start, "Restoring text that has been discarded");
         self.index = snapshot.get_start_pos();
+        self.line = snapshot.get_line();
+        self.column = snapshot.get_column();
     }
 
     pub fn snap(&self) -> Snapshot
     {
-        return Snapshot::new(self.index);
+        return Snapshot::new(self.index, self.line, self.column);
     }
 
-    fn next_token_internal(&mut self) -> Result<Rc<dyn TokenTrait>, String>
+    fn next_token_internal(&mut self) -> Result<Rc<dyn TokenTrait>, ParseError>
     {
         let _ = self.skip_whitespace();
+        self.token_start = self.snap();
         let ch = self.next_char();
 
         match ch
@@ -133,9 +392,12 @@ impl Lexer
                 // Clear the buffer to make sure there isn't any junk in it.
                 // We do this here to the handler functions do not need to worry.
                 self.buffer.clear();
+                self.string_byte_len = 0;
+                self.lone_surrogates.clear();
+                self.unicode_escapes.clear();
 
                 // println!("{0}", the_char);
-                let lookup_func: Option<&fn(&mut Lexer, char) -> Result<Rc<dyn TokenTrait>, String>> = self.lookup_table.get(&the_char);
+                let lookup_func: Option<&TokenHandler> = self.lookup_table.get(&the_char);
 
                 match lookup_func
                 {
@@ -144,210 +406,699 @@ impl Lexer
                         let result = func(self, the_char);
                         return result;
                     },
-                    None => { return Err(String::from("Error")); },
+                    // JSON5 identifiers can start with any unicode letter, which are too many for the table.
+                    None if self.options.get_dialect() == EnumDialect::JSON5 && the_char.is_alphabetic() => { return handle_symbol(self, the_char); },
+                    None => { return Err(self.token_error(EnumParseErrorKind::UnexpectedCharacter(the_char))); },
                 }
             },
-            None => { return Err(String::from("End of input (EOF)")); },
+            None => { return Err(self.eof_error(EnumParseErrorKind::UnexpectedEOF)); },
         }
     }
 
     fn next_char(&mut self) -> Option<char>
     {
-        let chars = &self.input.as_bytes();
+        let opt_ch = self.peek_char();
+
+        if let Some(ch) = opt_ch
+        {
+            self.advance(ch);
+        }
+
+        return opt_ch;
+    }
 
-        if self.index < chars.len()
+    /// Gets the character at the current position without consuming it (reading more of a streamed input if needed).
+    pub fn peek_char(&mut self) -> Option<char>
+    {
+        while self.index - self.window_start >= self.input.len()
         {
-            let ch = chars[self.index];
-            self.index += 1;
-            return Some(ch as char);
+            if !self.fill()
+            {
+                self.starved |= self.awaiting_input;
+                return None;
+            }
         }
 
-        return None;
+        return self.input[self.index - self.window_start..].chars().next();
     }
 
-    fn put_back(&mut self, delta: usize)
+    /// Reads the next chunk of a streamed input into 'input'. Returns false when there is nothing more to read.
+    fn fill(&mut self) -> bool
     {
-        if delta > self.index
+        if self.source.is_none()
         {
-            self.index = 0;
+            return false;
         }
 
-        else
+        self.discard_consumed();
+
+        let pending_len = self.pending.len();
+        self.pending.resize(pending_len + READ_CHUNK_SIZE, 0);
+
+        let source = self.source.as_mut().expect("The source was checked above");
+        let result = loop
+        {
+            match source.read(&mut self.pending[pending_len..])
+            {
+                Err(error) if error.kind() == ErrorKind::Interrupted => { continue; },
+                result => { break result; },
+            }
+        };
+
+        match result
         {
-            self.index -= delta;
+            Ok(0) =>
+            {
+                self.pending.truncate(pending_len);
+                self.source = None;
+                self.end_pending();
+                return false;
+            },
+            Ok(read) =>
+            {
+                self.pending.truncate(pending_len + read);
+                return self.decode_pending(read);
+            },
+            Err(error) =>
+            {
+                self.pending.truncate(pending_len);
+                self.end_error = Some(ParseError::new(EnumParseErrorKind::ReadFailed(error.to_string()), &self.snap(), self.index));
+                self.source = None;
+                return false;
+            },
         }
     }
 
-    fn skip_whitespace(&mut self) -> usize
+    /// Discards the text of a streamed input before 'keep_from' (or the current position, when it was
+    /// restored to before that, or the position being held) once there is enough of it.
+    fn discard_consumed(&mut self)
+    {
+        let keep_from = self.keep_from.min(self.index).min(self.held_from.unwrap_or(usize::MAX));
+
+        if keep_from - self.window_start >= READ_CHUNK_SIZE
+        {
+            self.input.drain(..keep_from - self.window_start);
+            self.window_start = keep_from;
+        }
+    }
+
+    /// Counts the last 'added' bytes of 'pending' towards the input size and moves its complete UTF-8
+    /// sequences to 'input'. Returns false when this cuts the input short (ex. it is invalid UTF-8).
+    fn decode_pending(&mut self, added: usize) -> bool
     {
-        // TODO: Don't use as_bytes because of UTF
-        let mut delta: usize = self.index;
-        let chars = &self.input.as_bytes();
-        let mut ch;
+        self.input_size += added;
+
+        if let Some(limit) = self.options.get_max_input_size().filter(|limit| self.input_size > *limit)
+        {
+            // The same error as for an input that is known to be too large up front.
+            self.end_error = Some(ParseError::new(EnumParseErrorKind::InputTooLarge { limit }, &Snapshot::new(0, 1, 1), self.input_size));
+            self.pending.clear();
+            self.close_input();
+            return false;
+        }
 
         loop
         {
-            if self.index >= chars.len()
+            let (valid_up_to, error_len) = match std::str::from_utf8(&self.pending)
             {
-                return 0;
-            }
+                Ok(_) => (self.pending.len(), None),
+                Err(error) => (error.valid_up_to(), error.error_len()),
+            };
 
-            ch = chars[self.index];
+            self.input.push_str(std::str::from_utf8(&self.pending[..valid_up_to]).expect("Prefix should be valid UTF-8"));
+            self.pending.drain(..valid_up_to);
 
-            if !ch.is_ascii_whitespace()
+            match error_len
             {
-                break;
+                Some(len) if self.replace_invalid_utf8 =>
+                {
+                    self.input.extend(std::iter::repeat_n(SUBSTITUTE_CHAR, len));
+                    self.pending.drain(..len);
+                },
+                Some(len) =>
+                {
+                    self.invalid_utf8 = Some((self.window_start + self.input.len(), len));
+                    self.pending.clear();
+                    self.close_input();
+                    return true;
+                },
+                None => { return true; },
             }
-
-            self.index += 1;
         }
+    }
 
-        if self.index >= chars.len()
+    /// Reports the bytes of a UTF-8 sequence that the input ended in the middle of as invalid.
+    fn end_pending(&mut self)
+    {
+        if !self.pending.is_empty() && self.replace_invalid_utf8
         {
-            return 0;
+            self.input.extend(std::iter::repeat_n(SUBSTITUTE_CHAR, self.pending.len()));
+            self.pending.clear();
         }
 
-        // Note: delta is already set to the start position, so we reuse it here
-        // for this calculation.
-        delta = self.index - delta;
-        return delta;
+        else if !self.pending.is_empty()
+        {
+            self.invalid_utf8 = Some((self.input_size - self.pending.len(), self.pending.len()));
+            self.pending.clear();
+        }
     }
-}
 
-fn decode_char(ch: char) -> u32
-{
-    match ch
+    /// Stops taking more input, once it has been cut short.
+    fn close_input(&mut self)
     {
-        '0'..='9' => { return (ch as u32) - ('0' as u32) },
-        'a'..='f' => { return (ch as u32) - ('a' as u32) + 10},
-        'A'..='F' => { return (ch as u32) - ('A' as u32) + 10},
-        _ => { assert!(false); }
+        self.source = None;
+        self.awaiting_input = false;
     }
 
-    return 0;
-}
+    /// Moves past 'ch' (which must be the character at the current position) while
+    /// keeping track of the line and column.
+    fn advance(&mut self, ch: char)
+    {
+        self.index += ch.len_utf8();
 
-fn handle_number(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, String>
-{
-    inst.buffer.append_char(ch);
-    let mut seen_dot = false;
-    let mut seen_e = false;
-    let mut seen_neg = false;
-    let mut seen_number = ch >= '0' && ch <= '9';
+        if ch == '\n'
+        {
+            self.line += 1;
+            self.column = 1;
+        }
 
-    loop
+        else
+        {
+            self.column += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> usize
     {
-        let cur_char_opt = inst.next_char();
+        let start = self.index;
 
-        match cur_char_opt
+        while let Some(ch) = self.peek_char()
         {
-            Some(cur_char) =>
+            if self.options.get_dialect() == EnumDialect::JSON5
             {
-                if cur_char >= '0' && cur_char <= '9'
+                // JSON5 also allows any unicode whitespace (plus the byte order mark) and comments.
+                if ch == '/' && self.skip_comment()
                 {
-                    seen_number = true;
-                    inst.buffer.append_char(cur_char);
+                    continue;
                 }
 
-                else if cur_char == '.'
+                else if !ch.is_whitespace() && ch != '\u{FEFF}'
                 {
-                    if seen_dot
-                    {
-                        return Err(String::from("Error parsing number where a second '.' was discovered"));
-                    }
-
-                    seen_dot = true;
-                    inst.buffer.append_char(cur_char);
+                    break;
                 }
+            }
 
-                else if cur_char == 'e' || cur_char == 'E'
-                {
-                    if seen_e
-                    {
-                        return Err(String::from("Error parsing number where a second 'e' was discovered"));
-                    }
+            else if !ch.is_ascii_whitespace()
+            {
+                break;
+            }
 
-                    seen_e = true;
-                    inst.buffer.append_char(cur_char);
-                }
+            self.advance(ch);
+        }
 
-                else if cur_char == '-'
-                {
-                    if !seen_e
-                    {
-                        return Err(String::from("Error parsing number where a '-' was discovered before 'e'"));
-                    }
+        if self.peek_char().is_none()
+        {
+            return 0;
+        }
 
-                    else if seen_neg
-                    {
-                        return Err(String::from("Error parsing number where a second '-' was discovered"));
-                    }
+        return self.index - start;
+    }
+}
 
-                    seen_neg = true;
-                    inst.buffer.append_char(cur_char);
+impl Lexer
+{
+    /// Skips a '// ...' or '/* ... */' comment at the current position. Returns false when there
+    /// isn't one, which includes a '/*' that is never closed (left for 'handle_bad_comment' to report).
+    fn skip_comment(&mut self) -> bool
+    {
+        let start = self.snap();
+        self.advance('/');
+
+        match self.next_char()
+        {
+            Some('/') =>
+            {
+                while let Some(ch) = self.peek_char().filter(|ch| !is_line_terminator(*ch))
+                {
+                    self.advance(ch);
                 }
 
-                else
+                return true;
+            },
+            Some('*') =>
+            {
+                // The '*' of the '/*' can't also be the one of the '*/'.
+                let mut last = ' ';
+
+                while let Some(ch) = self.next_char()
                 {
-                    inst.put_back(1);
-                    break;
+                    if ch == '/' && last == '*'
+                    {
+                        return true;
+                    }
+
+                    last = ch;
                 }
             },
-            None => { /*println!("Logic error in handle_symbol while getting the next char");*/ break; },
+            _ => {},
         }
+
+        self.restore(&start);
+        return false;
     }
+}
+
+/// Checks for a character that ends a line in JSON5 (and so ends a '//' comment).
+fn is_line_terminator(ch: char) -> bool
+{
+    return matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+}
 
-    if inst.buffer.empty()
+/// A '/' that 'skip_whitespace' didn't skip isn't the start of a valid comment.
+fn handle_bad_comment(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    if inst.peek_char() != Some('*')
     {
-        return Err(String::from("Logic error in handling symbol"));
+        return Err(inst.token_error(EnumParseErrorKind::UnexpectedCharacter(ch)));
     }
 
-    else if !seen_number
+    // The comment runs to the end of the input.
+    while inst.next_char().is_some() {}
+
+    return Err(inst.token_error(EnumParseErrorKind::UnterminatedComment));
+}
+
+fn decode_char(ch: char) -> u32
+{
+    match ch
     {
-        // TODO: print out said input would be helpful...
-        return Err(String::from("Did not see a number while lexing input"));
+        '0'..='9' => { return (ch as u32) - ('0' as u32) },
+        'a'..='f' => { return (ch as u32) - ('a' as u32) + 10},
+        'A'..='F' => { return (ch as u32) - ('A' as u32) + 10},
+        _ => { unreachable!("'{}' is not a hexadecimal digit", ch); }
     }
+}
 
-    let output = inst.buffer.to_string();
-    // println!("debug output: {0}, {1}", output, output.len());
+fn handle_number(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    inst.buffer.append_char(ch);
+    check_number_length(inst)?;
 
-    let opt_num = to_num(output);
+    if inst.options.get_dialect() == EnumDialect::JSON5
+    {
+        return handle_json5_number(inst, ch);
+    }
+
+    else if inst.options.get_lenient_numbers()
+    {
+        scan_lenient_number(inst, ch)?;
+    }
 
-    if opt_num.is_some()
+    else
     {
-        return Ok(Rc::new(TokenDouble::new(opt_num.unwrap())));
+        scan_strict_number(inst, ch)?;
     }
 
-    return Err(String::from("Error parsing number"));
+    let output = inst.buffer.to_string();
+    // println!("debug output: {0}, {1}", output, output.len());
+
+    match parse_f64(&output)
+    {
+        Some(num) => { return Ok(Rc::new(TokenDouble::new_lexeme(num, output))); },
+        None => { return Err(inst.token_error(EnumParseErrorKind::InvalidNumber(String::from("malformed number")))); },
+    }
 }
 
-fn handle_string(inst: &mut Lexer, _ch: char) -> Result<Rc<dyn TokenTrait>, String>
+/// Scans the rest of a number following the RFC 8259 grammar:
+///
+/// ```text
+/// number = [ '-' ] int [ frac ] [ exp ]
+/// int    = '0' / ( digit1-9 *digit )
+/// frac   = '.' 1*digit
+/// exp    = ( 'e' / 'E' ) [ '-' / '+' ] 1*digit
+/// ```
+fn scan_strict_number(inst: &mut Lexer, ch: char) -> Result<(), ParseError>
 {
-    // We don't add it to the buffer because we want to remove it and just get
-    // the raw value.
-    // inst.buffer.append_char(ch);
-    let mut last_was_escape = false;
-    let mut saw_close_quote = false;
+    let mut first_digit = ch;
 
-    loop
+    if ch == '.'
     {
-        let cur_char_opt = inst.next_char();
+        return Err(number_error(inst, "expected a digit before '.' (ex. '0.5' rather than '.5')"));
+    }
 
-        match cur_char_opt
+    else if ch == '-'
+    {
+        match inst.peek_char()
         {
-            Some(cur_char) =>
+            Some(next_char) if next_char.is_ascii_digit() =>
             {
-                // Handle escapes
-                if cur_char == '\\'
-                {
-                    if last_was_escape
-                    {
-                        let opt_escape_char = inst.escape_char_table.get(&cur_char);
+                consume_number_char(inst, next_char)?;
+                first_digit = next_char;
+            },
+            _ => { return Err(number_error(inst, "expected a digit after '-'")); },
+        }
+    }
 
-                        match opt_escape_char
-                        {
+    if first_digit == '0'
+    {
+        if let Some(next_char) = inst.peek_char().filter(|next_char| next_char.is_ascii_digit())
+        {
+            consume_number_char(inst, next_char)?;
+            return Err(number_error(inst, "leading zeros are not allowed"));
+        }
+    }
+
+    else
+    {
+        scan_digits(inst)?;
+    }
+
+    if inst.peek_char() == Some('.')
+    {
+        consume_number_char(inst, '.')?;
+
+        if scan_digits(inst)? == 0
+        {
+            return Err(number_error(inst, "expected a digit after '.'"));
+        }
+    }
+
+    if let Some(next_char) = inst.peek_char().filter(|next_char| *next_char == 'e' || *next_char == 'E')
+    {
+        consume_number_char(inst, next_char)?;
+
+        if let Some(sign) = inst.peek_char().filter(|sign| *sign == '-' || *sign == '+')
+        {
+            consume_number_char(inst, sign)?;
+        }
+
+        if scan_digits(inst)? == 0
+        {
+            return Err(number_error(inst, "expected a digit in the exponent"));
+        }
+    }
+
+    // Anything else that could belong to a number is out of place (ex. '1.2.3' or '1e5e5').
+    if let Some(next_char) = inst.peek_char().filter(|next_char| matches!(next_char, '.' | 'e' | 'E' | '-' | '+'))
+    {
+        consume_number_char(inst, next_char)?;
+        return Err(number_error(inst, &format!("unexpected '{}' in number", next_char)));
+    }
+
+    return Ok(());
+}
+
+/// Scans the rest of a number, also accepting forms JSON doesn't allow such as
+/// '.5', '5.' and '007'.
+fn scan_lenient_number(inst: &mut Lexer, ch: char) -> Result<(), ParseError>
+{
+    let mut seen_dot = ch == '.';
+    let mut seen_e = false;
+    let mut seen_sign = false;
+    let mut seen_number = ch.is_ascii_digit();
+
+    while let Some(cur_char) = inst.peek_char()
+    {
+        if cur_char.is_ascii_digit()
+        {
+            seen_number = true;
+        }
+
+        else if cur_char == '.'
+        {
+            if seen_dot
+            {
+                return Err(number_error(inst, "a second '.' was discovered"));
+            }
+
+            seen_dot = true;
+        }
+
+        else if cur_char == 'e' || cur_char == 'E'
+        {
+            if seen_e
+            {
+                return Err(number_error(inst, "a second 'e' was discovered"));
+            }
+
+            seen_e = true;
+        }
+
+        else if cur_char == '-' || cur_char == '+'
+        {
+            if !seen_e
+            {
+                return Err(number_error(inst, &format!("a '{}' was discovered before 'e'", cur_char)));
+            }
+
+            else if seen_sign
+            {
+                return Err(number_error(inst, "a second sign was discovered"));
+            }
+
+            seen_sign = true;
+        }
+
+        else
+        {
+            break;
+        }
+
+        consume_number_char(inst, cur_char)?;
+    }
+
+    if !seen_number
+    {
+        return Err(number_error(inst, "did not see a digit"));
+    }
+
+    return Ok(());
+}
+
+/// Fails as soon as the number being lexed is longer than the 'max_string_length' limit (which applies
+/// to its lexeme too), rather than once all of it is read. The rest of the number is then skipped
+/// without holding on to it, so the next token starts after it.
+fn check_number_length(inst: &mut Lexer) -> Result<(), ParseError>
+{
+    match inst.options.get_max_string_length()
+    {
+        Some(limit) if inst.buffer.len() > limit =>
+        {
+            let error = inst.token_error(EnumParseErrorKind::NumberTooLong { limit });
+
+            while let Some(cur_char) = inst.peek_char().filter(|cur_char| cur_char.is_ascii_alphanumeric() || matches!(cur_char, '.' | '-' | '+'))
+            {
+                inst.advance(cur_char);
+            }
+
+            return Err(error);
+        },
+        _ => { return Ok(()); },
+    }
+}
+
+/// Handles a JSON5 number, which can also be hexadecimal (ex. '0xFF'), start with a '+',
+/// have a leading or trailing '.' or be 'Infinity' or 'NaN'. The lexeme is rewritten as
+/// the equivalent JSON number (ex. '+.5' becomes '0.5') so it can be written back out.
+fn handle_json5_number(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    let (sign, body_start) = match ch
+    {
+        '-' => ("-", 1),
+        '+' => ("", 1),
+        _ => ("", 0),
+    };
+
+    let mut last_char = ch;
+    let mut is_hex = false;
+
+    while let Some(cur_char) = inst.peek_char()
+    {
+        // In a hexadecimal number an 'e' is a digit, so a sign can't follow it.
+        is_hex = is_hex || ((cur_char == 'x' || cur_char == 'X') && last_char == '0');
+        let is_exponent_sign = (cur_char == '-' || cur_char == '+') && (last_char == 'e' || last_char == 'E') && !is_hex;
+
+        if !cur_char.is_ascii_alphanumeric() && cur_char != '.' && !is_exponent_sign
+        {
+            break;
+        }
+
+        consume_number_char(inst, cur_char)?;
+        last_char = cur_char;
+    }
+
+    let lexeme = inst.buffer.to_string();
+    let body = &lexeme[body_start..];
+
+    if body == "Infinity"
+    {
+        let value = if sign == "-" { f64::NEG_INFINITY } else { f64::INFINITY };
+        return Ok(Rc::new(TokenDouble::new_lexeme(value, format!("{}Infinity", sign))));
+    }
+
+    else if body == "NaN"
+    {
+        return Ok(Rc::new(TokenDouble::new_lexeme(f64::NAN, String::from("NaN"))));
+    }
+
+    else if let Some(digits) = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X"))
+    {
+        if digits.is_empty() || !digits.chars().all(|digit| digit.is_ascii_hexdigit())
+        {
+            return Err(number_error(inst, "expected hexadecimal digits after '0x'"));
+        }
+
+        // Too large for a u64 so settle for the closest f64.
+        let (value, json_lexeme) = match u64::from_str_radix(digits, 16)
+        {
+            Ok(num) => (num as f64, num.to_string()),
+            Err(_) =>
+            {
+                let num = digits.chars().fold(0.0, |acc, digit| acc * 16.0 + decode_char(digit) as f64);
+                (num, format_f64(num))
+            },
+        };
+
+        let value = if sign == "-" { -value } else { value };
+        return Ok(Rc::new(TokenDouble::new_lexeme(value, format!("{}{}", sign, json_lexeme))));
+    }
+
+    else if body.len() > 1 && body.starts_with('0') && body[1..].starts_with(|digit: char| digit.is_ascii_digit())
+    {
+        return Err(number_error(inst, "leading zeros are not allowed"));
+    }
+
+    let mantissa = body.split(['e', 'E']).next().unwrap_or_default();
+
+    if !mantissa.contains(|digit: char| digit.is_ascii_digit())
+    {
+        return Err(number_error(inst, "did not see a digit"));
+    }
+
+    let mut json_lexeme = format!("{}{}", sign, body);
+
+    if body.starts_with('.')
+    {
+        json_lexeme.insert(sign.len(), '0');
+    }
+
+    // A trailing '.' (ex. '5.' or '5.e3') has no JSON equivalent, so drop it.
+    if let Some(dot) = json_lexeme.find('.').filter(|dot| !json_lexeme[dot + 1..].starts_with(|digit: char| digit.is_ascii_digit()))
+    {
+        json_lexeme.remove(dot);
+    }
+
+    match parse_f64(&json_lexeme)
+    {
+        Some(num) => { return Ok(Rc::new(TokenDouble::new_lexeme(num, json_lexeme))); },
+        _ => { return Err(number_error(inst, "malformed number")); },
+    }
+}
+
+/// Consumes any digits, returning how many there were.
+fn scan_digits(inst: &mut Lexer) -> Result<usize, ParseError>
+{
+    let mut count: usize = 0;
+
+    while let Some(cur_char) = inst.peek_char().filter(|cur_char| cur_char.is_ascii_digit())
+    {
+        consume_number_char(inst, cur_char)?;
+        count += 1;
+    }
+
+    return Ok(count);
+}
+
+fn consume_number_char(inst: &mut Lexer, ch: char) -> Result<(), ParseError>
+{
+    inst.advance(ch);
+    inst.buffer.append_char(ch);
+    return check_number_length(inst);
+}
+
+fn number_error(inst: &Lexer, msg: &str) -> ParseError
+{
+    return inst.token_error(EnumParseErrorKind::InvalidNumber(String::from(msg)));
+}
+
+fn handle_string(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    inst.string_quote = ch;
+
+    // Continue from where the string ran out of fed input the last time it was lexed.
+    if let Some(resume) = inst.string_resume.take().filter(|resume| resume.token_start == inst.token_start.get_start_pos())
+    {
+        inst.restore(&resume.position);
+        inst.buffer = resume.buffer;
+        inst.string_byte_len = resume.byte_len;
+        inst.lone_surrogates = resume.lone_surrogates;
+        inst.unicode_escapes = resume.unicode_escapes;
+    }
+
+    let was_starved = std::mem::take(&mut inst.starved);
+    let mut resume_point = (inst.snap(), inst.buffer.len(), inst.string_byte_len);
+    let result = scan_string(inst, &mut resume_point);
+
+    if inst.starved
+    {
+        let (position, buffer_len, byte_len) = resume_point;
+        let mut buffer = std::mem::replace(&mut inst.buffer, StringBuilder::new(4096));
+        buffer.truncate(buffer_len);
+        let mut lone_surrogates = std::mem::take(&mut inst.lone_surrogates);
+        lone_surrogates.retain(|(index, _)| *index < buffer_len);
+        let mut unicode_escapes = std::mem::take(&mut inst.unicode_escapes);
+        unicode_escapes.retain(|index| *index < buffer_len);
+        inst.string_resume = Some(StringResume { token_start: inst.token_start.get_start_pos(), position, buffer, byte_len, lone_surrogates, unicode_escapes });
+    }
+
+    inst.starved |= was_starved;
+    return result;
+}
+
+/// Lexes the rest of a string after its opening quote, keeping 'resume_point' at the position (along
+/// with the buffer's length and size in bytes) after the last complete character or escape.
+fn scan_string(inst: &mut Lexer, resume_point: &mut (Snapshot, usize, usize)) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    // We don't add it to the buffer because we want to remove it and just get
+    // the raw value.
+    // inst.buffer.append_char(ch);
+    let mut last_was_escape = false;
+    let mut saw_close_quote = false;
+    let mut escape_start = inst.snap();
+
+    loop
+    {
+        if !last_was_escape
+        {
+            // Check the limit as each character (or escape) is added, rather than once the whole string is decoded.
+            inst.string_byte_len += inst.buffer.as_slice()[resume_point.1..].iter().map(|ch| ch.len_utf8()).sum::<usize>();
+
+            if let Some(limit) = inst.options.get_max_string_length().filter(|limit| inst.string_byte_len > *limit)
+            {
+                let token_start = inst.token_start.clone();
+                return Err(string_error(inst, EnumParseErrorKind::StringTooLong { limit }, &token_start));
+            }
+
+            *resume_point = (inst.snap(), inst.buffer.len(), inst.string_byte_len);
+        }
+
+        let cur_char_opt = inst.next_char();
+
+        match cur_char_opt
+        {
+            Some(cur_char) =>
+            {
+                // Handle escapes
+                if cur_char == '\\'
+                {
+                    if last_was_escape
+                    {
+                        let opt_escape_char = inst.escape_char_table.get(&cur_char);
+
+                        match opt_escape_char
+                        {
                             Some(escape_char) => { inst.buffer.append_char(*escape_char); },
-                            None => { return Err(String::from("Error not a supported escape character")); },
+                            None => { return Err(string_error(inst, EnumParseErrorKind::InvalidEscape(cur_char), &escape_start)); },
                         }
 
                         last_was_escape = false;
@@ -355,6 +1106,8 @@ fn handle_string(inst: &mut Lexer, _ch: char) -> Result<Rc<dyn TokenTrait>, Stri
 
                     else
                     {
+                        // The '\' is a single byte, so step back over it to mark the start of the escape.
+                        escape_start = Snapshot::new(inst.index - 1, inst.line, inst.column - 1);
                         last_was_escape = true;
                     }
                 }
@@ -365,71 +1118,12 @@ fn handle_string(inst: &mut Lexer, _ch: char) -> Result<Rc<dyn TokenTrait>, Stri
                     // TODO: Can we refactor this to take advantage of using the 'escape_char_table'??
                     if cur_char == 'u'
                     {
-                        const UNICODE_LEN: usize = 4;
-                        const ERROR_MSG: &str = "Error invalid unicode character in sequence";
-
-                        // Create a temporary small array to store ascii hexadecimal characters.
-                        let mut temp_arr: [char; 4] = ['\0'; 4];
-                        let mut count: usize = 0;
-
-                        for _ in 0..UNICODE_LEN
-                        {
-                            let opt_char_next = inst.next_char();
-
-                            match opt_char_next
-                            {
-                                Some(mut char_next) =>
-                                {
-                                    if (char_next >= '0' && char_next <= '9') ||
-                                       (char_next >= 'A' && char_next <= 'F') ||
-                                       (char_next >= 'a' && char_next <= 'f')
-                                    {
-                                        if char_next >= 'a' && char_next <= 'f'
-                                        {
-                                            char_next.make_ascii_uppercase();
-                                        }
-
-                                        temp_arr[count] = char_next;
-                                        count += 1;
-                                    }
-
-                                    else
-                                    {
-                                        return Err(String::from(ERROR_MSG));
-                                    }
-                                },
-                                None => { return Err(String::from(ERROR_MSG)); },
-                            }
-                        }
-
-                        // Verify the length
-                        if count != UNICODE_LEN
-                        {
-                            return Err(String::from("Error unicode character length is not equal to '4'"));
-                        }
-
-                        if inst.stringify
-                        {
-                            let mut temp_utf16 = decode_char(temp_arr[0]) << 12;
-                            temp_utf16 |= decode_char(temp_arr[1]) << 8;
-                            temp_utf16 |= decode_char(temp_arr[2]) << 4;
-                            temp_utf16 |= decode_char(temp_arr[3]);
-
-                            let conv_ch = std::char::from_u32(temp_utf16).expect("Failed to convert character from UTF-16 to UTF-8");
-                            let _ = inst.buffer.append_char(conv_ch);
-                        }
+                        handle_unicode_escape(inst, &escape_start)?;
+                    }
 
-                        else
-                        {
-                            // Include escape sequence
-                            inst.buffer.append_str("\\u");
-
-                            // Commit to buffer
-                            for ch in temp_arr
-                            {
-                                inst.buffer.append_char(ch);
-                            }
-                        }
+                    else if inst.options.get_dialect() == EnumDialect::JSON5
+                    {
+                        handle_json5_escape(inst, cur_char, &escape_start)?;
                     }
 
                     else
@@ -439,24 +1133,18 @@ fn handle_string(inst: &mut Lexer, _ch: char) -> Result<Rc<dyn TokenTrait>, Stri
                         match opt_escape_char
                         {
                             Some(escape_char) => { inst.buffer.append_char(*escape_char); },
-                            None => { return Err(String::from("Error not a supported escape character")); },
+                            None => { return Err(string_error(inst, EnumParseErrorKind::InvalidEscape(cur_char), &escape_start)); },
                         }
                     }
 
                     last_was_escape = false;
                 }
 
-                else if cur_char == '"'
+                else if cur_char == inst.string_quote
                 {
                     // This marks the end of the string.
-                    if !last_was_escape
-                    {
-                        saw_close_quote = true;
-                        break;
-                    }
-
-                    last_was_escape = false;
-                    inst.buffer.append_char(cur_char);
+                    saw_close_quote = true;
+                    break;
                 }
 
                 // Normal char
@@ -465,201 +1153,848 @@ fn handle_string(inst: &mut Lexer, _ch: char) -> Result<Rc<dyn TokenTrait>, Stri
                     inst.buffer.append_char(cur_char);
                 }
             },
-            None => { /*println!("Logic error in handle_string while getting the next char");*/ break; },
+            None => { break; },
         }
     }
 
     if !saw_close_quote
     {
-        return Err(String::from("Error: Missing closing double-quote ('\"')."));
+        return Err(inst.eof_error(EnumParseErrorKind::UnterminatedString));
     }
 
     let output = inst.buffer.to_string();
     // println!("debug output: {0}, {1}", output, output.len());
 
+    // Only one of these is kept: lone surrogates when stringifying, escapes otherwise.
+    if !inst.lone_surrogates.is_empty()
+    {
+        let offsets = to_byte_offsets(inst.buffer.as_slice(), inst.lone_surrogates.iter().map(|(index, _)| *index));
+        let lone_surrogates = offsets.into_iter().zip(inst.lone_surrogates.iter().map(|(_, code_unit)| *code_unit)).collect();
+        return Ok(Rc::new(TokenString::new_with_lone_surrogates(output, lone_surrogates)));
+    }
+
+    if !inst.unicode_escapes.is_empty()
+    {
+        let unicode_escapes = to_byte_offsets(inst.buffer.as_slice(), inst.unicode_escapes.iter().copied());
+        return Ok(Rc::new(TokenString::new_with_unicode_escapes(output, unicode_escapes)));
+    }
+
     return Ok(Rc::new(TokenString::new(output)));
 }
 
-fn handle_symbol(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, String>
+/// Turns (ascending) indices into 'chars' into byte offsets in the String they make up.
+fn to_byte_offsets(chars: &[char], indices: impl Iterator<Item = usize>) -> Vec<usize>
 {
-    inst.buffer.append_char(ch);
+    let mut byte_offset = 0;
+    let mut char_index = 0;
+    let mut offsets = Vec::new();
 
-    loop
+    for index in indices
     {
-        let cur_char_opt = inst.next_char();
+        byte_offset += chars[char_index..index].iter().map(|ch| ch.len_utf8()).sum::<usize>();
+        char_index = index;
+        offsets.push(byte_offset);
+    }
 
-        match cur_char_opt
-        {
-            Some(cur_char) =>
-            {
-                if !cur_char.is_ascii_whitespace() && (cur_char.is_digit(10) || cur_char.is_alphabetic())
-                {
-                    inst.buffer.append_char(cur_char);
-                }
+    return offsets;
+}
 
-                else
-                {
-                    inst.put_back(1);
-                    break;
-                }
-            },
-            None => { /*println!("Logic error in handle_symbol while getting the next char");*/ break; },
-        }
+/// Handles the escapes JSON5 adds on top of JSON's: '\xXX', '\0', a '\' at the end of a line
+/// (which continues the string on the next line) and any other character standing for itself.
+fn handle_json5_escape(inst: &mut Lexer, cur_char: char, escape_start: &Snapshot) -> Result<(), ParseError>
+{
+    if let Some(escape_char) = inst.escape_char_table.get(&cur_char)
+    {
+        let escape_char = *escape_char;
+        inst.buffer.append_char(escape_char);
     }
 
-    if inst.buffer.empty()
+    else if is_line_terminator(cur_char)
     {
-        return Err(String::from("Logic error in handling symbol"));
+        if cur_char == '\r' && inst.peek_char() == Some('\n')
+        {
+            inst.advance('\n');
+        }
     }
 
-    let output = inst.buffer.to_string();
-    // println!("debug output: {0}, {1}", output, output.len());
+    else if cur_char == 'x'
+    {
+        let mut code_point: u32 = 0;
 
-    // Check to see if this symbol was true or false, thus making it a TokenBool.
-    if output == "true"
+        for _ in 0..2
+        {
+            match inst.next_char()
+            {
+                Some(char_next) if char_next.is_ascii_hexdigit() => { code_point = (code_point << 4) | decode_char(char_next); },
+                _ => { return Err(string_error(inst, EnumParseErrorKind::InvalidEscape('x'), escape_start)); },
+            }
+        }
+
+        inst.buffer.append_char(char::from_u32(code_point).expect("Two hexadecimal digits should always be a valid char"));
+    }
+
+    // '\0' is only allowed when it isn't followed by another digit (octal escapes aren't supported).
+    else if cur_char == '0' && !inst.peek_char().is_some_and(|char_next| char_next.is_ascii_digit())
     {
-        return Ok(Rc::new(TokenBool::new(true)));
+        inst.buffer.append_char('\0');
     }
 
-    else if output == "false"
+    else if cur_char.is_ascii_digit()
     {
-        return Ok(Rc::new(TokenBool::new(false)));
+        return Err(string_error(inst, EnumParseErrorKind::InvalidEscape(cur_char), escape_start));
     }
 
-    else if output == "null"
+    else
     {
-        return Ok(Rc::new(TokenNull::new()));
+        inst.buffer.append_char(cur_char);
     }
 
-    return Ok(Rc::new(TokenSymbol::new(output)));
+    return Ok(());
 }
 
-fn handle_single_char_symbol(_inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, String>
+/// Handles a '\uXXXX' escape (the '\u' has already been consumed), pairing up UTF-16 surrogates.
+/// When not stringifying, the escape sequence(s) are kept in the output.
+fn handle_unicode_escape(inst: &mut Lexer, escape_start: &Snapshot) -> Result<(), ParseError>
 {
-    return Ok(Rc::new(TokenSymbol::new(ch.to_string())));
-}
+    let code_unit = read_unicode_escape(inst, escape_start)?;
 
-fn to_num(input: String) -> Option<f64>
-{
-    if input.len() == 0
+    if is_high_surrogate(code_unit)
     {
-        return None;
-    }
+        // The low surrogate should be the very next escape.
+        let snapshot = inst.snap();
 
-    let mut result = 0.0;
-    let mut multicand = 10.0;
-    let mut index: usize = 0;
-    let mut seen_dot = false;
-    let is_neg = input.as_bytes()[index] == ('-' as u8);
+        if inst.next_char() == Some('\\') && inst.next_char() == Some('u')
+        {
+            let low_code_unit = read_unicode_escape(inst, &snapshot)?;
 
-    // Need to offset if negative.
-    if is_neg
-    {
-        index += 1;
+            if is_low_surrogate(low_code_unit)
+            {
+                if inst.options.get_stringify()
+                {
+                    let ch = std::char::from_u32(combine_surrogates(code_unit, low_code_unit)).expect("Surrogate pairs should form a valid char");
+                    inst.buffer.append_char(ch);
+                }
+
+                else
+                {
+                    append_unicode_escape(inst, code_unit);
+                    append_unicode_escape(inst, low_code_unit);
+                }
+
+                return Ok(());
+            }
+        }
+
+        // Not a pair so leave whatever followed to be lexed normally.
+        inst.restore(&snapshot);
+        return handle_lone_surrogate(inst, code_unit, escape_start);
+    }
+
+    else if is_low_surrogate(code_unit)
+    {
+        return handle_lone_surrogate(inst, code_unit, escape_start);
+    }
+
+    if inst.options.get_stringify()
+    {
+        let ch = std::char::from_u32(code_unit).expect("Code units outside of the surrogate range should be valid chars");
+        inst.buffer.append_char(ch);
+    }
+
+    else
+    {
+        append_unicode_escape(inst, code_unit);
     }
 
-    for ch in input.chars().skip(is_neg as usize)
+    return Ok(());
+}
+
+/// Applies the lexer's surrogate policy to a surrogate without its high/low partner.
+fn handle_lone_surrogate(inst: &mut Lexer, code_unit: u32, escape_start: &Snapshot) -> Result<(), ParseError>
+{
+    match inst.options.get_surrogate_policy()
     {
-        if ch >= '0' && ch <= '9'
+        EnumSurrogatePolicy::ERROR => { return Err(string_error(inst, EnumParseErrorKind::LoneSurrogate(code_unit), escape_start)); },
+        EnumSurrogatePolicy::REPLACE =>
         {
-            let as_digit = (ch as u32) - ('0' as u32);
-            // println!("[DEBUG]: digit is {0}", as_digit);
+            if inst.options.get_stringify()
+            {
+                inst.buffer.append_char(char::REPLACEMENT_CHARACTER);
+            }
 
-            if !seen_dot
+            else
+            {
+                append_unicode_escape(inst, char::REPLACEMENT_CHARACTER as u32);
+            }
+        },
+        EnumSurrogatePolicy::PRESERVE =>
+        {
+            // A String can't hold a lone surrogate, so a decoded string holds a placeholder and remembers
+            // which code unit it stands for.
+            if inst.options.get_stringify()
             {
-                result *= multicand;
-                result += as_digit as f64;
+                inst.lone_surrogates.push((inst.buffer.len(), code_unit as u16));
+                inst.buffer.append_char(char::REPLACEMENT_CHARACTER);
             }
 
             else
             {
-                result += (as_digit as f64) * multicand;
+                append_unicode_escape(inst, code_unit);
             }
-        }
+        },
+    }
+
+    return Ok(());
+}
+
+/// Reads the 4 hexadecimal digits of a '\uXXXX' escape into a UTF-16 code unit.
+fn read_unicode_escape(inst: &mut Lexer, escape_start: &Snapshot) -> Result<u32, ParseError>
+{
+    const UNICODE_LEN: usize = 4;
+    let mut code_unit: u32 = 0;
 
-        else if ch == '.'
+    for _ in 0..UNICODE_LEN
+    {
+        match inst.next_char()
         {
-            multicand = 0.1;
-            seen_dot = true;
-            index += 1;
-            continue;
+            Some(char_next) if char_next.is_ascii_hexdigit() => { code_unit = (code_unit << 4) | decode_char(char_next); },
+            Some(char_next) =>
+            {
+                let kind = EnumParseErrorKind::InvalidUnicodeEscape(format!("'{}' is not a hexadecimal digit", char_next.escape_debug()));
+                return Err(string_error(inst, kind, escape_start));
+            },
+            None => { return Err(string_error(inst, EnumParseErrorKind::InvalidUnicodeEscape(String::from("expected 4 hexadecimal digits")), escape_start)); },
         }
+    }
+
+    return Ok(code_unit);
+}
+
+/// Appends the code unit as a '\uXXXX' escape (with upper case hexadecimal digits), remembering where it starts
+/// so it isn't mistaken for a '\' followed by 'uXXXX' later on.
+fn append_unicode_escape(inst: &mut Lexer, code_unit: u32)
+{
+    inst.unicode_escapes.push(inst.buffer.len());
+    inst.buffer.append_string(&format!("\\u{:04X}", code_unit));
+}
+
+/// Creates an error for a bad escape sequence and then skips past the rest of the string,
+/// so the next token starts after the closing quote.
+fn string_error(inst: &mut Lexer, kind: EnumParseErrorKind, escape_start: &Snapshot) -> ParseError
+{
+    let error = ParseError::new(kind, escape_start, inst.index);
+    let mut last_was_escape = false;
 
-        else if ch == 'e' || ch == 'E'
+    // Nothing carries on after an error in fed input (see 'new_push'), so don't wait for more of it
+    // just to skip the string.
+    if inst.awaiting_input
+    {
+        return error;
+    }
+
+    while let Some(ch) = inst.next_char()
+    {
+        if ch == inst.string_quote && !last_was_escape
         {
-            index += 1;
-            let exp_result = to_num(String::from(input.get(index..).unwrap()));
+            break;
+        }
 
-            if exp_result.is_none()
-            {
-                return None;
-            }
+        last_was_escape = ch == '\\' && !last_was_escape;
+    }
+
+    return error;
+}
 
-            const EXP: f64 = 10.0;
-            result *= EXP.powf(exp_result.unwrap());
+fn handle_symbol(inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    inst.buffer.append_char(ch);
+
+    while let Some(cur_char) = inst.peek_char()
+    {
+        let is_json5_char = inst.options.get_dialect() == EnumDialect::JSON5 && (cur_char == '$' || cur_char == '_');
 
+        if !cur_char.is_ascii_digit() && !cur_char.is_alphabetic() && !is_json5_char
+        {
             break;
         }
 
-        if seen_dot
+        inst.advance(cur_char);
+        inst.buffer.append_char(cur_char);
+    }
+
+    let output = inst.buffer.to_string();
+    // println!("debug output: {0}, {1}", output, output.len());
+
+    if let Some(limit) = inst.options.get_max_string_length()
+    {
+        if output.len() > limit
         {
-            multicand /= 10.0;
+            return Err(inst.token_error(EnumParseErrorKind::StringTooLong { limit }));
         }
+    }
+
+    // Check to see if this symbol was true or false, thus making it a TokenBool.
+    if output == "true"
+    {
+        return Ok(Rc::new(TokenBool::new(true)));
+    }
+
+    else if output == "false"
+    {
+        return Ok(Rc::new(TokenBool::new(false)));
+    }
+
+    else if output == "null"
+    {
+        return Ok(Rc::new(TokenNull::new()));
+    }
 
-        index += 1;
-        // println!("[DEBUG]: Current state result is {0}, multicand is {1}", result, multicand);
+    else if inst.options.get_dialect() == EnumDialect::JSON5 && output == "Infinity"
+    {
+        return Ok(Rc::new(TokenDouble::new_lexeme(f64::INFINITY, output)));
     }
 
-    if is_neg
+    else if inst.options.get_dialect() == EnumDialect::JSON5 && output == "NaN"
     {
-        result = -result;
+        return Ok(Rc::new(TokenDouble::new_lexeme(f64::NAN, output)));
     }
 
-    // println!("[DEBUG]: Result is {0}", result);
-    return Some(result);
+    return Ok(Rc::new(TokenSymbol::new(output)));
+}
+
+fn handle_single_char_symbol(_inst: &mut Lexer, ch: char) -> Result<Rc<dyn TokenTrait>, ParseError>
+{
+    return Ok(Rc::new(TokenSymbol::new(ch.to_string())));
 }
 
 #[cfg(test)]
 mod tests
 {
     #[allow(unused_imports)]
-    use crate::parser::token::{EnumTokenType, TokenTrait};
+    use crate::parser::token::{describe_token, EnumTokenType, TokenTrait};
+    use crate::parser::dialect::EnumDialect;
     use crate::parser::lexer::Lexer;
+    use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
+    use crate::parser::token_double::TokenDouble;
+    use crate::parser::token_string::TokenString;
+    use super::READ_CHUNK_SIZE;
+
+    use std::io::{Cursor, Read};
+    use std::rc::Rc;
 
     #[test]
     fn lex_accepts_empty_input()
     {
         let input = String::from("");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_accepts_curly_brackets()
+    {
+        let input = String::from(" { } ");
+        let first_token = String::from("{");
+        let second_token = String::from("}");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_bool_true()
+    {
+        let first_token = String::from("{");
+        let second_token = true;
+        let third_token = String::from("}");
+        let input = String::from("{ true }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::BOOL);
+            assert!(token.is_bool());
+            assert_eq!(token.as_bool().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_bool_false()
+    {
+        let first_token = String::from("{");
+        let second_token = false;
+        let third_token = String::from("}");
+        let input = String::from("{ false }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::BOOL);
+            assert!(token.is_bool());
+            assert_eq!(token.as_bool().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+    }
+
+    #[test]
+    fn lex_token_double()
+    {
+        let first_token = String::from("{");
+        let second_token = 123.45;
+        let third_token = String::from("}");
+        let input = String::from("{ 123.45 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_neg_double()
+    {
+        let first_token = String::from("{");
+        let second_token = -123.45;
+        let third_token = String::from("}");
+        let input = String::from("{ -123.45 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_explicit_pos_double_fails()
+    {
+        let first_token = String::from("{");
+        let input = String::from("{ +123.45 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_double_neg_double_fails()
+    {
+        let input = String::from("--123.45");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+        let token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_double_big_e_10()
+    {
+        let first_token = String::from("{");
+        let second_token = 123.45E10;
+        let third_token = String::from("}");
+        let input = String::from("{ 123.45E10 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_neg_double_big_e_10()
+    {
+        let first_token = String::from("{");
+        let second_token = -123.45E10;
+        let third_token = String::from("}");
+        let input = String::from("{ -123.45E10 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_neg_zero_zero_one_double()
+    {
+        let first_token = String::from("{");
+        let second_token = -0.01;
+        let third_token = String::from("}");
+        let input = String::from("{ -0.01 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_one_e_ten_double()
+    {
+        let first_token = String::from("{");
+        let second_token = 1.0e10;
+        let third_token = String::from("}");
+        let input = String::from("{ 1.0e10 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_one_e_negative_ten_double()
+    {
+        let first_token = String::from("{");
+        let second_token = 1.5e-10;
+        let third_token = String::from("}");
+        let input = String::from("{ 1.5e-10 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_one_e_ten_point_five_double_fails()
+    {
+        let first_token = String::from("{");
+        let input = String::from("{ 1.0e10.5 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
+        }
 
-        let token_result = lexer.next_token();
+        token_result = lexer.next_token();
         assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_accepts_curly_brackets()
+    fn lex_token_one_e_neg_neg_ten_double_fails()
     {
-        let input = String::from(" { } ");
         let first_token = String::from("{");
-        let second_token = String::from("}");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ 1.0e--10 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
             assert!(token.is_symbol());
             assert_eq!(token.as_symbol().unwrap(), &first_token);
         }
 
         token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_token_neg_dot_neg_double_fails()
+    {
+        let first_token = String::from("{");
+        let input = String::from("{ -.- }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
             assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &second_token);
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
         }
 
         token_result = lexer.next_token();
@@ -667,13 +2002,11 @@ mod tests
     }
 
     #[test]
-    fn lex_token_bool_true()
+    fn lex_token_neg_five_dot_neg_five_double_fails()
     {
         let first_token = String::from("{");
-        let second_token = true;
-        let third_token = String::from("}");
-        let input = String::from("{ true }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ -5.-5 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -686,23 +2019,24 @@ mod tests
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::BOOL);
-            assert!(token.is_bool());
-            assert_eq!(token.as_bool().unwrap(), second_token);
-        }
+    #[test]
+    fn lex_token_neg_only_double_fails()
+    {
+        let first_token = String::from("{");
+        let input = String::from("{ - }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        token_result = lexer.next_token();
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
             assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
             assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.as_symbol().unwrap(), &first_token);
         }
 
         token_result = lexer.next_token();
@@ -710,13 +2044,13 @@ mod tests
     }
 
     #[test]
-    fn lex_token_bool_false()
+    fn lex_token_double_leading_dot()
     {
         let first_token = String::from("{");
-        let second_token = false;
+        let second_token = 0.45;
         let third_token = String::from("}");
-        let input = String::from("{ false }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ .45 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().lenient_numbers(true).build());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -733,9 +2067,9 @@ mod tests
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::BOOL);
-            assert!(token.is_bool());
-            assert_eq!(token.as_bool().unwrap(), second_token);
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), second_token);
         }
 
         token_result = lexer.next_token();
@@ -747,16 +2081,19 @@ mod tests
             assert!(token.is_symbol());
             assert_eq!(token.as_symbol().unwrap(), &third_token);
         }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_token_double()
+    fn lex_token_double_leading_negative()
     {
         let first_token = String::from("{");
-        let second_token = 123.45;
+        let second_token = -123.45;
         let third_token = String::from("}");
-        let input = String::from("{ 123.45 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ -123.45 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -793,13 +2130,12 @@ mod tests
     }
 
     #[test]
-    fn lex_token_neg_double()
+    fn lex_token_null()
     {
         let first_token = String::from("{");
-        let second_token = -123.45;
         let third_token = String::from("}");
-        let input = String::from("{ -123.45 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ null }");
+        let mut lexer = Lexer::new_move(input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -816,9 +2152,8 @@ mod tests
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
+            assert_eq!(token.get_type(), EnumTokenType::NULL);
+            assert!(token.is_null());
         }
 
         token_result = lexer.next_token();
@@ -836,11 +2171,13 @@ mod tests
     }
 
     #[test]
-    fn lex_token_explicit_pos_double_fails()
+    fn lex_string()
     {
         let first_token = String::from("{");
-        let input = String::from("{ +123.45 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let second_token = String::from("Hi");
+        let third_token = String::from("}");
+        let input = String::from("{ \"Hi\" }");
+        let mut lexer = Lexer::new_move(input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -853,55 +2190,65 @@ mod tests
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
-
-    #[test]
-    fn lex_token_double_neg_double_fails()
-    {
-        let input = String::from("--123.45");
-        let mut lexer = Lexer::new_copy(&input, false);
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
+        assert!(token_result.is_ok());
 
-    #[test]
-    fn lex_token_double_big_e_10()
-    {
-        let first_token = String::from("{");
-        let second_token = 123.45E10;
-        let third_token = String::from("}");
-        let input = String::from("{ 123.45E10 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), &second_token);
+        }
 
-        let mut token_result = lexer.next_token();
+        token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
             assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
             assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.as_symbol().unwrap(), &third_token);
         }
 
         token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_escape_double_quote()
+    {
+        let first_token = "\"";
+        let input = String::from("\"\\\"\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_escape_forward_slash()
+    {
+        let first_token = "/";
+        let input = String::from("\"\\/\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -909,13 +2256,13 @@ mod tests
     }
 
     #[test]
-    fn lex_token_neg_double_big_e_10()
+    fn lex_escape_multi_backslash_characters()
     {
         let first_token = String::from("{");
-        let second_token = -123.45E10;
+        let second_token = String::from("\"Hi\"");
         let third_token = String::from("}");
-        let input = String::from("{ -123.45E10 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("{ \"\\\"Hi\\\"\" }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -932,9 +2279,9 @@ mod tests
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), &second_token);
         }
 
         token_result = lexer.next_token();
@@ -952,42 +2299,41 @@ mod tests
     }
 
     #[test]
-    fn lex_token_neg_zero_zero_one_double()
+    fn lex_escape_backspace()
     {
-        let first_token = String::from("{");
-        let second_token = -0.01;
-        let third_token = String::from("}");
-        let input = String::from("{ -0.01 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "\x08";
+        let input = String::from("\"\\b\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
-        }
+    #[test]
+    fn lex_escape_form_feed()
+    {
+        let first_token = "\x0C";
+        let input = String::from("\"\\f\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        token_result = lexer.next_token();
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -995,42 +2341,62 @@ mod tests
     }
 
     #[test]
-    fn lex_token_one_e_ten_double()
+    fn lex_escape_newline()
     {
-        let first_token = String::from("{");
-        let second_token = 1.0e10;
-        let third_token = String::from("}");
-        let input = String::from("{ 1.0e10 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "\n";
+        let input = String::from("\"\\n\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_escape_carriage_return()
+    {
+        let first_token = "\r";
+        let input = String::from("\"\\r\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_escape_tab()
+    {
+        let first_token = "\t";
+        let input = String::from("\"\\t\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1038,42 +2404,51 @@ mod tests
     }
 
     #[test]
-    fn lex_token_one_e_negative_ten_double()
+    fn lex_escape_single_quote_invalid()
     {
-        let first_token = String::from("{");
-        let second_token = 1.5e-10;
-        let third_token = String::from("}");
-        let input = String::from("{ 1.5e-10 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("\\'");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
+
+        let token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
+
+    #[test]
+    fn lex_escape_unicode_checkmark_min_value_valid()
+    {
+        let first_token = "\\u0000";
+        let input = String::from("\"\\u0000\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
-        }
+    #[test]
+    fn lex_escape_unicode_checkmark_max_value_valid()
+    {
+        let first_token = "\\uFFFF";
+        let input = String::from("\"\\uFFFF\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        token_result = lexer.next_token();
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1081,20 +2456,20 @@ mod tests
     }
 
     #[test]
-    fn lex_token_one_e_ten_point_five_double_fails()
+    fn lex_escape_unicode_checkmark_valid()
     {
-        let first_token = String::from("{");
-        let input = String::from("{ 1.0e10.5 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "\\u2713";
+        let input = String::from("\"\\u2713\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1102,20 +2477,20 @@ mod tests
     }
 
     #[test]
-    fn lex_token_one_e_neg_neg_ten_double_fails()
+    fn lex_escape_unicode_mix_casing_does_to_upper_and_is_valid()
     {
-        let first_token = String::from("{");
-        let input = String::from("{ 1.0e--10 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "\\uBEEF";
+        let input = String::from("\"\\uBeeF\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1123,62 +2498,60 @@ mod tests
     }
 
     #[test]
-    fn lex_token_neg_dot_neg_double_fails()
+    fn lex_escape_unicode_not_enough_hex_digits_invalid()
     {
-        let first_token = String::from("{");
-        let input = String::from("{ -.- }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("\"\\u271\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
-        }
+    #[test]
+    fn lex_escape_unicode_not_enough_hex_digits_invalid2()
+    {
+        let input = String::from("\"\\u21\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        token_result = lexer.next_token();
+        let token_result = lexer.next_token();
         assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_token_neg_five_dot_neg_five_double_fails()
+    fn lex_escape_unicode_not_enough_hex_digits_invalid3()
     {
-        let first_token = String::from("{");
-        let input = String::from("{ -5.-5 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("\"\\u2\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let token_result = lexer.next_token();
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
-        }
+    #[test]
+    fn lex_escape_unicode_not_enough_hex_digits_invalid4()
+    {
+        let input = String::from("\"\\u\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        token_result = lexer.next_token();
+        let token_result = lexer.next_token();
         assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_token_neg_only_double_fails()
+    fn lex_escape_unicode_checkmark_and_stringify()
     {
-        let first_token = String::from("{");
-        let input = String::from("{ - }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "✓";
+        let input = String::from("\"\\u2713\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1186,42 +2559,41 @@ mod tests
     }
 
     #[test]
-    fn lex_token_double_leading_dot()
+    fn lex_escape_unicode_pound_and_stringify()
     {
-        let first_token = String::from("{");
-        let second_token = 0.45;
-        let third_token = String::from("}");
-        let input = String::from("{ .45 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "£";
+        let input = String::from("\"\\u00A3\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
-        }
+    #[test]
+    fn lex_escape_unicode_abcd_and_stringify()
+    {
+        let first_token = "ꯍ";
+        let input = String::from("\"\\uaBcD\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
-        token_result = lexer.next_token();
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1229,42 +2601,41 @@ mod tests
     }
 
     #[test]
-    fn lex_token_double_leading_negative()
+    fn lex_escape_unicode_beef_and_stringify()
     {
-        let first_token = String::from("{");
-        let second_token = -123.45;
-        let third_token = String::from("}");
-        let input = String::from("{ -123.45 }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "뻯";
+        let input = String::from("\"\\uBeeF\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(token_result.is_err());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), second_token);
-        }
+    #[test]
+    fn lex_escape_unicode_four_zeroes_stringify()
+    {
+        let first_token = " ";
+        let input = String::from("\"\\u0000\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
-        token_result = lexer.next_token();
+        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            assert_eq!(token.get_type(), EnumTokenType::STRING);
+            assert!(token.is_string());
+            assert_eq!(token.as_string().unwrap(), first_token);
         }
 
         token_result = lexer.next_token();
@@ -1272,54 +2643,82 @@ mod tests
     }
 
     #[test]
-    fn lex_token_null()
+    fn lex_escape_unicode_random_gen_series_stringify()
     {
-        let first_token = String::from("{");
-        let third_token = String::from("}");
-        let input = String::from("{ null }");
-        let mut lexer = Lexer::new_move(input, false);
+        let unicode_chars: [char; 16] = [ '뜿', '柹', '裧', 'ꒊ', 'ꍵ', '낸', '깥', '軒',
+                                          '', 'ꇏ', '녢', '枈', '', 'Ꟃ', '쩿', 'ꪶ'
+        ];
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let unicode_raw: [u16; 16] = [ 0xb73f, 0x67f9, 0x88e7, 0xa48a, 0xa375, 0xb0b8, 0xae65, 0x8ed2,
+                                        0xe777, 0xa1cf, 0xb162, 0x6788, 0xf384, 0xa7c2, 0xca7f, 0xaab6
+        ];
+
+        assert_eq!(unicode_chars.len(), unicode_raw.len());
+        let mut input = String::with_capacity(0x10);
 
+        for i in 0..unicode_chars.len()
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
-        }
+            let ch = *unicode_chars.get(i).expect("missing char");
+            let val = *unicode_raw.get(i).expect("missing raw value");
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+            input.clear();
+            input.push_str("\"\\u");
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::NULL);
-            assert!(token.is_null());
-        }
+            for j in (0..4).rev()
+            {
+                let mut sub_val: u8 = ((val >> (j * 4)) & 0x0F) as u8;
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+                match sub_val
+                {
+                    0..=9 => { sub_val += b'0'; },
+                    0x0A..=0x0F => { sub_val -= 10;  sub_val += b'A'; },
+                    _ => { unreachable!("sub_val: {0}", sub_val); },
+                }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+                input.push(sub_val as char);
+            }
+
+            input.push('"');
+
+            let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
+            let mut token_result = lexer.next_token();
+            assert!(token_result.is_ok());
+
+            {
+                let token = token_result.unwrap();
+                assert_eq!(token.get_type(), EnumTokenType::STRING);
+                assert!(token.is_string());
+                assert_eq!(token.as_string().unwrap().chars().next().unwrap(), ch);
+            }
+
+            token_result = lexer.next_token();
+            assert!(token_result.is_err());
         }
+    }
+
+    #[test]
+    fn lex_escape_unicode_four_zs_stringify_invalid()
+    {
+        let input = String::from("\"\\uzzZZ\"");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
+
+        let mut token_result = lexer.next_token();
+        assert!(token_result.is_err());
 
         token_result = lexer.next_token();
         assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_string()
+    fn lex_simple_key_value_pair()
     {
         let first_token = String::from("{");
-        let second_token = String::from("Hi");
-        let third_token = String::from("}");
-        let input = String::from("{ \"Hi\" }");
-        let mut lexer = Lexer::new_move(input, false);
+        let second_token = String::from("key");
+        let third_token = String::from(":");
+        let fourth_token = String::from("value");
+        let fifth_token = String::from("}");
+        let input = String::from("{ \"key\": \"value\" }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -1352,45 +2751,23 @@ mod tests
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
-
-    #[test]
-    fn lex_escape_double_quote()
-    {
-        let first_token = "\"";
-        let input = String::from("\"\\\"\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
             assert_eq!(token.get_type(), EnumTokenType::STRING);
             assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.as_string().unwrap(), &fourth_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
-
-    #[test]
-    fn lex_escape_forward_slash()
-    {
-        let first_token = "/";
-        let input = String::from("\"\\/\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &fifth_token);
         }
 
         token_result = lexer.next_token();
@@ -1398,13 +2775,19 @@ mod tests
     }
 
     #[test]
-    fn lex_escape_multi_backslash_characters()
+    fn lex_multi_field_object()
     {
         let first_token = String::from("{");
-        let second_token = String::from("\"Hi\"");
-        let third_token = String::from("}");
-        let input = String::from("{ \"\\\"Hi\\\"\" }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let second_token = String::from("1");
+        let third_token = String::from(":");
+        let fourth_token: f64 = 123.0;
+        let fifth_token = String::from(",");
+        let sixth_token = String::from("2");
+        let seventh_token = &third_token;
+        let eighth_token: f64 = 456.0;
+        let ninth_token = String::from("}");
+        let input = String::from("{ \"1\": 123, \"2\": 456 }");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -1437,66 +2820,63 @@ mod tests
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
-
-    #[test]
-    fn lex_escape_backspace()
-    {
-        let first_token = "\x08";
-        let input = String::from("\"\\b\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), fourth_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
+        assert!(token_result.is_ok());
 
-    #[test]
-    fn lex_escape_form_feed()
-    {
-        let first_token = "\x0C";
-        let input = String::from("\"\\f\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &fifth_token);
+        }
 
-        let mut token_result = lexer.next_token();
+        token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
             assert_eq!(token.get_type(), EnumTokenType::STRING);
             assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.as_string().unwrap(), &sixth_token);
         }
 
         token_result = lexer.next_token();
-        assert!(token_result.is_err());
-    }
+        assert!(token_result.is_ok());
 
-    #[test]
-    fn lex_escape_newline()
-    {
-        let first_token = "\n";
-        let input = String::from("\"\\n\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), seventh_token);
+        }
 
-        let mut token_result = lexer.next_token();
+        token_result = lexer.next_token();
         assert!(token_result.is_ok());
 
         {
             let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
+            assert!(token.is_double());
+            assert_eq!(token.as_double().unwrap(), eighth_token);
+        }
+
+        token_result = lexer.next_token();
+        assert!(token_result.is_ok());
+
+        {
+            let token = token_result.unwrap();
+            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
+            assert!(token.is_symbol());
+            assert_eq!(token.as_symbol().unwrap(), &ninth_token);
         }
 
         token_result = lexer.next_token();
@@ -1504,11 +2884,11 @@ mod tests
     }
 
     #[test]
-    fn lex_escape_carriage_return()
+    fn lex_multi_byte_utf8_string_round_trips()
     {
-        let first_token = "\r";
-        let input = String::from("\"\\r\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let first_token = "こんにちは, 😊, ñ, \u{10FFFF}";
+        let input = format!("\"{}\"", first_token);
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
         let mut token_result = lexer.next_token();
         assert!(token_result.is_ok());
@@ -1516,8 +2896,7 @@ mod tests
         {
             let token = token_result.unwrap();
             assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(token.as_string().unwrap().as_bytes(), first_token.as_bytes());
         }
 
         token_result = lexer.next_token();
@@ -1525,504 +2904,559 @@ mod tests
     }
 
     #[test]
-    fn lex_escape_tab()
+    fn lex_multi_byte_utf8_columns_count_characters()
     {
-        let first_token = "\t";
-        let input = String::from("\"\\t\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let input = String::from("[\"日本\", @]");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
+        for _ in 0..3
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert!(lexer.next_token().is_ok());
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let error = lexer.next_token().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('@'));
+        assert_eq!(error.get_column(), 8);
+        assert_eq!(error.get_start(), 11);
     }
 
     #[test]
-    fn lex_escape_single_quote_invalid()
+    fn lex_multi_byte_unexpected_character()
     {
-        let input = String::from("\\'");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("→");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::default());
 
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let error = lexer.next_token().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('→'));
+        assert_eq!(error.get_start(), 0);
+        assert_eq!(error.get_end(), 3);
     }
 
     #[test]
-    fn lex_escape_unicode_checkmark_min_value_valid()
+    fn lex_invalid_utf8_in_string()
     {
-        let first_token = "\\u0000";
-        let input = String::from("\"\\u0000\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input: Vec<u8> = vec![b'[', b'\n', b'"', b'a', 0xC3, 0x28, b'"', b']'];
+        let mut lexer = Lexer::new_bytes(input, &ParserOptions::default());
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert!(lexer.next_token().is_ok());
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
-        }
+        let error = lexer.next_token().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidUtf8);
+        assert_eq!(error.get_start(), 4);
+        assert_eq!(error.get_end(), 5);
+        assert_eq!(error.get_line(), 2);
+        assert_eq!(error.get_column(), 3);
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        // The lexer never moves past the invalid bytes.
+        assert_eq!(lexer.next_token().err().unwrap().get_kind(), &EnumParseErrorKind::InvalidUtf8);
     }
 
     #[test]
-    fn lex_escape_unicode_checkmark_max_value_valid()
+    fn lex_invalid_utf8_between_tokens()
     {
-        let first_token = "\\uFFFF";
-        let input = String::from("\"\\uFFFF\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let input: Vec<u8> = vec![b'1', b' ', 0xFF];
+        let mut lexer = Lexer::new_bytes(input, &ParserOptions::default());
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
-        }
+        assert!(lexer.next_token().is_ok());
+        assert!(!lexer.is_at_end());
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let error = lexer.next_token().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidUtf8);
+        assert_eq!(error.get_start(), 2);
     }
 
     #[test]
-    fn lex_escape_unicode_checkmark_valid()
+    fn lex_valid_utf8_bytes()
     {
-        let first_token = "\\u2713";
-        let input = String::from("\"\\u2713\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = "\"ünïcödé\"".as_bytes().to_vec();
+        let mut lexer = Lexer::new_bytes(input, &ParserOptions::default());
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let token = lexer.next_token().unwrap();
+        assert_eq!(token.as_string().unwrap(), "ünïcödé");
+        assert!(lexer.is_at_end());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
-        }
+    fn lex_single_string(input: &str, stringify: bool, policy: EnumSurrogatePolicy) -> Result<String, ParseError>
+    {
+        let options = ParserOptions::builder().stringify(stringify).surrogate_policy(policy).build();
+        let mut lexer = Lexer::new_move(String::from(input), &options);
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let token = lexer.next_token()?;
+        assert!(lexer.is_at_end());
+
+        return Ok(token.as_string().unwrap().clone());
     }
 
     #[test]
-    fn lex_escape_unicode_mix_casing_does_to_upper_and_is_valid()
+    fn lex_escape_unicode_surrogate_pair_stringify()
     {
-        let first_token = "\\uBEEF";
-        let input = String::from("\"\\uBeeF\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let output = lex_single_string("\"\\uD83D\\uDE00\"", true, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "😀");
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
-        }
+        let output = lex_single_string("\"\\ud83d\\ude00\"", true, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "😀");
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        // Lowest and highest code points that need a pair.
+        let output = lex_single_string("\"\\uD800\\uDC00\\uDBFF\\uDFFF\"", true, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "\u{10000}\u{10FFFF}");
     }
 
     #[test]
-    fn lex_escape_unicode_not_enough_hex_digits_invalid()
+    fn lex_escape_unicode_surrogate_pair_no_stringify()
     {
-        let input = String::from("\"\\u271\"");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let output = lex_single_string("\"\\ud83d\\ude00\"", false, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "\\uD83D\\uDE00");
     }
 
     #[test]
-    fn lex_escape_unicode_not_enough_hex_digits_invalid2()
+    fn lex_escape_unicode_kept_escapes_no_stringify()
     {
-        let input = String::from("\"\\u21\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let inputs = [
+            ("\"\\u0041\"", "\\u0041", vec![ 0 ]),
+            ("\"é\\ud83d\\ude00\\\\u0041\"", "é\\uD83D\\uDE00\\u0041", vec![ 2, 8 ]),
+            // An escaped '\' followed by 'uXXXX' is just text.
+            ("\"\\\\u0041\"", "\\u0041", vec![]),
+        ];
 
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        for (input, expected, unicode_escapes) in inputs
+        {
+            let mut lexer = Lexer::new_copy(input, &ParserOptions::default());
+
+            let token = lexer.next_token().unwrap();
+            let token_string = token.downcast_ref::<TokenString>().unwrap();
+            assert_eq!(token_string.get_value(), expected, "{}", input);
+            assert_eq!(token_string.get_unicode_escapes(), &unicode_escapes[..], "{}", input);
+        }
     }
 
     #[test]
-    fn lex_escape_unicode_not_enough_hex_digits_invalid3()
+    fn lex_escape_unicode_emoji_sequences_stringify()
     {
-        let input = String::from("\"\\u2\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        // Variation selectors, zero width joiners, regional indicators and skin tone modifiers.
+        let input = "\"I \\u2764\\uFE0F \\uD83C\\uDF55 & \\uD83D\\uDC68\\u200D\\uD83D\\uDC69\\u200D\\uD83D\\uDC67 \\uD83C\\uDDFA\\uD83C\\uDDF8 \\uD83D\\uDC4D\\uD83C\\uDFFD\"";
+        let output = lex_single_string(input, true, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "I ❤️ 🍕 & 👨‍👩‍👧 🇺🇸 👍🏽");
+    }
 
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
+    #[test]
+    fn lex_escape_unicode_mixed_raw_and_escaped_emoji_stringify()
+    {
+        let output = lex_single_string("\"😀=\\uD83D\\uDE00\"", true, EnumSurrogatePolicy::ERROR);
+        assert_eq!(output.unwrap(), "😀=😀");
     }
 
     #[test]
-    fn lex_escape_unicode_not_enough_hex_digits_invalid4()
+    fn lex_escape_unicode_lone_high_surrogate_error()
     {
-        let input = String::from("\"\\u\"");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let input = String::from("\"ab\\uD83D\" 1");
+        let mut lexer = Lexer::new_copy(&input, &ParserOptions::builder().stringify(true).build());
 
-        let token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let error = lexer.next_token().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::LoneSurrogate(0xD83D));
+        assert_eq!(error.get_start(), 3);
+        assert_eq!(error.get_end(), 9);
+        assert_eq!(error.get_message(), "lone UTF-16 surrogate '\\uD83D'");
+
+        // The rest of the string is skipped.
+        let token = lexer.next_token().unwrap();
+        assert_eq!(token.as_double().unwrap(), 1.0);
     }
 
     #[test]
-    fn lex_escape_unicode_checkmark_and_stringify()
+    fn lex_escape_unicode_lone_surrogates_error()
     {
-        let first_token = "✓";
-        let input = String::from("\"\\u2713\"");
-        let mut lexer = Lexer::new_copy(&input, true);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let inputs = [ "\"\\uDE00\"", "\"\\uD83D\\u0041\"", "\"\\uD83D\\uD83D\\uDE00\"", "\"\\uDE00\\uD83D\"", "\"\\uD83Dx\"", "\"\\uD83D\\n\"" ];
 
+        for input in inputs
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            for stringify in [true, false]
+            {
+                let error = lex_single_string(input, stringify, EnumSurrogatePolicy::ERROR).err().unwrap();
+                assert!(matches!(error.get_kind(), EnumParseErrorKind::LoneSurrogate(_)), "{}", input);
+            }
         }
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+    #[test]
+    fn lex_escape_unicode_lone_surrogates_replace()
+    {
+        let policy = EnumSurrogatePolicy::REPLACE;
+
+        assert_eq!(lex_single_string("\"\\uDE00\"", true, policy).unwrap(), "\u{FFFD}");
+        assert_eq!(lex_single_string("\"\\uD83D\\u0041\"", true, policy).unwrap(), "\u{FFFD}A");
+        assert_eq!(lex_single_string("\"\\uD83D\\uD83D\\uDE00\"", true, policy).unwrap(), "\u{FFFD}😀");
+        assert_eq!(lex_single_string("\"\\uDE00\\uD83D\"", true, policy).unwrap(), "\u{FFFD}\u{FFFD}");
+        assert_eq!(lex_single_string("\"\\uD83Dx\"", true, policy).unwrap(), "\u{FFFD}x");
+        assert_eq!(lex_single_string("\"\\uD83D\\n\"", true, policy).unwrap(), "\u{FFFD}\n");
+        assert_eq!(lex_single_string("\"\\uD83D\\u0041\"", false, policy).unwrap(), "\\uFFFD\\u0041");
     }
 
     #[test]
-    fn lex_escape_unicode_pound_and_stringify()
+    fn lex_escape_unicode_lone_surrogates_preserve()
     {
-        let first_token = "£";
-        let input = String::from("\"\\u00A3\"");
-        let mut lexer = Lexer::new_copy(&input, true);
+        let policy = EnumSurrogatePolicy::PRESERVE;
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert_eq!(lex_single_string("\"\\ude00\"", false, policy).unwrap(), "\\uDE00");
+        assert_eq!(lex_single_string("\"\\uD83D\\u0041\"", false, policy).unwrap(), "\\uD83D\\u0041");
+    }
+
+    #[test]
+    fn lex_escape_unicode_lone_surrogates_preserve_stringify()
+    {
+        let inputs = [
+            ("\"\\ude00\"", "\u{FFFD}", vec![ (0, 0xDE00) ]),
+            ("\"\\uD83D\\u0041\"", "\u{FFFD}A", vec![ (0, 0xD83D) ]),
+            ("\"é\\uD83D\\uD83D\\uDE00\\uDC00\"", "é\u{FFFD}😀\u{FFFD}", vec![ (2, 0xD83D), (9, 0xDC00) ]),
+            // The escape's text is kept as is so it can't be mistaken for a lone surrogate.
+            ("\"\\\\uD83D\"", "\\uD83D", vec![]),
+        ];
 
+        for (input, expected, lone_surrogates) in inputs
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            let options = ParserOptions::builder().stringify(true).surrogate_policy(EnumSurrogatePolicy::PRESERVE).build();
+            let mut lexer = Lexer::new_copy(input, &options);
+
+            let token = lexer.next_token().unwrap();
+            let token_string = token.downcast_ref::<TokenString>().unwrap();
+            assert_eq!(token_string.get_value(), expected, "{}", input);
+            assert_eq!(token_string.get_lone_surrogates(), &lone_surrogates[..], "{}", input);
         }
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+    #[test]
+    fn lex_escape_unicode_bad_low_surrogate_digits()
+    {
+        let error = lex_single_string("\"\\uD83D\\uDEZZ\"", true, EnumSurrogatePolicy::REPLACE).err().unwrap();
+        assert!(matches!(error.get_kind(), EnumParseErrorKind::InvalidUnicodeEscape(_)));
+        assert_eq!(error.get_start(), 7);
     }
 
     #[test]
-    fn lex_escape_unicode_abcd_and_stringify()
+    fn lex_token_double_is_correctly_rounded()
     {
-        let first_token = "ꯍ";
-        let input = String::from("\"\\uaBcD\"");
-        let mut lexer = Lexer::new_copy(&input, true);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let inputs = [ "0.1", "123.45E10", "-0.3", "3.141592653589793238462643383279", "2.2250738585072011e-308", "9007199254740993" ];
 
+        for input in inputs
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            let mut lexer = Lexer::new_copy(&String::from(input), &ParserOptions::default());
+            let token = lexer.next_token().unwrap();
+            assert_eq!(token.as_double().unwrap().to_bits(), input.parse::<f64>().unwrap().to_bits(), "{}", input);
         }
-
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
     }
 
-    #[test]
-    fn lex_escape_unicode_beef_and_stringify()
+    fn lex_single_number(input: &str, lenient_numbers: bool) -> Result<f64, ParseError>
     {
-        let first_token = "뻯";
-        let input = String::from("\"\\uBeeF\"");
-        let mut lexer = Lexer::new_copy(&input, true);
+        let mut lexer = Lexer::new_copy(&String::from(input), &ParserOptions::builder().lenient_numbers(lenient_numbers).build());
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let token = lexer.next_token()?;
 
+        // The whole input should be a single number.
+        if !lexer.is_at_end()
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            match lexer.next_token()
+            {
+                Ok(token) => { return Err(lexer.token_error(EnumParseErrorKind::TrailingData(describe_token(&*token)))); },
+                Err(error) => { return Err(error); },
+            }
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        return Ok(token.as_double().unwrap());
     }
 
     #[test]
-    fn lex_escape_unicode_four_zeroes_stringify()
+    fn lex_strict_numbers_valid()
     {
-        let first_token = " ";
-        let input = String::from("\"\\u0000\"");
-        let mut lexer = Lexer::new_copy(&input, true);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let inputs = [ "0", "-0", "0.5", "-0.5", "10", "123.456", "1e5", "1E5", "1e+5", "1e-5", "1.5E+10", "-0e0", "0.0e-0", "9007199254740993" ];
 
+        for input in inputs
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), first_token);
+            assert_eq!(lex_single_number(input, false).map(f64::to_bits), Ok(input.parse::<f64>().unwrap().to_bits()), "{}", input);
         }
-
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_escape_unicode_random_gen_series_stringify()
-    {
-        let unicode_chars: [char; 16] = [ '뜿', '柹', '裧', 'ꒊ', 'ꍵ', '낸', '깥', '軒',
-                                          '', 'ꇏ', '녢', '枈', '', 'Ꟃ', '쩿', 'ꪶ'
-        ];
-
-        let unicode_raw: [u16; 16] = [ 0xb73f, 0x67f9, 0x88e7, 0xa48a, 0xa375, 0xb0b8, 0xae65, 0x8ed2,
-                                        0xe777, 0xa1cf, 0xb162, 0x6788, 0xf384, 0xa7c2, 0xca7f, 0xaab6
+    fn lex_strict_numbers_invalid()
+    {
+        let inputs =
+        [
+            (".5", "expected a digit before '.' (ex. '0.5' rather than '.5')", 1),
+            ("-.5", "expected a digit after '-'", 1),
+            ("-", "expected a digit after '-'", 1),
+            ("01", "leading zeros are not allowed", 2),
+            ("-007", "leading zeros are not allowed", 3),
+            ("1.", "expected a digit after '.'", 2),
+            ("1.e5", "expected a digit after '.'", 2),
+            ("1e", "expected a digit in the exponent", 2),
+            ("1e-", "expected a digit in the exponent", 3),
+            ("1e+", "expected a digit in the exponent", 3),
+            ("1.2.3", "unexpected '.' in number", 4),
+            ("1e5e5", "unexpected 'e' in number", 4),
+            ("1e--5", "expected a digit in the exponent", 3),
+            ("1-2", "unexpected '-' in number", 2),
         ];
 
-        assert_eq!(unicode_chars.len(), unicode_raw.len());
-        let mut input = String::with_capacity(0x10);
-
-        for i in 0..unicode_chars.len()
+        for (input, msg, end) in inputs
         {
-            let ch = *unicode_chars.get(i).expect("missing char");
-            let val = *unicode_raw.get(i).expect("missing raw value");
-
-            input.clear();
-            input.push_str("\"\\u");
+            let error = lex_single_number(input, false).expect_err(input);
+            assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidNumber(String::from(msg)), "{}", input);
+            assert_eq!(error.get_start(), 0, "{}", input);
+            assert_eq!(error.get_end(), end, "{}", input);
+        }
 
-            for j in (0..4).rev()
-            {
-                let mut sub_val: u8 = ((val >> (j * 4)) & 0x0F) as u8;
+        assert!(lex_single_number("0x10", false).is_err());
+    }
 
-                match sub_val
-                {
-                    0..=9 => { sub_val += '0' as u8; },
-                    0x0A..=0x0F => { sub_val -= 10;  sub_val += 'A' as u8; },
-                    _ => { println!("sub_val: {0}", sub_val); assert!(false); },
-                }
+    #[test]
+    fn lex_lenient_numbers()
+    {
+        let inputs = [ (".5", 0.5), ("-.5", -0.5), ("5.", 5.0), ("007", 7.0), ("1e+5", 1e5), ("1.5e-2", 0.015) ];
 
-                input.push(sub_val as char);
-            }
+        for (input, expected) in inputs
+        {
+            assert_eq!(lex_single_number(input, true), Ok(expected), "{}", input);
+        }
 
-            input.push('"');
+        for input in [ "-", ".", "1e", "1e-", "1.2.3", "1e5e5", "1-2" ]
+        {
+            assert!(lex_single_number(input, true).is_err(), "{}", input);
+        }
+    }
 
-            let mut lexer = Lexer::new_copy(&input, true);
-            let mut token_result = lexer.next_token();
-            assert!(token_result.is_ok());
+    fn lex_json5(input: &str) -> Result<Vec<Rc<dyn TokenTrait>>, ParseError>
+    {
+        let options = ParserOptions::builder().stringify(true).dialect(EnumDialect::JSON5).build();
+        let mut lexer = Lexer::new_copy(&String::from(input), &options);
 
-            {
-                let token = token_result.unwrap();
-                assert_eq!(token.get_type(), EnumTokenType::STRING);
-                assert!(token.is_string());
-                assert_eq!(token.as_string().unwrap().chars().next().unwrap(), ch);
-            }
+        let mut tokens = Vec::<Rc<dyn TokenTrait>>::new();
 
-            token_result = lexer.next_token();
-            assert!(token_result.is_err());
+        while !lexer.is_at_end()
+        {
+            tokens.push(lexer.next_token()?);
         }
+
+        return Ok(tokens);
     }
 
-    #[test]
-    fn lex_escape_unicode_four_zs_stringify_invalid()
+    fn lex_json5_number(input: &str) -> (f64, String)
     {
-        let input = String::from("\"\\uzzZZ\"");
-        let mut lexer = Lexer::new_copy(&input, true);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let tokens = lex_json5(input).expect(input);
+        assert_eq!(tokens.len(), 1, "{}", input);
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+        let token = tokens[0].downcast_ref::<TokenDouble>().expect(input);
+        return (token.get_value(), token.get_lexeme().clone());
     }
 
     #[test]
-    fn lex_simple_key_value_pair()
+    fn lex_json5_comments_and_whitespace()
     {
-        let first_token = String::from("{");
-        let second_token = String::from("key");
-        let third_token = String::from(":");
-        let fourth_token = String::from("value");
-        let fifth_token = String::from("}");
-        let input = String::from("{ \"key\": \"value\" }");
-        let mut lexer = Lexer::new_copy(&input, false);
+        let tokens = lex_json5("// line comment\n/* block\ncomment */ [\u{A0}1,\u{FEFF}/**/2 ]// end").unwrap();
+        let described: Vec<String> = tokens.iter().map(|token| describe_token(&**token)).collect();
+        assert_eq!(described, vec![ "'['", "number 1", "','", "number 2", "']'" ]);
 
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let error = lex_json5("[1] /* never closed").err().expect("Unterminated comment");
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnterminatedComment);
+        assert_eq!(error.get_start(), 4);
+        assert_eq!(error.get_end(), 19);
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
-        }
+        let error = lex_json5("/ 1").err().expect("Lone '/'");
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('/'));
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        // Comments are still an error in strict JSON.
+        let mut lexer = Lexer::new_copy(&String::from("// comment"), &ParserOptions::default());
+        assert_eq!(lexer.next_token().err().unwrap().get_kind(), &EnumParseErrorKind::UnexpectedCharacter('/'));
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), &second_token);
-        }
+    #[test]
+    fn lex_json5_strings()
+    {
+        let tokens = lex_json5(r#"'single "quoted"' 'it\'s' "line \
+continued" '\x41\v\0\q'"#).unwrap();
+        let strings: Vec<&String> = tokens.iter().map(|token| token.as_string().unwrap()).collect();
+        assert_eq!(strings, vec![ "single \"quoted\"", "it's", "line continued", "A\x0B\0q" ]);
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let tokens = lex_json5("'crlf \\\r\ncontinued'").unwrap();
+        assert_eq!(tokens[0].as_string().unwrap(), "crlf continued");
 
+        for (input, ch) in [ ("'\\x4'", 'x'), ("'\\01'", '0'), ("'\\7'", '7') ]
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            let error = lex_json5(input).err().expect(input);
+            assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidEscape(ch), "{}", input);
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let error = lex_json5("'unterminated").err().expect("Unterminated string");
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnterminatedString);
+    }
+
+    #[test]
+    fn lex_json5_numbers()
+    {
+        let inputs = [
+            ("0x1F", 31.0, "31"), ("-0xff", -255.0, "-255"), ("+1", 1.0, "1"), (".5", 0.5, "0.5"),
+            ("-.5", -0.5, "-0.5"), ("5.", 5.0, "5"), ("5.e3", 5000.0, "5e3"), ("+1.5E+2", 150.0, "1.5E+2"),
+            ("0x10000000000000000", 18446744073709551616.0, "18446744073709552000"),
+        ];
 
+        for (input, value, lexeme) in inputs
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), &fourth_token);
+            assert_eq!(lex_json5_number(input), (value, String::from(lexeme)), "{}", input);
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert_eq!(lex_json5_number("Infinity"), (f64::INFINITY, String::from("Infinity")));
+        assert_eq!(lex_json5_number("-Infinity"), (f64::NEG_INFINITY, String::from("-Infinity")));
+        assert_eq!(lex_json5_number("+Infinity"), (f64::INFINITY, String::from("Infinity")));
+        assert!(lex_json5_number("NaN").0.is_nan());
+        assert!(lex_json5_number("-NaN").0.is_nan());
 
+        for input in [ "0x", "0xG", "+", ".", "-.e5", "007", "1.2.3", "+Inf", "1e" ]
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &fifth_token);
+            assert!(lex_json5(input).is_err(), "{}", input);
         }
-
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
     }
 
     #[test]
-    fn lex_multi_field_object()
+    fn lex_json5_identifiers()
     {
-        let first_token = String::from("{");
-        let second_token = String::from("1");
-        let third_token = String::from(":");
-        let fourth_token: f64 = 123.0;
-        let fifth_token = String::from(",");
-        let sixth_token = String::from("2");
-        let seventh_token = &third_token;
-        let eighth_token: f64 = 456.0;
-        let ninth_token = String::from("}");
-        let input = String::from("{ \"1\": 123, \"2\": 456 }");
-        let mut lexer = Lexer::new_copy(&input, false);
-
-        let mut token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let tokens = lex_json5("$id _private café2 Infinity").unwrap();
+        let described: Vec<String> = tokens.iter().map(|token| describe_token(&**token)).collect();
+        assert_eq!(described, vec![ "'$id'", "'_private'", "'café2'", "number Infinity" ]);
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &first_token);
-        }
+        // Without JSON5 these are errors or plain symbols.
+        let mut lexer = Lexer::new_copy(&String::from("Infinity $id"), &ParserOptions::default());
+        assert!(lexer.next_token().unwrap().is_symbol());
+        assert_eq!(lexer.next_token().err().unwrap().get_kind(), &EnumParseErrorKind::UnexpectedCharacter('$'));
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+    /// Hands out its bytes one at a time, so every character and token is split across reads.
+    struct OneByteReader
+    {
+        bytes: Vec<u8>,
+        position: usize,
+    }
 
+    impl Read for OneByteReader
+    {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), &second_token);
+            if self.position >= self.bytes.len() || buf.is_empty()
+            {
+                return Ok(0);
+            }
+
+            buf[0] = self.bytes[self.position];
+            self.position += 1;
+            return Ok(1);
         }
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+    /// Fails after handing out its bytes.
+    struct FailingReader
+    {
+        bytes: Option<Vec<u8>>,
+    }
 
+    impl Read for FailingReader
+    {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &third_token);
+            match self.bytes.take()
+            {
+                Some(bytes) => { buf[..bytes.len()].copy_from_slice(&bytes); return Ok(bytes.len()); },
+                None => { return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken pipe")); },
+            }
         }
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+    fn lex_all(lexer: &mut Lexer) -> (Vec<String>, ParseError)
+    {
+        let mut described = Vec::new();
 
+        loop
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), fourth_token);
+            match lexer.next_token()
+            {
+                Ok(token) => { described.push(describe_token(&*token)); },
+                Err(error) => { return (described, error); },
+            }
         }
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+    #[test]
+    fn lex_reader_matches_string_input()
+    {
+        let input = String::from("// café\n{ 'ké': [\"\\u00e9 ü\", 1.5e3, -0x1F, true, null] /* a */ , key: \"😀\" } /*/ */");
+        let options = ParserOptions::builder().dialect(EnumDialect::JSON5).stringify(true).build();
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &fifth_token);
-        }
+        let mut expected = Lexer::new_copy(&input, &options);
+        let mut streamed = Lexer::new_reader(OneByteReader { bytes: input.clone().into_bytes(), position: 0 }, &options);
+        let (expected_tokens, expected_error) = lex_all(&mut expected);
+        let (streamed_tokens, streamed_error) = lex_all(&mut streamed);
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert_eq!(streamed_tokens, expected_tokens);
+        assert_eq!(streamed_error, expected_error);
+        assert_eq!(streamed.get_input_size(), input.len());
+    }
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::STRING);
-            assert!(token.is_string());
-            assert_eq!(token.as_string().unwrap(), &sixth_token);
-        }
+    #[test]
+    fn lex_reader_discards_consumed_input()
+    {
+        let input = format!("[{}1]", "1, ".repeat(200000));
+        let mut lexer = Lexer::new_reader(Cursor::new(input.clone().into_bytes()), &ParserOptions::default());
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let mut count = 0;
+        let mut max_held = 0;
 
+        while let Ok(token) = lexer.next_token()
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), seventh_token);
+            // A token can always be restored to right after lexing it.
+            let token_start = lexer.get_token_start().clone();
+            lexer.restore(&token_start);
+            assert_eq!(describe_token(&*lexer.next_token().unwrap()), describe_token(&*token));
+
+            count += 1;
+            max_held = max_held.max(lexer.get_input().len());
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        assert_eq!(count, 400003);
+        assert!(lexer.is_at_end());
+        assert!(lexer.get_input_start() > 0);
+        assert!(max_held < 3 * READ_CHUNK_SIZE, "{}", max_held);
+    }
 
+    #[test]
+    fn lex_reader_errors()
+    {
+        // Invalid UTF-8, also when a sequence is split across reads or cut short by the end of input.
+        for (bytes, start) in [ (b"[\"a\xFF\"]".to_vec(), 3), (b"[\"\xC3\xA9\xC3".to_vec(), 4) ]
         {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::DOUBLE);
-            assert!(token.is_double());
-            assert_eq!(token.as_double().unwrap(), eighth_token);
+            let mut lexer = Lexer::new_reader(OneByteReader { bytes, position: 0 }, &ParserOptions::default());
+            let (_, error) = lex_all(&mut lexer);
+            assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidUtf8);
+            assert_eq!(error.get_start(), start);
         }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_ok());
+        let mut lexer = Lexer::new_reader(FailingReader { bytes: Some(b"[1, 2".to_vec()) }, &ParserOptions::default());
+        let (tokens, error) = lex_all(&mut lexer);
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ReadFailed(String::from("broken pipe")));
+        assert!(!lexer.is_at_end());
 
-        {
-            let token = token_result.unwrap();
-            assert_eq!(token.get_type(), EnumTokenType::SYMBOL);
-            assert!(token.is_symbol());
-            assert_eq!(token.as_symbol().unwrap(), &ninth_token);
-        }
+        let options = ParserOptions::builder().max_input_size(Some(10)).build();
+        let mut lexer = Lexer::new_reader(OneByteReader { bytes: b"[1, 2, 3, 4, 5]".to_vec(), position: 0 }, &options);
+        let (tokens, error) = lex_all(&mut lexer);
+        assert_eq!(tokens.len(), 7);
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 10 });
+        assert_eq!(lexer.get_input_size(), 11);
+    }
 
-        token_result = lexer.next_token();
-        assert!(token_result.is_err());
+    #[test]
+    fn lex_reader_lossy_replaces_invalid_utf8()
+    {
+        // Also when a sequence is split across reads or cut short by the end of input.
+        let mut lexer = Lexer::new_reader_lossy(OneByteReader { bytes: b"[\"a\xFF\xC3\xA9\", 1] \xC3".to_vec(), position: 0 }, &ParserOptions::default());
+        let (tokens, error) = lex_all(&mut lexer);
+        assert_eq!(tokens, vec![ "'['", "string \"a\u{1A}\u{e9}\"", "','", "number 1", "']'" ]);
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('\u{1A}'));
+        assert_eq!(error.get_start(), 12);
+        assert!(lexer.is_at_end());
     }
 }
-
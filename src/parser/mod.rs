@@ -1,6 +1,22 @@
+pub mod control;
+pub mod dialect;
+pub mod duplicate_key_policy;
+pub mod embedded_json;
+pub mod feed_result;
+pub mod json_event;
+pub mod json_events;
+pub mod json_handler;
+pub mod json_scanner;
 pub mod lexer;
+pub mod number_mode;
+pub mod parse_checks;
+pub mod parse_error;
+#[allow(clippy::module_inception)]
 pub mod parser;
+pub mod parser_options;
+pub mod push_parser;
 pub mod snapshot;
+pub mod surrogate_policy;
 pub mod token;
 pub mod token_bool;
 pub mod token_char;
@@ -8,4 +24,5 @@ pub mod token_double;
 pub mod token_null;
 pub mod token_string;
 pub mod token_symbol;
+pub mod tree_builder;
 
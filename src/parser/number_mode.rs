@@ -0,0 +1,13 @@
+/// Which RNode numbers are parsed into.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumNumberMode
+{
+    /// Every number becomes an RNodeDouble.
+    #[default]
+    DOUBLE,
+    /// Every number becomes an RNodeNumber, keeping 64-bit integers exact along with the original lexeme.
+    EXACT,
+    /// Every number becomes an RNodeDecimal, keeping its exact value however large or precise.
+    #[cfg(feature = "arbitrary_precision")]
+    DECIMAL,
+}
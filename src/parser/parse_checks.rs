@@ -0,0 +1,163 @@
+use crate::parser::dialect::EnumDialect;
+use crate::parser::lexer::Lexer;
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::snapshot::Snapshot;
+use crate::parser::token::{describe_token, EnumTokenType, TokenTrait};
+use crate::parser::token_double::TokenDouble;
+use crate::parser::token_string::TokenString;
+
+// The checks shared by the Parser and JsonEvents, so both report the same errors for the same input.
+
+/// Fails when the whole input is larger than the 'max_input_size' limit.
+pub fn check_input_size(lexer: &Lexer) -> Result<(), ParseError>
+{
+    match lexer.get_options().get_max_input_size()
+    {
+        Some(limit) if lexer.get_input_size() > limit =>
+        {
+            return Err(ParseError::new(EnumParseErrorKind::InputTooLarge { limit }, &Snapshot::new(0, 1, 1), lexer.get_input_size()));
+        },
+        _ => { return Ok(()); },
+    }
+}
+
+/// Fails when opening an array/object (the last token) inside of 'depth' enclosing ones would pass
+/// the 'max_depth' limit.
+pub fn check_depth(lexer: &Lexer, depth: usize) -> Result<(), ParseError>
+{
+    match lexer.get_options().get_max_depth()
+    {
+        Some(limit) if depth >= limit => { return Err(lexer.token_error(EnumParseErrorKind::DepthLimitExceeded { limit })); },
+        _ => { return Ok(()); },
+    }
+}
+
+/// Gets the error for another element of an array already holding 'count' of them, if it passes the 'max_elements' limit.
+pub fn get_element_limit_error(options: &ParserOptions, count: usize) -> Option<EnumParseErrorKind>
+{
+    return options.get_max_elements().filter(|limit| count >= *limit).map(|limit| EnumParseErrorKind::TooManyElements { limit });
+}
+
+/// Gets the error for another member of an object already holding 'count' of them, if it passes the 'max_members' limit.
+pub fn get_member_limit_error(options: &ParserOptions, count: usize) -> Option<EnumParseErrorKind>
+{
+    return options.get_max_members().filter(|limit| count >= *limit).map(|limit| EnumParseErrorKind::TooManyMembers { limit });
+}
+
+/// Gets the key a member starts with (the last token): a string or, with JSON5, an identifier.
+pub fn read_key(lexer: &Lexer, token: &dyn TokenTrait) -> Result<String, ParseError>
+{
+    if let Some(token_string) = token.downcast_ref::<TokenString>()
+    {
+        // A key is a plain String, so it can't keep a lone surrogate the lexer preserved in a decoded string.
+        if let Some((_, code_unit)) = token_string.get_lone_surrogates().first()
+        {
+            return Err(lexer.token_error(EnumParseErrorKind::LoneSurrogate(*code_unit as u32)));
+        }
+
+        // Nor can it record which '\uXXXX' escapes were kept as text, so a key always has them decoded.
+        return decode_unicode_escapes(token_string).map_err(|code_unit| lexer.token_error(EnumParseErrorKind::LoneSurrogate(code_unit as u32)));
+    }
+
+    match get_identifier_key(token).filter(|_| lexer.get_options().get_dialect() == EnumDialect::JSON5)
+    {
+        Some(key) =>
+        {
+            // Quoted keys are checked by the lexer along with every other string.
+            if let Some(limit) = lexer.get_options().get_max_string_length().filter(|limit| key.len() > *limit)
+            {
+                return Err(lexer.token_error(EnumParseErrorKind::StringTooLong { limit }));
+            }
+
+            return Ok(key);
+        },
+        None => { return Err(lexer.token_error(EnumParseErrorKind::ExpectedKey(describe_token(token)))); },
+    }
+}
+
+/// Creates the error for a key written from 'key_start' to 'key_end' that was first written at 'first_start'.
+pub fn get_duplicate_key_error(key: &str, first_start: &Snapshot, key_start: &Snapshot, key_end: usize) -> ParseError
+{
+    let kind = EnumParseErrorKind::DuplicateKey
+    {
+        key: String::from(key), first_start: first_start.get_start_pos(), first_line: first_start.get_line(), first_column: first_start.get_column(),
+    };
+
+    return ParseError::new(kind, key_start, key_end);
+}
+
+/// Fails unless only whitespace (or, with JSON5, comments) is left after the root value.
+pub fn expect_end_of_input(lexer: &mut Lexer) -> Result<(), ParseError>
+{
+    if lexer.is_at_end()
+    {
+        return Ok(());
+    }
+
+    let start = lexer.snap();
+    let first_char = lexer.peek_char();
+    let found = match lexer.next_token()
+    {
+        Ok(token) => describe_token(&*token),
+        Err(error) if error.get_kind().ends_input() => { return Err(error); },
+        Err(_) => format!("'{}'", first_char.unwrap()),
+    };
+
+    return Err(ParseError::new(EnumParseErrorKind::TrailingData(found), &start, lexer.get_position()));
+}
+
+/// Decodes the '\uXXXX' escapes the lexer kept in a string, failing with the first surrogate that isn't paired up.
+fn decode_unicode_escapes(token_string: &TokenString) -> Result<String, u16>
+{
+    let value = token_string.get_value();
+    let mut escapes = token_string.get_unicode_escapes().iter().peekable();
+    let mut result = String::with_capacity(value.len());
+    let mut last_end = 0;
+
+    while let Some(start) = escapes.next()
+    {
+        result.push_str(&value[last_end..*start]);
+        let mut code_units = vec![ u16::from_str_radix(&value[start + 2..start + 6], 16).expect("A kept escape should have 4 hexadecimal digits") ];
+        last_end = start + 6;
+
+        // Surrogate pairs are kept as two escapes in a row.
+        while let Some(next) = escapes.next_if(|next| **next == last_end)
+        {
+            code_units.push(u16::from_str_radix(&value[next + 2..next + 6], 16).expect("A kept escape should have 4 hexadecimal digits"));
+            last_end = next + 6;
+        }
+
+        for ch in char::decode_utf16(code_units)
+        {
+            result.push(ch.map_err(|error| error.unpaired_surrogate())?);
+        }
+    }
+
+    result.push_str(&value[last_end..]);
+    return Ok(result);
+}
+
+/// Gets the name of an unquoted JSON5 key, which is an identifier (ex. 'name' or '$_id') or a word
+/// the lexer already turned into a value (ex. 'true' or 'NaN').
+pub fn get_identifier_key(token: &dyn TokenTrait) -> Option<String>
+{
+    match token.get_type()
+    {
+        EnumTokenType::SYMBOL =>
+        {
+            let symbol = token.as_symbol().unwrap();
+            let is_identifier = symbol.starts_with(|ch: char| ch.is_alphabetic() || ch == '$' || ch == '_');
+
+            return if is_identifier { Some(symbol.clone()) } else { None };
+        },
+        EnumTokenType::BOOL => { return Some(token.as_bool().unwrap().to_string()); },
+        EnumTokenType::NULL => { return Some(String::from("null")); },
+        EnumTokenType::DOUBLE =>
+        {
+            let lexeme = token.downcast_ref::<TokenDouble>()?.get_lexeme();
+            return if lexeme == "Infinity" || lexeme == "NaN" { Some(lexeme.clone()) } else { None };
+        },
+        _ => { return None; },
+    }
+}
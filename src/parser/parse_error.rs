@@ -0,0 +1,222 @@
+use crate::parser::snapshot::Snapshot;
+
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnumParseErrorKind
+{
+    /// A character that cannot start any token (ex. '@').
+    UnexpectedCharacter(char),
+    /// A sequence of bytes that is not valid UTF-8.
+    InvalidUtf8,
+    /// Reading a streamed input failed (ex. an I/O error on a pipe).
+    ReadFailed(String),
+    /// The input ended while a value was still expected.
+    UnexpectedEOF,
+    /// A string was opened but never closed with a '"'.
+    UnterminatedString,
+    /// A '/*' comment that is never closed with a '*/' (JSON5 only).
+    UnterminatedComment,
+    /// A '\' followed by a character that is not a valid escape.
+    InvalidEscape(char),
+    /// A malformed '\uXXXX' escape sequence.
+    InvalidUnicodeEscape(String),
+    /// A '\uXXXX' escape of a UTF-16 surrogate that is missing its high/low partner.
+    LoneSurrogate(u32),
+    /// A number that does not follow the number grammar.
+    InvalidNumber(String),
+    /// A number too large to be parsed into a double (ex. '1e999'), which 'EnumNumberMode::EXACT' can keep.
+    NumberOutOfRange,
+    /// Expected a value (object, array, string, number, bool or null).
+    ExpectedValue(String),
+    /// Expected a string key inside of an object.
+    ExpectedKey(String),
+    /// Expected the ':' between an object key and its value.
+    ExpectedColon(String),
+    /// Expected a ',' or the closing bracket of the enclosing array/object.
+    ExpectedCommaOrEnd { close: char, found: String },
+    /// A key that already appeared earlier in the same object (the error spans the second occurrence).
+    DuplicateKey { key: String, first_start: usize, first_line: usize, first_column: usize },
+    /// Arrays/objects nested more deeply than the 'max_depth' limit.
+    DepthLimitExceeded { limit: usize },
+    /// A string or key longer (in bytes) than the 'max_string_length' limit.
+    StringTooLong { limit: usize },
+    /// A number longer (in bytes) than the 'max_string_length' limit, which also applies to numbers.
+    NumberTooLong { limit: usize },
+    /// An object with more members than the 'max_members' limit.
+    TooManyMembers { limit: usize },
+    /// An array with more elements than the 'max_elements' limit.
+    TooManyElements { limit: usize },
+    /// An input larger (in bytes) than the 'max_input_size' limit.
+    InputTooLarge { limit: usize },
+    /// Something other than whitespace follows the root value.
+    TrailingData(String),
+    /// Text before the first RS (0x1E) of a JSON text sequence.
+    MissingRecordSeparator,
+    /// A JSON text sequence record holding a number, bool or null that isn't followed by whitespace,
+    /// so it may have been cut short.
+    TruncatedRecord,
+}
+
+impl EnumParseErrorKind
+{
+    /// Checks for an error about the input running out (or being cut short), after which there is
+    /// nothing more to lex.
+    pub fn ends_input(&self) -> bool
+    {
+        return matches!(self, EnumParseErrorKind::UnexpectedEOF | EnumParseErrorKind::InvalidUtf8 | EnumParseErrorKind::ReadFailed(_) | EnumParseErrorKind::InputTooLarge { .. });
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError
+{
+    kind: EnumParseErrorKind,
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ParseError
+{
+    pub fn new(kind: EnumParseErrorKind, start: &Snapshot, end: usize) -> Self
+    {
+        let start_pos = start.get_start_pos();
+        Self { kind, start: start_pos, end: end.max(start_pos), line: start.get_line(), column: start.get_column() }
+    }
+
+    pub fn get_kind(&self) -> &EnumParseErrorKind
+    {
+        return &self.kind;
+    }
+
+    /// The byte offset of the start of the offending span.
+    pub fn get_start(&self) -> usize
+    {
+        return self.start;
+    }
+
+    /// The byte offset one past the end of the offending span.
+    pub fn get_end(&self) -> usize
+    {
+        return self.end;
+    }
+
+    /// 1-based line number of the start of the offending span.
+    pub fn get_line(&self) -> usize
+    {
+        return self.line;
+    }
+
+    /// 1-based column (in characters) of the start of the offending span.
+    pub fn get_column(&self) -> usize
+    {
+        return self.column;
+    }
+
+    /// Moves an error found by parsing a part of a larger input (ex. one line of NDJSON) to where it
+    /// is in the whole input, given where the part starts.
+    pub fn relocate(mut self, part_start: &Snapshot) -> Self
+    {
+        let (offset, line_offset, column_offset) = (part_start.get_start_pos(), part_start.get_line() - 1, part_start.get_column() - 1);
+
+        // Only the first line of the part starts part way into a line of the whole input.
+        if self.line == 1
+        {
+            self.column += column_offset;
+        }
+
+        if let EnumParseErrorKind::DuplicateKey { first_start, first_line, first_column, .. } = &mut self.kind
+        {
+            if *first_line == 1
+            {
+                *first_column += column_offset;
+            }
+
+            *first_start += offset;
+            *first_line += line_offset;
+        }
+
+        self.start += offset;
+        self.end += offset;
+        self.line += line_offset;
+        return self;
+    }
+
+    pub fn get_message(&self) -> String
+    {
+        match &self.kind
+        {
+            EnumParseErrorKind::UnexpectedCharacter(ch) => { format!("unexpected character '{}'", ch.escape_debug()) },
+            EnumParseErrorKind::InvalidUtf8 => { String::from("invalid UTF-8 byte sequence") },
+            EnumParseErrorKind::ReadFailed(msg) => { format!("failed to read the input: {}", msg) },
+            EnumParseErrorKind::UnexpectedEOF => { String::from("unexpected end of input") },
+            EnumParseErrorKind::UnterminatedString => { String::from("missing closing double-quote ('\"')") },
+            EnumParseErrorKind::UnterminatedComment => { String::from("missing closing '*/' of the comment") },
+            EnumParseErrorKind::InvalidEscape(ch) => { format!("'\\{}' is not a supported escape sequence", ch.escape_debug()) },
+            EnumParseErrorKind::InvalidUnicodeEscape(msg) => { format!("invalid unicode escape: {}", msg) },
+            EnumParseErrorKind::LoneSurrogate(code_unit) => { format!("lone UTF-16 surrogate '\\u{:04X}'", code_unit) },
+            EnumParseErrorKind::InvalidNumber(msg) => { format!("invalid number: {}", msg) },
+            EnumParseErrorKind::NumberOutOfRange => { String::from("number is too large to be a double") },
+            EnumParseErrorKind::ExpectedValue(found) => { format!("expected a value but found {}", found) },
+            EnumParseErrorKind::ExpectedKey(found) => { format!("expected a string key but found {}", found) },
+            EnumParseErrorKind::ExpectedColon(found) => { format!("expected ':' but found {}", found) },
+            EnumParseErrorKind::ExpectedCommaOrEnd { close, found } => { format!("expected ',' or '{}' but found {}", close, found) },
+            EnumParseErrorKind::DuplicateKey { key, first_line, first_column, .. } => { format!("duplicate key \"{}\" (first defined at line {}, column {})", key, first_line, first_column) },
+            EnumParseErrorKind::DepthLimitExceeded { limit } => { format!("arrays and objects are nested more than {} deep", limit) },
+            EnumParseErrorKind::StringTooLong { limit } => { format!("string is longer than the limit of {} bytes", limit) },
+            EnumParseErrorKind::NumberTooLong { limit } => { format!("number is longer than the limit of {} bytes", limit) },
+            EnumParseErrorKind::TooManyMembers { limit } => { format!("object has more than the limit of {} members", limit) },
+            EnumParseErrorKind::TooManyElements { limit } => { format!("array has more than the limit of {} elements", limit) },
+            EnumParseErrorKind::InputTooLarge { limit } => { format!("input is larger than the limit of {} bytes", limit) },
+            EnumParseErrorKind::TrailingData(found) => { format!("expected the end of input after the root value but found {}", found) },
+            EnumParseErrorKind::MissingRecordSeparator => { String::from("expected a record separator (RS) before the record") },
+            EnumParseErrorKind::TruncatedRecord => { String::from("the record may have been truncated") },
+        }
+    }
+}
+
+impl Display for ParseError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at line {}, column {}", self.get_message(), self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+    use crate::parser::snapshot::Snapshot;
+
+    #[test]
+    fn create_parse_error()
+    {
+        let snapshot = Snapshot::new(10, 2, 3);
+        let error = ParseError::new(EnumParseErrorKind::UnexpectedCharacter('@'), &snapshot, 11);
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('@'));
+        assert_eq!(error.get_start(), 10);
+        assert_eq!(error.get_end(), 11);
+        assert_eq!(error.get_line(), 2);
+        assert_eq!(error.get_column(), 3);
+        assert_eq!(error.to_string(), "unexpected character '@' at line 2, column 3");
+    }
+
+    #[test]
+    fn relocate_parse_error()
+    {
+        let part_start = Snapshot::new(100, 5, 2);
+
+        let error = ParseError::new(EnumParseErrorKind::UnexpectedCharacter('@'), &Snapshot::new(3, 1, 4), 4).relocate(&part_start);
+        assert_eq!((error.get_start(), error.get_end(), error.get_line(), error.get_column()), (103, 104, 5, 5));
+
+        let kind = EnumParseErrorKind::DuplicateKey { key: String::from("a"), first_start: 1, first_line: 1, first_column: 2 };
+        let error = ParseError::new(kind, &Snapshot::new(12, 2, 1), 15).relocate(&part_start);
+        assert_eq!((error.get_start(), error.get_end(), error.get_line(), error.get_column()), (112, 115, 6, 1));
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::DuplicateKey { key: String::from("a"), first_start: 101, first_line: 5, first_column: 3 });
+    }
+}
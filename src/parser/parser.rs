@@ -1,409 +1,890 @@
+use crate::parser::dialect::EnumDialect;
+use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
 use crate::parser::lexer::Lexer;
+use crate::parser::number_mode::EnumNumberMode;
+use crate::parser::parse_checks::{check_depth, check_input_size, expect_end_of_input, get_duplicate_key_error, get_element_limit_error, get_member_limit_error, read_key};
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::parser::snapshot::Snapshot;
+use crate::parser::parser_options::ParserOptions;
 use crate::rnodes::rnode::RNode;
 use crate::rnodes::rnode_array::RNodeArray;
 use crate::rnodes::rnode_bool::RNodeBool;
+#[cfg(feature = "arbitrary_precision")]
+use crate::rnodes::rnode_decimal::RNodeDecimal;
 use crate::rnodes::rnode_double::RNodeDouble;
+use crate::rnodes::rnode_error::RNodeError;
 use crate::rnodes::rnode_null::RNodeNull;
+use crate::rnodes::rnode_number::RNodeNumber;
 use crate::rnodes::rnode_object::RNodeObject;
 use crate::rnodes::rnode_string::RNodeString;
+use crate::utils::ordered_map::OrderedMap;
 
-use super::token::{EnumTokenType, TokenTrait};
+use super::token::{describe_token, is_symbol, EnumTokenType, TokenTrait};
+use super::token_double::TokenDouble;
+use super::token_string::TokenString;
 
 use std::rc::Rc;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Where an object key was written, from the start of the token to one past its end.
+struct KeySpan
+{
+    start: Snapshot,
+    end: usize,
+}
+
+/// What followed a value inside of an array or object.
+#[derive(Debug, PartialEq)]
+enum EnumSeparator
+{
+    /// A ',' (or, when recovering, a missing ',' before the next value).
+    COMMA,
+    /// The closing bracket of the array/object (or, when recovering, the end of input or
+    /// the closing bracket of an enclosing array/object).
+    END,
+}
+
+/// An array or object whose values are still being parsed.
+enum EnumFrame
+{
+    ARRAY(Vec<Rc<dyn RNode>>),
+    OBJECT(Box<ObjectFrame>),
+}
+
+impl EnumFrame
+{
+    fn into_node(self) -> Rc<dyn RNode>
+    {
+        match self
+        {
+            EnumFrame::ARRAY(nodes) => { return Rc::new(RNodeArray::new(nodes)); },
+            EnumFrame::OBJECT(object) => { return Rc::new(RNodeObject::new_multi(object.nodes, object.duplicates)); },
+        }
+    }
+}
+
+struct ObjectFrame
+{
+    nodes: OrderedMap<Rc<dyn RNode>>,
+    duplicates: OrderedMap<Vec<Rc<dyn RNode>>>,
+    /// Where each key was first written (for reporting duplicates).
+    first_keys: HashMap<String, Snapshot>,
+    /// How many members were parsed so far, counting duplicate keys.
+    member_count: usize,
+    /// The key of the member whose value is being parsed.
+    key: Option<(String, KeySpan)>,
+}
+
+impl ObjectFrame
+{
+    fn new() -> Self
+    {
+        Self { nodes: OrderedMap::new(), duplicates: OrderedMap::new(), first_keys: HashMap::new(), member_count: 0, key: None }
+    }
+}
 
 pub struct Parser
 {
     lexer: Lexer,
-    guess_table: HashMap<String, fn(&mut Parser, Rc<dyn TokenTrait>) -> Option<Rc<dyn RNode>>>,
+    /// Maps each symbol that opens an array/object to the frame it pushes.
+    guess_table: HashMap<String, fn() -> EnumFrame>,
+    recover: bool,
+    errors: Vec<ParseError>,
+    /// Problems that didn't stop the parse (ex. duplicate keys).
+    warnings: Vec<ParseError>,
+    /// Where each array/object being parsed starts, which after a failed parse are the ones it left open.
+    open_starts: Vec<usize>,
 }
 
 impl Parser
 {
-    #[allow(dead_code)]
-    pub fn new_copy(input: &String, stringify: bool) -> Self
+    pub fn new_copy(input: &str, options: &ParserOptions) -> Self
     {
-        let mut result = Self { lexer: Lexer::new_copy(&input, stringify), guess_table: HashMap::new() };
-        result.init_guess_table();
-        return result;
+        return Self::new_move(String::from(input), options);
+    }
+
+    /// Creates a Parser from raw bytes, which are expected to be UTF-8.
+    pub fn new_bytes(input: Vec<u8>, options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_bytes(input, options));
+    }
+
+    pub fn new_move(input: String, options: &ParserOptions) -> Self
+    {
+        return Self::new_lexer(Lexer::new_move(input, options));
     }
 
-    #[allow(dead_code)]
-    pub fn new_move(input: String, stringify: bool) -> Self
+    /// Creates a Parser that reads its input from 'source' as it goes (see 'Lexer::new_reader'),
+    /// so only the resulting tree is held in memory rather than the whole input too.
+    pub fn new_reader(source: impl Read + 'static, options: &ParserOptions) -> Self
     {
-        let mut result = Self { lexer: Lexer::new_move(input, stringify), guess_table: HashMap::new() };
+        return Self::new_lexer(Lexer::new_reader(source, options));
+    }
+
+    /// Creates a Parser for the tokens of 'lexer' (ex. one made with 'Lexer::new_reader_lossy').
+    pub fn new_lexer(lexer: Lexer) -> Self
+    {
+        let mut result = Self { lexer, guess_table: HashMap::new(), recover: false, errors: Vec::new(), warnings: Vec::new(), open_starts: Vec::new() };
         result.init_guess_table();
         return result;
     }
 
     fn init_guess_table(&mut self)
     {
-        self.guess_table.insert("[".to_string(), try_parse_array);
-        self.guess_table.insert("{".to_string(), try_parse_object);
+        self.guess_table.insert("[".to_string(), || EnumFrame::ARRAY(Vec::new()));
+        self.guess_table.insert("{".to_string(), || EnumFrame::OBJECT(Box::new(ObjectFrame::new())));
     }
 
-    #[allow(dead_code)]
-    pub fn parse(&mut self) -> Result<Rc<dyn RNode>, String>
+    pub fn get_options(&self) -> &ParserOptions
     {
-        loop
-        {
-            let snapshot = self.lexer.snap();
-            let token_result = self.lexer.next_token();
+        return self.lexer.get_options();
+    }
 
-            match token_result
-            {
-                Ok(_token) =>
-                {
-                    self.lexer.restore(&snapshot);
-                    let opt_node_type = self.try_parse_type();
-
-                    match opt_node_type
-                    {
-                        Some(node_type) => { return Ok(node_type); },
-                        None => { return Err(String::from("Failed to parse token into an AST RNode")); },
-                    }
-                },
-                Err(e) => { return Err(e); },
-            }
-        }
+    /// Takes the warnings (ex. duplicate keys that were allowed by the policy) from the last parse.
+    pub fn take_warnings(&mut self) -> Vec<ParseError>
+    {
+        return std::mem::take(&mut self.warnings);
+    }
+
+    fn is_json5(&self) -> bool
+    {
+        return self.get_options().get_dialect() == EnumDialect::JSON5;
+    }
+
+    /// Gets the text being parsed. For a streamed input this is only the part still being held,
+    /// which starts at the byte offset 'get_input_start'.
+    pub fn get_input(&self) -> &String
+    {
+        return self.lexer.get_input();
+    }
+
+    /// Gets the byte offset (into the whole input) of the start of 'get_input'.
+    pub fn get_input_start(&self) -> usize
+    {
+        return self.lexer.get_input_start();
+    }
+
+    /// Parses a single root value, which must be followed by nothing but whitespace.
+    pub fn parse(&mut self) -> Result<Rc<dyn RNode>, ParseError>
+    {
+        check_input_size(&self.lexer)?;
+
+        let node = self.try_parse_type()?;
+        expect_end_of_input(&mut self.lexer)?;
 
-        // return Err(String::from("Un-expected error occurred. Is this a Parser bug?"));
+        return Ok(node);
     }
 
-    #[allow(dead_code)]
-    fn try_parse_type(&mut self) -> Option<Rc<dyn RNode>>
+    /// Parses the input as a stream of concatenated values (ex. '{"a": 1} {"b": 2} 3') and
+    /// returns all of them in order. An input of only whitespace yields no values.
+    pub fn parse_stream(&mut self) -> Result<Vec<Rc<dyn RNode>>, ParseError>
     {
-        let token_result = self.lexer.next_token();
+        let mut nodes = Vec::<Rc<dyn RNode>>::new();
+        check_input_size(&self.lexer)?;
 
-        match token_result
+        while !self.lexer.is_at_end()
         {
-            Ok(token) =>
-            {
-                match token.get_type()
-                {
-                    EnumTokenType::BOOL => { return Some(Rc::new(RNodeBool::new(token.as_bool().unwrap()))); },
-                    EnumTokenType::DOUBLE => { return Some(Rc::new(RNodeDouble::new(token.as_double().unwrap()))); },
-                    EnumTokenType::NULL => { return Some(Rc::new(RNodeNull::new())); },
-                    EnumTokenType::STRING => { return Some(Rc::new(RNodeString::new_copy(token.as_string().unwrap()))); },
-                    EnumTokenType::SYMBOL =>
-                    {
-                        let symbol = token.as_symbol().unwrap();
-                        let opt_guess = &self.guess_table.get(symbol);
-
-                        if opt_guess.is_some()
-                        {
-                            let fp = opt_guess.expect("It's a tarp!!");
-                            return fp(self, Rc::clone(&token));
-                        }
-
-                        return None;
-                    },
-                    _ => { panic!(); },
-                }
-            },
-            Err(_) => { return None; },
+            nodes.push(self.try_parse_type()?);
         }
+
+        return Ok(nodes);
     }
-}
 
-fn try_parse_array(parser: &mut Parser, token_in: Rc<dyn TokenTrait>) -> Option<Rc<dyn RNode>>
-{
-    if !token_in.is_symbol() || token_in.as_symbol().unwrap() != "["
+    /// Parses a single value starting at 'start' (ex. a position found by scanning the text), leaving
+    /// the parser just past it. Unlike 'parse', whatever follows the value is left alone.
+    pub fn parse_value_at(&mut self, start: &Snapshot) -> Result<Rc<dyn RNode>, ParseError>
+    {
+        self.lexer.restore(start);
+        self.warnings.clear();
+        return self.try_parse_type();
+    }
+
+    /// Moves from 'start' up to the next character 'is_target' accepts, returning where it is (see
+    /// 'Lexer::skip_until'). Returns None when the input ends first.
+    pub fn find_from(&mut self, start: &Snapshot, is_target: fn(char) -> bool) -> Option<Snapshot>
+    {
+        self.lexer.restore(start);
+        return if self.lexer.skip_until(is_target) { Some(self.lexer.snap()) } else { None };
+    }
+
+    /// Gets the position of the start of the last token read (ex. the one 'parse_value_at' failed at).
+    pub fn get_token_start(&self) -> &Snapshot
+    {
+        return self.lexer.get_token_start();
+    }
+
+    /// Gets where each array/object left open by the last failed parse starts (outermost first).
+    pub fn get_open_starts(&self) -> &[usize]
+    {
+        return &self.open_starts;
+    }
+
+    /// Keeps the text of a streamed input from the byte offset 'position' on, even once the parser has
+    /// read past it, until it is called again with None (see 'Lexer::hold_from').
+    pub fn hold_from(&mut self, position: Option<usize>)
+    {
+        self.lexer.hold_from(position);
+    }
+
+    /// Gets the current position (ex. just past the value parsed by 'parse_value_at').
+    pub fn snap(&self) -> Snapshot
     {
-        return None;
+        return self.lexer.snap();
     }
 
-    let mut last_was_comma = false;
-    let mut nodes = Vec::<Rc<dyn RNode>>::new();
+    /// Parses the input without stopping at the first error. Every value that fails to parse is
+    /// replaced by an RNodeError placeholder and the parser resynchronizes at the next ',', ']' or '}'.
+    /// Returns the (partial) tree along with every error that was encountered.
+    pub fn parse_recovering(&mut self) -> (Rc<dyn RNode>, Vec<ParseError>)
+    {
+        self.recover = true;
+        self.errors.clear();
+
+        // Too much input to even look at, so there is nothing to recover.
+        if let Err(error) = check_input_size(&self.lexer)
+        {
+            self.recover = false;
+            return (Rc::new(RNodeError::new(error.clone())), vec![ error ]);
+        }
+
+        let result = self.try_parse_type();
+        let root = match result
+        {
+            Ok(node) => node,
+            Err(error) => self.recover_value(error).expect("Recovering parser should not fail"),
+        };
+
+        if let Err(error) = expect_end_of_input(&mut self.lexer)
+        {
+            self.record_error(error).expect("Recovering parser should not fail");
+        }
+
+        self.recover = false;
+        return (root, std::mem::take(&mut self.errors));
+    }
 
-    loop
+    /// Parses the next value. Arrays and objects are kept on an explicit stack rather than parsed
+    /// recursively, so how deeply they can be nested isn't bounded by the size of the thread's stack.
+    fn try_parse_type(&mut self) -> Result<Rc<dyn RNode>, ParseError>
     {
-        let snapshot = parser.lexer.snap();
-        let peek_token_result = parser.lexer.next_token();
+        let mut stack = Vec::<EnumFrame>::new();
+        self.open_starts.clear();
 
-        match peek_token_result
+        loop
         {
-            Ok(peek_token) =>
+            let mut node = match self.try_start_value(&mut stack)
+            {
+                Ok(Some(node)) => node,
+                // An array/object was opened and now expects its first value.
+                Ok(None) => { continue; },
+                Err(error) if stack.is_empty() => { return Err(error); },
+                Err(error) => self.recover_value(error)?,
+            };
+
+            // Hand the finished value to the enclosing array/object, closing each one that is then finished too.
+            loop
             {
-                // If the last token was a comma, expect a type next or invalid
-                if last_was_comma
+                let frame = match stack.last_mut()
                 {
-                    last_was_comma = false;
-
-                    // Restore since try_parse_type will expect the start of the next Token we want
-                    // to try and parse next.
-                    parser.lexer.restore(&snapshot);
-                    let opt_type_node = parser.try_parse_type();
-
-                    match opt_type_node
-                    {
-                        Some(type_node) => { nodes.push(type_node); },
-                        None => { /* TODO: log error?? */ return None; },
-                    }
-                }
+                    Some(frame) => frame,
+                    None => { return Ok(node); },
+                };
 
-                else if peek_token.is_symbol()
+                if self.add_to_frame(frame, node)?
                 {
-                    let symbol = peek_token.as_symbol().unwrap();
-
-                    // Check for end of array
-                    if symbol == "]"
-                    {
-                        break;
-                    }
-
-                    // See if it's a comma then
-                    else if symbol == ","
-                    {
-                        last_was_comma = true;
-                    }
-
-                    // Is a sub-array??
-                    else if symbol == "["
-                    {
-                        let opt_array_type = try_parse_array(parser, Rc::clone(&peek_token));
-
-                        match opt_array_type
-                        {
-                            Some(array_type) => { nodes.push(array_type); },
-                            None => { return None; },
-                        }
-                    }
-
-                    // Nested object
-                    else if symbol == "{"
-                    {
-                        let opt_obj_type = try_parse_object(parser, Rc::clone(&peek_token));
-
-                        match opt_obj_type
-                        {
-                            Some(obj_type) => { nodes.push(obj_type); },
-                            None => { return None; },
-                        }
-                    }
-
-                    else
-                    {
-                        // TODO: print error??
-                        return None;
-                    }
+                    break;
                 }
 
-                else if peek_token.is_bool()
-                {
-                    last_was_comma = false;
-                    nodes.push(Rc::new(RNodeBool::new(peek_token.as_bool().unwrap())));
-                }
+                node = stack.pop().unwrap().into_node();
+                self.open_starts.pop();
+            }
+        }
+    }
+
+    /// Starts the next value. Returns the value when it is already complete (ex. a number or '[]'), or
+    /// None after pushing the array/object it opens onto the 'stack'.
+    fn try_start_value(&mut self, stack: &mut Vec<EnumFrame>) -> Result<Option<Rc<dyn RNode>>, ParseError>
+    {
+        let token = self.lexer.next_token()?;
+
+        if let Some(node) = create_scalar(&self.lexer, &*token)?
+        {
+            return Ok(Some(node));
+        }
+
+        if let Some(create_frame) = token.as_symbol().ok().and_then(|symbol| self.guess_table.get(symbol).copied())
+        {
+            check_depth(&self.lexer, stack.len())?;
+            let mut frame = create_frame();
+            self.open_starts.push(self.lexer.get_token_start().get_start_pos());
+
+            if self.begin_frame(&mut frame)?
+            {
+                stack.push(frame);
+                return Ok(None);
+            }
+
+            self.open_starts.pop();
+            return Ok(Some(frame.into_node()));
+        }
 
-                else if peek_token.is_double()
+        return Err(self.lexer.token_error(EnumParseErrorKind::ExpectedValue(describe_token(&*token))));
+    }
+
+    /// Continues an array/object right after its '[' or '{'. Returns whether it expects a value.
+    fn begin_frame(&mut self, frame: &mut EnumFrame) -> Result<bool, ParseError>
+    {
+        match frame
+        {
+            EnumFrame::ARRAY(nodes) =>
+            {
+                // Check for an empty array before expecting any values.
+                if self.try_consume_symbol("]")
                 {
-                    last_was_comma = false;
-                    nodes.push(Rc::new(RNodeDouble::new(peek_token.as_double().unwrap())));
+                    return Ok(false);
                 }
 
-                else if peek_token.is_null()
+                return self.begin_element(nodes.len());
+            },
+            EnumFrame::OBJECT(object) =>
+            {
+                // Empty object
+                if self.try_consume_symbol("}")
                 {
-                    last_was_comma = false;
-                    nodes.push(Rc::new(RNodeNull::new()));
+                    return Ok(false);
                 }
 
-                // TODO: Add more token types here...
-                else if peek_token.is_string()
+                return self.begin_member(object);
+            },
+        }
+    }
+
+    /// Adds a finished value to the array/object and continues past it. Returns whether it expects another value.
+    fn add_to_frame(&mut self, frame: &mut EnumFrame, node: Rc<dyn RNode>) -> Result<bool, ParseError>
+    {
+        match frame
+        {
+            EnumFrame::ARRAY(nodes) =>
+            {
+                nodes.push(node);
+
+                // After each value, expect either a ',' or the end of the array (JSON5 allows a trailing ',').
+                if self.expect_comma_or_end(']')? == EnumSeparator::END || (self.is_json5() && self.try_consume_symbol("]"))
                 {
-                    last_was_comma = false;
-                    nodes.push(Rc::new(RNodeString::new_copy(peek_token.as_string().unwrap())));
+                    return Ok(false);
                 }
 
-                // TODO: Can we reduce logic with the above 'else' statement somehow??
-                else
+                return self.begin_element(nodes.len());
+            },
+            EnumFrame::OBJECT(object) =>
+            {
+                let (key, key_span) = object.key.take().expect("A value should only be parsed after its key");
+                self.insert_member(object, key, key_span, node)?;
+
+                if self.end_member()?
                 {
-                    // TODO: print error??
-                    return None;
+                    return Ok(false);
                 }
+
+                return self.begin_member(object);
             },
-            Err(_) => { return None; }
         }
     }
 
-    return Some(Rc::new(RNodeArray::new(nodes)));
-}
-
-fn try_parse_object(parser: &mut Parser, token_in: Rc<dyn TokenTrait>) -> Option<Rc<dyn RNode>>
-{
-    if !token_in.is_symbol() || token_in.as_symbol().unwrap() != "{"
+    /// Checks whether an array holding 'count' elements may have another one. Returns whether it expects a value.
+    fn begin_element(&mut self, count: usize) -> Result<bool, ParseError>
     {
-        return None;
-    }
+        if let Some(kind) = get_element_limit_error(self.get_options(), count)
+        {
+            self.reject_extra_values(kind, ']')?;
+            return Ok(false);
+        }
 
-    let mut last_was_comma: bool = true;
-    let mut nodes = BTreeMap::<String, Rc<dyn RNode>>::new();
+        return Ok(true);
+    }
 
-    loop
+    /// Parses the '"key":' of the next member, leaving its value to be parsed. When recovering, members
+    /// that end up without a value to parse are skipped or completed with a placeholder. Returns whether
+    /// the object expects a value.
+    fn begin_member(&mut self, object: &mut ObjectFrame) -> Result<bool, ParseError>
     {
-        let snapshot = parser.lexer.snap();
-        let mut opt_peek_token = parser.lexer.next_token();
+        loop
+        {
+            if let Some(kind) = get_member_limit_error(self.get_options(), object.member_count)
+            {
+                self.reject_extra_values(kind, '}')?;
+                return Ok(false);
+            }
+
+            object.member_count += 1;
+
+            if let Some((key, key_span)) = self.try_parse_key(object)?
+            {
+                object.key = Some((key, key_span));
+                return Ok(true);
+            }
+
+            if self.end_member()?
+            {
+                return Ok(false);
+            }
+        }
+    }
 
+    /// Parses a member's key and ':', returning the key and where it was written. When recovering, returns
+    /// None for a member that is skipped (since there is no key) or already inserted with a placeholder value.
+    fn try_parse_key(&mut self, object: &mut ObjectFrame) -> Result<Option<(String, KeySpan)>, ParseError>
+    {
         // First expect the String key
-        match opt_peek_token
+        let key = match self.lexer.next_token().and_then(|token| read_key(&self.lexer, &*token))
         {
-            Ok(mut peek_token) =>
+            Ok(key) => key,
+            Err(error) =>
             {
-                if !last_was_comma
+                // Skip the whole member since there is no key to store its value under.
+                self.recover_value(error)?;
+                return Ok(None);
+            },
+        };
+
+        let key_span = KeySpan { start: self.lexer.get_token_start().clone(), end: self.lexer.get_position() };
+
+        // Next expect a ':'
+        match self.lexer.next_token()
+        {
+            Ok(token) if is_symbol(&*token, ":") => {},
+            result =>
+            {
+                let error = match result
                 {
-                    last_was_comma = true;
-
-                    if !peek_token.is_symbol()
-                    {
-                        println!("Error: expected a ','");
-                        return None;
-                    }
-
-                    let symbol = peek_token.as_symbol().unwrap();
-
-                    if symbol == "}"
-                    {
-                        break;
-                    }
-
-                    else if symbol != ","
-                    {
-                        println!("Error: expected a ','");
-                        return None;
-                    }
-                }
+                    Ok(token) => self.lexer.token_error(EnumParseErrorKind::ExpectedColon(describe_token(&*token))),
+                    Err(error) => error,
+                };
 
-                else
+                self.record_error(error.clone())?;
+
+                // Most likely just a missing ':' so carry on with the value.
+                if !self.next_starts_value()
                 {
-                    last_was_comma = false;
-
-                    if !peek_token.is_string()
-                    {
-                        if peek_token.is_symbol() && peek_token.as_symbol().unwrap() == "}"
-                        {
-                            break;
-                        }
-
-                        // TODO: Log the error here??
-                        return None;
-                    }
-
-                    let key = peek_token.as_string().unwrap().clone();
-
-                    // Next expect a ':'
-                    opt_peek_token = parser.lexer.next_token();
-
-                    if opt_peek_token.is_err()
-                    {
-                        return None;
-                    }
-
-                    peek_token = opt_peek_token.unwrap();
-
-                    if !peek_token.is_symbol() || peek_token.as_symbol().unwrap() != ":"
-                    {
-                        return None;
-                    }
-
-                    // Last should be a value of some RNode type.
-                    let opt_node_type = parser.try_parse_type();
-
-                    match opt_node_type
-                    {
-                        Some(node_type) =>
-                        {
-                            nodes.insert(key, Rc::clone(&node_type));
-                        },
-                        None => { println!("Error: expected to parse an RNode type at {0} (key: {1})", snapshot.to_string(), key); return None; },
-                    }
+                    self.synchronize();
+                    self.insert_member(object, key, key_span, Rc::new(RNodeError::new(error)))?;
+                    return Ok(None);
                 }
             },
-            Err(_) => { return None; },
         }
+
+        return Ok(Some((key, key_span)));
     }
 
-    return Some(Rc::new(RNodeObject::new(nodes)));
-}
+    /// Inserts a member, applying the duplicate key policy when its key was already seen.
+    fn insert_member(&mut self, object: &mut ObjectFrame, key: String, key_span: KeySpan, node: Rc<dyn RNode>) -> Result<(), ParseError>
+    {
+        match object.first_keys.get(&key)
+        {
+            Some(first_start) =>
+            {
+                let problem = get_duplicate_key_error(&key, first_start, &key_span.start, key_span.end);
+                insert_duplicate(self, &mut object.nodes, &mut object.duplicates, key, node, problem)?;
+            },
+            None =>
+            {
+                object.first_keys.insert(key.clone(), key_span.start);
+                object.nodes.insert(key, node);
+            },
+        }
 
-#[cfg(test)]
-mod tests
-{
-    use crate::parser::parser::Parser;
-    use crate::rnodes::rnode::EnumNodeType;
-    use crate::rnodes::rnode_array::RNodeArray;
-    use crate::rnodes::rnode_bool::RNodeBool;
-    use crate::rnodes::rnode_double::RNodeDouble;
+        return Ok(());
+    }
 
-    use crate::rnodes::rnode::RNode;
-    use crate::rnodes::rnode_null::RNodeNull;
-    use crate::rnodes::rnode_object::RNodeObject;
-    use crate::rnodes::rnode_string::RNodeString;
+    /// Expects either a ',' followed by the next key or the end of the object (JSON5 allows a trailing ',').
+    /// Returns whether the object was closed.
+    fn end_member(&mut self) -> Result<bool, ParseError>
+    {
+        return Ok(self.expect_comma_or_end('}')? == EnumSeparator::END || (self.is_json5() && self.try_consume_symbol("}")));
+    }
 
-    #[test]
-    fn parse_empty_array()
+    /// Handles an array/object that already holds as many values as its limit allows. The error spans
+    /// the start of the next value. When recovering, the rest of the array/object is skipped.
+    fn reject_extra_values(&mut self, kind: EnumParseErrorKind, close: char) -> Result<(), ParseError>
     {
-        let input = String::from("[]");
-        let mut parser = Parser::new_copy(&input, false);
-        let node_type_result = parser.parse();
+        let snapshot = self.lexer.snap();
+        let _ = self.lexer.next_token();
+        let error = self.lexer.token_error(kind);
 
-        assert!(node_type_result.is_ok());
+        self.lexer.restore(&snapshot);
+        self.record_error(error)?;
 
-        let rnode = node_type_result.unwrap();
-        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+        while let Some(symbol) = self.synchronize()
+        {
+            // Leave the closing bracket of an enclosing array/object for the caller.
+            if symbol == "," || symbol == close.to_string()
+            {
+                let _ = self.lexer.next_token();
+            }
 
-        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
-        assert!(node_array.is_empty());
+            if symbol != ","
+            {
+                break;
+            }
+        }
+
+        return Ok(());
     }
 
-    #[test]
-    fn parse_empty_array_with_space()
+    /// Consumes the next token if it is the given symbol.
+    fn try_consume_symbol(&mut self, symbol: &str) -> bool
     {
-        let input = String::from("[   ]");
-        let mut parser = Parser::new_copy(&input, false);
-        let node_type_result = parser.parse();
-
-        assert!(node_type_result.is_ok());
+        let snapshot = self.lexer.snap();
 
-        let rnode = node_type_result.unwrap();
-        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+        if let Ok(token) = self.lexer.next_token()
+        {
+            if is_symbol(&*token, symbol)
+            {
+                return true;
+            }
+        }
 
-        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
-        assert!(node_array.is_empty());
+        self.lexer.restore(&snapshot);
+        return false;
     }
 
-    #[test]
-    fn parse_array_with_inner_array()
+    /// Expects either a ',' or the 'close' symbol after a value inside of an array or object.
+    fn expect_comma_or_end(&mut self, close: char) -> Result<EnumSeparator, ParseError>
     {
-        let input = String::from("[ [] ]");
-        let mut parser = Parser::new_copy(&input, false);
-        let node_type_result = parser.parse();
+        let error = match self.lexer.next_token()
+        {
+            Ok(token) =>
+            {
+                if is_symbol(&*token, ",")
+                {
+                    return Ok(EnumSeparator::COMMA);
+                }
 
-        assert!(node_type_result.is_ok());
+                else if is_symbol(&*token, &close.to_string())
+                {
+                    return Ok(EnumSeparator::END);
+                }
 
-        let rnode = node_type_result.unwrap();
-        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+                self.lexer.token_error(EnumParseErrorKind::ExpectedCommaOrEnd { close, found: describe_token(&*token) })
+            },
+            Err(error) => error,
+        };
 
-        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
-        assert!(!node_array.is_empty());
-        assert_eq!(node_array.len(), 1);
+        self.record_error(error)?;
 
-        let opt_node_array = node_array.get(0);
-        assert!(opt_node_array.is_some());
+        // Most likely just a missing ',' so carry on with the next value.
+        if self.next_starts_value()
+        {
+            return Ok(EnumSeparator::COMMA);
+        }
 
-        let node_array2 = opt_node_array.unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
-        assert!(node_array2.is_empty());
-        assert_eq!(node_array2.len(), 0);
+        // Otherwise skip ahead to the next ',', ']' or '}'.
+        match self.synchronize()
+        {
+            Some(symbol) if symbol == "," =>
+            {
+                let _ = self.lexer.next_token();
+                return Ok(EnumSeparator::COMMA);
+            },
+            Some(symbol) if symbol == close.to_string() =>
+            {
+                let _ = self.lexer.next_token();
+                return Ok(EnumSeparator::END);
+            },
+            // Leave the closing bracket of an enclosing array/object (or the end of input) for the caller.
+            _ => { return Ok(EnumSeparator::END); },
+        }
     }
 
-    #[test]
-    fn parse_array_with_inner_bool()
+    /// When recovering, records the error and rewinds to the start of the offending token
+    /// (so it can be resynchronized on). Otherwise, simply returns the error.
+    fn record_error(&mut self, error: ParseError) -> Result<(), ParseError>
     {
-        let input = String::from("[ true ]");
-        let mut parser = Parser::new_copy(&input, false);
-        let node_type_result = parser.parse();
+        if !self.recover
+        {
+            return Err(error);
+        }
 
-        assert!(node_type_result.is_ok());
+        match error.get_kind()
+        {
+            EnumParseErrorKind::ExpectedValue(_) | EnumParseErrorKind::ExpectedKey(_) |
+            EnumParseErrorKind::ExpectedColon(_) | EnumParseErrorKind::ExpectedCommaOrEnd { .. } |
+            // Rewinding to the '['/'{' lets the whole array/object that is nested too deeply be skipped.
+            EnumParseErrorKind::DepthLimitExceeded { .. } =>
+            {
+                let token_start = Snapshot::new(error.get_start(), error.get_line(), error.get_column());
+                self.lexer.restore(&token_start);
+            },
+            _ => {},
+        }
 
-        let rnode = node_type_result.unwrap();
+        // The same problem can be reported by each enclosing array/object (ex. the end of input),
+        // so only keep the first report.
+        let is_duplicate = self.errors.last().is_some_and(|last| last.get_start() == error.get_start());
+
+        if !is_duplicate
+        {
+            self.errors.push(error);
+        }
+
+        return Ok(());
+    }
+
+    /// When recovering, records the error, skips to the next ',', ']' or '}' and returns
+    /// a placeholder for the value that failed to parse. Otherwise, simply returns the error.
+    fn recover_value(&mut self, error: ParseError) -> Result<Rc<dyn RNode>, ParseError>
+    {
+        let placeholder = Rc::new(RNodeError::new(error.clone()));
+        self.record_error(error)?;
+        self.synchronize();
+
+        return Ok(placeholder);
+    }
+
+    /// Checks (without consuming anything) whether the next token can start a value.
+    fn next_starts_value(&mut self) -> bool
+    {
+        let snapshot = self.lexer.snap();
+        let result = match self.lexer.next_token()
+        {
+            Ok(token) => !token.is_symbol() || self.guess_table.contains_key(token.as_symbol().unwrap()),
+            Err(_) => false,
+        };
+
+        self.lexer.restore(&snapshot);
+        return result;
+    }
+
+    /// Skips tokens (and any nested arrays/objects) until the next ',', ']' or '}', which is left
+    /// to be consumed by the caller. Returns the symbol found, or None at the end of input.
+    fn synchronize(&mut self) -> Option<String>
+    {
+        let mut depth: usize = 0;
+
+        loop
+        {
+            let snapshot = self.lexer.snap();
+            let token = match self.lexer.next_token()
+            {
+                Ok(token) => token,
+                Err(error) if error.get_kind().ends_input() => { return None; },
+                // Skip over anything the lexer can't make sense of.
+                Err(_) => { continue; },
+            };
+
+            if !token.is_symbol()
+            {
+                continue;
+            }
+
+            let symbol = token.as_symbol().unwrap();
+
+            if symbol == "[" || symbol == "{"
+            {
+                depth += 1;
+            }
+
+            else if depth > 0 && (symbol == "]" || symbol == "}")
+            {
+                depth -= 1;
+            }
+
+            else if depth == 0 && (symbol == "," || symbol == "]" || symbol == "}")
+            {
+                let result = symbol.clone();
+                self.lexer.restore(&snapshot);
+                return Some(result);
+            }
+        }
+    }
+}
+
+/// Creates the RNode for a bool, number, null or string token, with numbers parsed according to the
+/// 'number_mode' option. Returns None for any other token.
+pub fn create_scalar(lexer: &Lexer, token: &dyn TokenTrait) -> Result<Option<Rc<dyn RNode>>, ParseError>
+{
+    match token.get_type()
+    {
+        EnumTokenType::BOOL => { return Ok(Some(Rc::new(RNodeBool::new(token.as_bool().unwrap())))); },
+        EnumTokenType::DOUBLE => { return create_number(lexer, token).map(Some); },
+        EnumTokenType::NULL => { return Ok(Some(Rc::new(RNodeNull::new()))); },
+        EnumTokenType::STRING =>
+        {
+            let token_string = token.downcast_ref::<TokenString>().expect("A string token should be a TokenString");
+
+            if !token_string.get_lone_surrogates().is_empty()
+            {
+                return Ok(Some(Rc::new(RNodeString::new_with_lone_surrogates(token_string.get_value().clone(), token_string.get_lone_surrogates().to_vec()))));
+            }
+
+            return Ok(Some(Rc::new(RNodeString::new_with_unicode_escapes(token_string.get_value().clone(), token_string.get_unicode_escapes().to_vec()))));
+        },
+        _ => { return Ok(None); },
+    }
+}
+
+fn create_number(lexer: &Lexer, token: &dyn TokenTrait) -> Result<Rc<dyn RNode>, ParseError>
+{
+    let value = token.as_double().unwrap();
+
+    match (lexer.get_options().get_number_mode(), token.downcast_ref::<TokenDouble>())
+    {
+        (EnumNumberMode::EXACT, Some(token_double)) => { return Ok(Rc::new(RNodeNumber::from_lexeme(token_double.get_lexeme().clone(), value))); },
+        #[cfg(feature = "arbitrary_precision")]
+        (EnumNumberMode::DECIMAL, Some(token_double)) =>
+        {
+            match RNodeDecimal::from_lexeme(token_double.get_lexeme().clone())
+            {
+                Some(node) => { return Ok(Rc::new(node)); },
+                // JSON5's 'Infinity' and 'NaN' have no decimal value either.
+                None if matches!(token_double.get_lexeme().trim_start_matches('-'), "Infinity" | "NaN") => { return Err(lexer.token_error(EnumParseErrorKind::InvalidNumber(format!("'{}' is not a decimal number", token_double.get_lexeme())))); },
+                None => { return Err(lexer.token_error(EnumParseErrorKind::InvalidNumber(String::from("the exponent is too large")))); },
+            }
+        },
+        // Only JSON5's 'Infinity' is meant to be infinite, any other number would be written back as something else.
+        (_, Some(token_double)) if value.is_infinite() && !token_double.get_lexeme().ends_with("Infinity") =>
+        {
+            return Err(lexer.token_error(EnumParseErrorKind::NumberOutOfRange));
+        },
+        _ => { return Ok(Rc::new(RNodeDouble::new(value))); },
+    }
+}
+
+/// Applies the duplicate key policy to the second (or later) occurrence of a key. The 'problem' is
+/// either returned as an error or kept as a warning.
+fn insert_duplicate(parser: &mut Parser, nodes: &mut OrderedMap<Rc<dyn RNode>>, duplicates: &mut OrderedMap<Vec<Rc<dyn RNode>>>,
+                    key: String, node: Rc<dyn RNode>, problem: ParseError) -> Result<(), ParseError>
+{
+    let policy = parser.get_options().get_duplicate_key_policy();
+
+    if policy == EnumDuplicateKeyPolicy::ERROR
+    {
+        return parser.record_error(problem);
+    }
+
+    insert_duplicate_value(policy, nodes, duplicates, key, node);
+    parser.warnings.push(problem);
+    return Ok(());
+}
+
+/// Inserts the value of a key that is already in 'nodes' as the duplicate key policy says to. The
+/// ERROR policy keeps the first value (as when recovering), since reporting it is up to the caller.
+pub fn insert_duplicate_value(policy: EnumDuplicateKeyPolicy, nodes: &mut OrderedMap<Rc<dyn RNode>>, duplicates: &mut OrderedMap<Vec<Rc<dyn RNode>>>,
+                              key: String, node: Rc<dyn RNode>)
+{
+    match policy
+    {
+        EnumDuplicateKeyPolicy::ERROR | EnumDuplicateKeyPolicy::FIRST_WINS => {},
+        EnumDuplicateKeyPolicy::LAST_WINS => { nodes.insert(key, node); },
+        EnumDuplicateKeyPolicy::KEEP_ALL =>
+        {
+            match duplicates.get_mut(&key)
+            {
+                Some(values) => { values.push(Rc::clone(&node)); },
+                None =>
+                {
+                    let first = Rc::clone(nodes.get(&key).expect("The first occurrence should have been inserted"));
+                    duplicates.insert(key.clone(), vec![ first, Rc::clone(&node) ]);
+                },
+            }
+
+            nodes.insert(key, node);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::number_mode::EnumNumberMode;
+    use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
+    use crate::rnodes::rnode::EnumNodeType;
+    use crate::rnodes::rnode_array::RNodeArray;
+    use crate::rnodes::rnode_bool::RNodeBool;
+    use crate::rnodes::rnode_double::RNodeDouble;
+    use crate::rnodes::rnode_error::RNodeError;
+
+    use crate::rnodes::rnode::RNode;
+    use crate::rnodes::rnode_null::RNodeNull;
+    use crate::rnodes::rnode_number::{EnumNumber, RNodeNumber};
+    use crate::rnodes::rnode_object::RNodeObject;
+    use crate::rnodes::rnode_string::RNodeString;
+
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    #[test]
+    fn parse_empty_array()
+    {
+        let input = String::from("[]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let node_type_result = parser.parse();
+
+        assert!(node_type_result.is_ok());
+
+        let rnode = node_type_result.unwrap();
+        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert!(node_array.is_empty());
+    }
+
+    #[test]
+    fn parse_empty_array_with_space()
+    {
+        let input = String::from("[   ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let node_type_result = parser.parse();
+
+        assert!(node_type_result.is_ok());
+
+        let rnode = node_type_result.unwrap();
+        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert!(node_array.is_empty());
+    }
+
+    #[test]
+    fn parse_array_with_inner_array()
+    {
+        let input = String::from("[ [] ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let node_type_result = parser.parse();
+
+        assert!(node_type_result.is_ok());
+
+        let rnode = node_type_result.unwrap();
+        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert!(!node_array.is_empty());
+        assert_eq!(node_array.len(), 1);
+
+        let opt_node_array = node_array.get(0);
+        assert!(opt_node_array.is_some());
+
+        let node_array2 = opt_node_array.unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert!(node_array2.is_empty());
+        assert_eq!(node_array2.len(), 0);
+    }
+
+    #[test]
+    fn parse_array_with_inner_bool()
+    {
+        let input = String::from("[ true ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let node_type_result = parser.parse();
+
+        assert!(node_type_result.is_ok());
+
+        let rnode = node_type_result.unwrap();
         assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
 
         let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
@@ -421,7 +902,7 @@ mod tests
     fn parse_array_with_inner_double()
     {
         let input = String::from("[ 123.456 ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -444,7 +925,7 @@ mod tests
     fn parse_array_with_inner_null()
     {
         let input = String::from("[ null ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -465,7 +946,7 @@ mod tests
     {
         let value = String::from("Hello, world!");
         let input = String::from("[ \"Hello, world!\" ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -488,7 +969,7 @@ mod tests
     fn parse_array_with_inner_bool_and_double()
     {
         let input = String::from("[ true, 123.456 ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -517,7 +998,7 @@ mod tests
     fn parse_array_with_inner_all_nodes()
     {
         let input = String::from("[ [ ], true, 123.456, null, \"Hello\", { \"key\": false } ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -577,7 +1058,7 @@ mod tests
     fn parse_array_with_inner_objects()
     {
         let input = String::from("[ { \"key\": true }, {} ]");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -618,7 +1099,7 @@ mod tests
     fn parse_bool()
     {
         let input = String::from("true");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -635,7 +1116,7 @@ mod tests
     {
         let num: f64 = 123.456;
         let input = num.to_string();
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -651,7 +1132,7 @@ mod tests
     fn parse_null()
     {
         let input = String::from("null");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -665,7 +1146,7 @@ mod tests
     {
         let key = String::from("key");
         let input = String::from("{ \"key\": 123.456 }");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -689,7 +1170,7 @@ mod tests
     {
         let key = String::from("key");
         let input = String::from("{ \"key\": \"\" }");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -714,7 +1195,7 @@ mod tests
         let key0 = String::from("key0");
         let key1 = String::from("key1");
         let input = String::from("{ \"key0\": 123.456, \"key1\": -42 }");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -750,7 +1231,7 @@ mod tests
         let inner_key1 = String::from("1");
         let inner_key2 = String::from("nil");
         let input = String::from("{ \"key0\": 123.456, \"key1\": -42, \"key2\": false, \"key3\": \"Hello\", \"key4\": { \"1\": 1, \"nil\": null } }");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -811,7 +1292,7 @@ mod tests
     {
         let value = String::from("Hi");
         let input = String::from("\"Hi\"");
-        let mut parser = Parser::new_copy(&input, false);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
         let node_type_result = parser.parse();
 
         assert!(node_type_result.is_ok());
@@ -822,5 +1303,793 @@ mod tests
         let node_string = rnode.downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
         assert_eq!(node_string.get_value(), &value);
     }
-}
 
+    #[test]
+    fn parse_object_missing_comma_error()
+    {
+        let input = String::from("{\n    \"a\": 1\n    \"b\": 2\n}");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let node_type_result = parser.parse();
+
+        assert!(node_type_result.is_err());
+
+        let error = node_type_result.err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: '}', found: String::from("string \"b\"") });
+        assert_eq!(error.get_line(), 3);
+        assert_eq!(error.get_column(), 5);
+        assert_eq!(error.get_start(), 17);
+        assert_eq!(error.get_end(), 20);
+    }
+
+    #[test]
+    fn parse_object_missing_colon_error()
+    {
+        let input = String::from("{ \"a\" 1 }");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedColon(String::from("number 1")));
+        assert_eq!(error.get_line(), 1);
+        assert_eq!(error.get_column(), 7);
+    }
+
+    #[test]
+    fn parse_object_trailing_comma_error()
+    {
+        let input = String::from("{ \"a\": 1, }");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedKey(String::from("'}'")));
+        assert_eq!(error.get_column(), 11);
+    }
+
+    #[test]
+    fn parse_array_missing_comma_error()
+    {
+        let input = String::from("[ 1 2 ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: ']', found: String::from("number 2") });
+        assert_eq!(error.get_start(), 4);
+    }
+
+    #[test]
+    fn parse_array_unexpected_eof_error()
+    {
+        let input = String::from("[ 1,\n");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+        assert_eq!(error.get_line(), 2);
+        assert_eq!(error.get_column(), 1);
+    }
+
+    #[test]
+    fn parse_unterminated_string_error()
+    {
+        let input = String::from("[ \"abc ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnterminatedString);
+        assert_eq!(error.get_start(), 2);
+        assert_eq!(error.get_end(), input.len());
+    }
+
+    #[test]
+    fn parse_bad_escape_error()
+    {
+        let input = String::from("[ \"a\\qb\" ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidEscape('q'));
+        assert_eq!(error.get_start(), 4);
+        assert_eq!(error.get_end(), 6);
+    }
+
+    #[test]
+    fn parse_unexpected_character_error()
+    {
+        let input = String::from("[ @ ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('@'));
+        assert_eq!(error.get_column(), 3);
+    }
+
+    #[test]
+    fn parse_recovering_array()
+    {
+        let input = String::from("[1 2, @, 3]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: ']', found: String::from("number 2") });
+        assert_eq!(errors[1].get_kind(), &EnumParseErrorKind::UnexpectedCharacter('@'));
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 4);
+        assert_eq!(node_array.get(1).unwrap().get_node_type(), EnumNodeType::DOUBLE);
+
+        let node_error = node_array.get(2).unwrap().downcast_rc::<RNodeError>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_error.get_error(), &errors[1]);
+
+        let node_double = node_array.get(3).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_double.value, 3.0);
+    }
+
+    #[test]
+    fn parse_recovering_object_collects_every_error()
+    {
+        let input = String::from("{\n    \"a\": 1\n    \"b\": tru,\n    \"c\" 3,\n    \"d\": [1, 2,],\n    \"e\": null\n}");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: '}', found: String::from("string \"b\"") });
+        assert_eq!(errors[0].get_line(), 3);
+        assert_eq!(errors[1].get_kind(), &EnumParseErrorKind::ExpectedValue(String::from("'tru'")));
+        assert_eq!(errors[1].get_line(), 3);
+        assert_eq!(errors[2].get_kind(), &EnumParseErrorKind::ExpectedColon(String::from("number 3")));
+        assert_eq!(errors[2].get_line(), 4);
+        assert_eq!(errors[3].get_kind(), &EnumParseErrorKind::ExpectedValue(String::from("']'")));
+        assert_eq!(errors[3].get_line(), 5);
+
+        let node_object = rnode.downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_object.len(), 5);
+        assert_eq!(node_object.get(&String::from("a")).unwrap().get_node_type(), EnumNodeType::DOUBLE);
+        assert_eq!(node_object.get(&String::from("b")).unwrap().get_node_type(), EnumNodeType::ERROR);
+        assert_eq!(node_object.get(&String::from("c")).unwrap().get_node_type(), EnumNodeType::DOUBLE);
+        assert_eq!(node_object.get(&String::from("e")).unwrap().get_node_type(), EnumNodeType::NULL);
+
+        let node_array = node_object.get(&String::from("d")).unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 3);
+        assert_eq!(node_array.get(2).unwrap().get_node_type(), EnumNodeType::ERROR);
+    }
+
+    #[test]
+    fn parse_recovering_unclosed_brackets_reports_eof_once()
+    {
+        let input = String::from("[1, [2, {\"a\": 3");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 2);
+
+        let inner_array = node_array.get(1).unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(inner_array.len(), 2);
+        assert_eq!(inner_array.get(1).unwrap().get_node_type(), EnumNodeType::OBJECT);
+    }
+
+    #[test]
+    fn parse_recovering_mismatched_bracket()
+    {
+        let input = String::from("[{\"a\": 1], 2]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::ExpectedCommaOrEnd { close: '}', found: String::from("']'") });
+        assert_eq!(errors[1].get_kind(), &EnumParseErrorKind::TrailingData(String::from("','")));
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 1);
+        assert_eq!(node_array.get(0).unwrap().get_node_type(), EnumNodeType::OBJECT);
+    }
+
+    #[test]
+    fn parse_recovering_valid_input_has_no_errors()
+    {
+        let input = String::from("{ \"key\": [1, true, null] }");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert!(errors.is_empty());
+        assert_eq!(rnode.get_node_type(), EnumNodeType::OBJECT);
+    }
+
+    #[test]
+    fn parse_trailing_garbage_error()
+    {
+        let input = String::from("{\"a\":1} garbage");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TrailingData(String::from("'garbage'")));
+        assert_eq!(error.get_start(), 8);
+        assert_eq!(error.get_end(), input.len());
+    }
+
+    #[test]
+    fn parse_trailing_bracket_error()
+    {
+        let input = String::from("[1]]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TrailingData(String::from("']'")));
+        assert_eq!(error.get_column(), 4);
+    }
+
+    #[test]
+    fn parse_trailing_invalid_character_error()
+    {
+        let input = String::from("true @");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TrailingData(String::from("'@'")));
+    }
+
+    #[test]
+    fn parse_trailing_whitespace_is_valid()
+    {
+        let input = String::from(" [1] \n\t \r\n");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn parse_stream_of_values()
+    {
+        let input = String::from("{\"a\": 1} [2]\n\"three\" 4 null");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let nodes = parser.parse_stream().unwrap();
+
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0].get_node_type(), EnumNodeType::OBJECT);
+        assert_eq!(nodes[1].get_node_type(), EnumNodeType::ARRAY);
+        assert_eq!(nodes[2].get_node_type(), EnumNodeType::STRING);
+        assert_eq!(nodes[3].get_node_type(), EnumNodeType::DOUBLE);
+        assert_eq!(nodes[4].get_node_type(), EnumNodeType::NULL);
+    }
+
+    #[test]
+    fn parse_stream_of_adjacent_values()
+    {
+        let input = String::from("{}{}[]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let nodes = parser.parse_stream().unwrap();
+
+        assert_eq!(nodes.len(), 3);
+    }
+
+    #[test]
+    fn parse_stream_empty_input()
+    {
+        let input = String::from("  \n ");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let nodes = parser.parse_stream().unwrap();
+
+        assert!(nodes.is_empty());
+    }
+
+    #[test]
+    fn parse_stream_reports_bad_value()
+    {
+        let input = String::from("{} ]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse_stream().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::ExpectedValue(String::from("']'")));
+    }
+
+    #[test]
+    fn parse_recovering_trailing_data()
+    {
+        let input = String::from("[1, 2] 3");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(rnode.get_node_type(), EnumNodeType::ARRAY);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::TrailingData(String::from("number 3")));
+    }
+
+    #[test]
+    fn parse_invalid_utf8_error()
+    {
+        let input: Vec<u8> = vec![b'{', b'"', b'k', b'"', b':', b' ', b'"', 0xE2, 0x82, b'"', b'}'];
+        let mut parser = Parser::new_bytes(input, &ParserOptions::default());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidUtf8);
+        assert_eq!(error.get_start(), 7);
+    }
+
+    #[test]
+    fn parse_recovering_invalid_utf8_terminates()
+    {
+        let input: Vec<u8> = vec![b'[', b'1', b',', b' ', 0xFF, b']'];
+        let mut parser = Parser::new_bytes(input, &ParserOptions::default());
+        let (_, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::InvalidUtf8);
+    }
+
+    #[test]
+    fn parse_exact_numbers()
+    {
+        let input = String::from("[9007199254740993, -9223372036854775808, 18446744073709551615, 18446744073709551616, 1.50, 1E3]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::EXACT).build());
+
+        let node_array = parser.parse().unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        let numbers: Vec<Rc<RNodeNumber>> = (0..node_array.len())
+            .map(|i| node_array.get(i).unwrap().downcast_rc::<RNodeNumber>().map_err(|_| "Shouldn't happen").unwrap())
+            .collect();
+
+        assert_eq!(numbers[0].get_node_type(), EnumNodeType::NUMBER);
+        assert_eq!(numbers[0].get_value(), EnumNumber::U64(9007199254740993));
+        assert_eq!(numbers[1].get_value(), EnumNumber::I64(i64::MIN));
+        assert_eq!(numbers[2].get_value(), EnumNumber::U64(u64::MAX));
+        assert!(!numbers[3].is_integer());
+        assert_eq!(numbers[3].get_lexeme(), "18446744073709551616");
+        assert_eq!(numbers[4].get_value(), EnumNumber::F64(1.5));
+        assert_eq!(numbers[4].get_lexeme(), "1.50");
+        assert_eq!(numbers[5].as_u64(), Ok(1000));
+        assert_eq!(numbers[5].get_lexeme(), "1E3");
+    }
+
+    #[test]
+    fn parse_numbers_too_large_for_a_double()
+    {
+        let input = String::from("[1.7976931348623157e308, -1.7976931348623157e309]");
+        let error = Parser::new_copy(&input, &ParserOptions::default()).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::NumberOutOfRange);
+        assert_eq!((error.get_start(), error.get_end()), (25, 48));
+
+        // Exact numbers keep the lexeme, and JSON5's 'Infinity' is infinite on purpose.
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::EXACT).build());
+        let node_array = parser.parse().unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        let node = node_array.get(1).unwrap().downcast_rc::<RNodeNumber>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node.get_lexeme(), "-1.7976931348623157e309");
+
+        let input = String::from("[-Infinity, 1e-400]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().dialect(EnumDialect::JSON5).build());
+        let node_array = parser.parse().unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.get(0).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value, f64::NEG_INFINITY);
+        assert_eq!(node_array.get(1).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value, 0.0);
+    }
+
+    #[test]
+    fn parse_json5_document()
+    {
+        let input = String::from(r#"// A JSON5 document
+{
+    unquoted: 'single',
+    $id: 0x1F,
+    "quoted": [ 1, .5, +Infinity, NaN, ],
+    true: null,
+    Infinity: 'line \
+continued',
+}"#);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().stringify(true).dialect(EnumDialect::JSON5).build());
+
+        let node_object = parser.parse().unwrap().downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_object.len(), 5);
+
+        let get_string = |key: &str| node_object.get(&String::from(key)).unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap().get_value().clone();
+        assert_eq!(get_string("unquoted"), "single");
+        assert_eq!(get_string("Infinity"), "line continued");
+        assert_eq!(node_object.get(&String::from("true")).unwrap().get_node_type(), EnumNodeType::NULL);
+
+        let id = node_object.get(&String::from("$id")).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(id.value, 31.0);
+
+        let node_array = node_object.get(&String::from("quoted")).unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 4);
+        assert_eq!(node_array.get(2).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value, f64::INFINITY);
+    }
+
+    #[test]
+    fn parse_json5_errors()
+    {
+        for input in [ "[1,,]", "[,]", "{,}", "{a: 1,,}", "{'a' 1}", "{-a: 1}", "{1: 2}" ]
+        {
+            let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::builder().dialect(EnumDialect::JSON5).build());
+            assert!(parser.parse().is_err(), "{}", input);
+        }
+
+        // Strict JSON is still the default.
+        for input in [ "[1,]", "{a: 1}", "{'a': 1}", "// comment\n1", "0x1F", "Infinity" ]
+        {
+            let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::default());
+            assert!(parser.parse().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_json5_exact_numbers()
+    {
+        let input = String::from("[0xFFFFFFFFFFFFFFFF, -0x10, +.5]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().dialect(EnumDialect::JSON5).number_mode(EnumNumberMode::EXACT).build());
+
+        let node_array = parser.parse().unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        let numbers: Vec<Rc<RNodeNumber>> = (0..node_array.len())
+            .map(|i| node_array.get(i).unwrap().downcast_rc::<RNodeNumber>().map_err(|_| "Shouldn't happen").unwrap())
+            .collect();
+
+        assert_eq!(numbers[0].get_value(), EnumNumber::U64(u64::MAX));
+        assert_eq!(numbers[1].get_value(), EnumNumber::I64(-16));
+        assert_eq!(numbers[2].get_lexeme(), "0.5");
+    }
+
+    #[test]
+    fn parser_shares_options_with_lexer()
+    {
+        let options = ParserOptions::builder().dialect(EnumDialect::JSON5).lenient_numbers(true).build();
+        let mut parser = Parser::new_copy(&String::from("[.5, 'a',]"), &options);
+        assert_eq!(parser.get_options(), &options);
+        assert!(parser.parse().is_ok());
+
+        let mut parser = Parser::new_copy(&String::from("[.5, 'a',]"), &ParserOptions::default());
+        assert!(parser.parse().is_err());
+    }
+
+    fn parse_with_duplicate_keys(input: &str, policy: EnumDuplicateKeyPolicy) -> (Result<Rc<RNodeObject>, ParseError>, Vec<ParseError>)
+    {
+        let mut parser = Parser::new_copy(&String::from(input), &ParserOptions::builder().duplicate_key_policy(policy).build());
+
+        let result = parser.parse().map(|node| node.downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap());
+        return (result, parser.take_warnings());
+    }
+
+    fn get_double(node_object: &RNodeObject, key: &str) -> f64
+    {
+        return node_object.get(&String::from(key)).unwrap().downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value;
+    }
+
+    #[test]
+    fn parse_duplicate_keys_error()
+    {
+        let (result, _) = parse_with_duplicate_keys("{\"a\": 1,\n \"b\": 2, \"a\": 3}", EnumDuplicateKeyPolicy::ERROR);
+        let error = result.err().expect("Duplicate keys should be an error");
+
+        let expected = EnumParseErrorKind::DuplicateKey { key: String::from("a"), first_start: 1, first_line: 1, first_column: 2 };
+        assert_eq!(error.get_kind(), &expected);
+        assert_eq!((error.get_start(), error.get_end()), (18, 21));
+        assert_eq!((error.get_line(), error.get_column()), (2, 10));
+        assert_eq!(error.to_string(), "duplicate key \"a\" (first defined at line 1, column 2) at line 2, column 10");
+
+        // Keys in different objects aren't duplicates.
+        let (result, warnings) = parse_with_duplicate_keys("{\"a\": {\"a\": 1}}", EnumDuplicateKeyPolicy::ERROR);
+        assert!(result.is_ok());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_duplicate_keys_first_and_last_wins()
+    {
+        let input = "{\"a\": 1, \"b\": 2, \"a\": 3, \"a\": 4}";
+
+        let (result, warnings) = parse_with_duplicate_keys(input, EnumDuplicateKeyPolicy::FIRST_WINS);
+        let node_object = result.unwrap();
+        assert_eq!(get_double(&node_object, "a"), 1.0);
+        assert_eq!(node_object.len(), 2);
+        assert!(!node_object.has_duplicates());
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].get_start(), 25);
+
+        let (result, warnings) = parse_with_duplicate_keys(input, EnumDuplicateKeyPolicy::LAST_WINS);
+        let node_object = result.unwrap();
+        assert_eq!(get_double(&node_object, "a"), 4.0);
+        assert_eq!(node_object.keys().collect::<Vec<&String>>(), vec![ "a", "b" ]);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn parse_duplicate_keys_keep_all()
+    {
+        let (result, warnings) = parse_with_duplicate_keys("{\"a\": 1, \"b\": 2, \"a\": 3, \"a\": 4}", EnumDuplicateKeyPolicy::KEEP_ALL);
+        let node_object = result.unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(node_object.has_duplicates());
+        assert_eq!(get_double(&node_object, "a"), 4.0);
+
+        let values: Vec<f64> = node_object.get_all(&String::from("a")).into_iter()
+            .map(|node| node.downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value)
+            .collect();
+        assert_eq!(values, vec![ 1.0, 3.0, 4.0 ]);
+        assert_eq!(node_object.get_all(&String::from("b")).len(), 1);
+        assert!(node_object.get_all(&String::from("c")).is_empty());
+    }
+
+    #[test]
+    fn parse_recovering_duplicate_keys()
+    {
+        let mut parser = Parser::new_copy(&String::from("{\"a\": 1, \"a\": 2, \"b\" 3}"), &ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR).build());
+
+        let (root, errors) = parser.parse_recovering();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0].get_kind(), EnumParseErrorKind::DuplicateKey { .. }));
+
+        let node_object = root.downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(get_double(&node_object, "a"), 1.0);
+    }
+
+    #[test]
+    fn parse_numbers_default_to_double()
+    {
+        let input = String::from("9007199254740993");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let rnode = parser.parse().unwrap();
+
+        assert_eq!(rnode.get_node_type(), EnumNodeType::DOUBLE);
+    }
+
+    #[test]
+    fn parse_deeply_nested_without_limit()
+    {
+        // Far deeper than a recursive parser could go (parsing and dropping the tree mustn't overflow the stack).
+        let depth = 200000;
+        let input = format!("{}{}{}", "[{\"a\": ".repeat(depth), "1", "}]".repeat(depth));
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_depth(None).build());
+        let mut node = parser.parse().unwrap();
+
+        for _ in 0..depth
+        {
+            let node_array = node.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+            assert_eq!(node_array.len(), 1);
+
+            let node_object = node_array.get(0).unwrap().downcast_rc::<RNodeObject>().map_err(|_| "Shouldn't happen").unwrap();
+            assert_eq!(node_object.len(), 1);
+            node = node_object.get(&String::from("a")).unwrap();
+        }
+
+        assert_eq!(node.downcast_rc::<RNodeDouble>().map_err(|_| "Shouldn't happen").unwrap().value, 1.0);
+
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth - 1));
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_depth(None).build());
+        assert_eq!(parser.parse().err().unwrap().get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_depth(None).build());
+        let (_, errors) = parser.parse_recovering();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+    }
+
+    #[test]
+    fn parse_depth_limit()
+    {
+        // There is no limit by default.
+        let input = "[".repeat(100000);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        assert_eq!(parser.parse().err().unwrap().get_kind(), &EnumParseErrorKind::UnexpectedEOF);
+
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_depth(Some(128)).build());
+        let error = parser.parse().err().unwrap();
+
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::DepthLimitExceeded { limit: 128 });
+        assert_eq!(error.get_start(), 128);
+
+        let options = ParserOptions::builder().max_depth(Some(2)).build();
+        assert!(Parser::new_copy(&String::from("[{\"a\": 1}, [2]]"), &options).parse().is_ok());
+
+        let error = Parser::new_copy(&String::from("[{\"a\": [1]}]"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::DepthLimitExceeded { limit: 2 });
+        assert_eq!(error.get_start(), 7);
+
+        // The depth starts over for every value of a stream.
+        let mut parser = Parser::new_copy(&String::from("[[1]] [[2]]"), &options);
+        assert_eq!(parser.parse_stream().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn parse_recovering_depth_limit_skips_nested_value()
+    {
+        let input = String::from("[1, [[[2, 3]]], 4]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_depth(Some(1)).build());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::DepthLimitExceeded { limit: 1 });
+        assert_eq!(errors[0].get_start(), 4);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 3);
+        assert_eq!(node_array.get(1).unwrap().get_node_type(), EnumNodeType::ERROR);
+        assert_eq!(node_array.get(2).unwrap().get_node_type(), EnumNodeType::DOUBLE);
+    }
+
+    #[test]
+    fn parse_string_length_limit()
+    {
+        let options = ParserOptions::builder().max_string_length(Some(3)).build();
+        assert!(Parser::new_copy(&String::from("{\"abc\": \"é\"}"), &options).parse().is_ok());
+
+        // The error ends at the first character past the limit, without decoding the rest of the string.
+        let error = Parser::new_copy(&String::from("[\"abcd\"]"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::StringTooLong { limit: 3 });
+        assert_eq!((error.get_start(), error.get_end()), (1, 6));
+
+        let input = format!("[\"ab\\u00e9{}\", 1", "x".repeat(100000));
+        let error = Parser::new_copy(&input, &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::StringTooLong { limit: 3 });
+        assert_eq!((error.get_start(), error.get_end()), (1, 10));
+
+        // The limit applies to numbers too.
+        let error = Parser::new_copy(&String::from("[123, 1234]"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::NumberTooLong { limit: 3 });
+        assert_eq!((error.get_start(), error.get_end()), (6, 10));
+
+        // Like a string, it ends at the first character past the limit and the rest of the number is skipped.
+        let input = format!("[1{}e5, 2]", "0".repeat(100000));
+        let error = Parser::new_copy(&input, &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::NumberTooLong { limit: 3 });
+        assert_eq!((error.get_start(), error.get_end()), (1, 5));
+
+        let (_, errors) = Parser::new_copy(&input, &options).parse_recovering();
+        assert_eq!(errors.len(), 1);
+
+        let error = Parser::new_copy(&String::from("{\"abcd\": 1}"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::StringTooLong { limit: 3 });
+
+        let options = options.to_builder().dialect(EnumDialect::JSON5).build();
+        let error = Parser::new_copy(&String::from("{abcd: 1}"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::StringTooLong { limit: 3 });
+        assert_eq!(error.get_start(), 1);
+
+        let error = Parser::new_copy(&String::from("[0x1234]"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::NumberTooLong { limit: 3 });
+    }
+
+    #[test]
+    fn parse_element_and_member_limits()
+    {
+        let options = ParserOptions::builder().max_elements(Some(2)).max_members(Some(2)).build();
+        assert!(Parser::new_copy(&String::from("{\"a\": [1, 2], \"b\": []}"), &options).parse().is_ok());
+
+        let error = Parser::new_copy(&String::from("[1, 2, 3]"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TooManyElements { limit: 2 });
+        assert_eq!(error.get_start(), 7);
+
+        // Duplicate keys count as members too.
+        let error = Parser::new_copy(&String::from("{\"a\": 1, \"a\": 2, \"a\": 3}"), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::TooManyMembers { limit: 2 });
+        assert_eq!(error.get_start(), 17);
+    }
+
+    #[test]
+    fn parse_recovering_element_limit_skips_the_rest()
+    {
+        let input = String::from("[[1, 2, [3], 4], 5]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().max_elements(Some(2)).build());
+        let (rnode, errors) = parser.parse_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_kind(), &EnumParseErrorKind::TooManyElements { limit: 2 });
+        assert_eq!(errors[0].get_start(), 8);
+
+        let node_array = rnode.downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node_array.len(), 2);
+
+        let inner_array = node_array.get(0).unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(inner_array.len(), 2);
+    }
+
+    #[test]
+    fn parse_input_size_limit()
+    {
+        let options = ParserOptions::builder().max_input_size(Some(8)).build();
+        assert!(Parser::new_copy(&String::from("[1, 2]  "), &options).parse().is_ok());
+
+        let mut parser = Parser::new_copy(&String::from("[1, 2, 3]"), &options);
+        let error = parser.parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+        assert_eq!((error.get_start(), error.get_end()), (0, 9));
+
+        // Invalid UTF-8 still counts towards the size.
+        let mut parser = Parser::new_bytes(vec![ b'"', 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, b'"' ], &options);
+        assert_eq!(parser.parse().err().unwrap().get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+        assert_eq!(parser.parse_stream().err().unwrap().get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+
+        let (rnode, errors) = parser.parse_recovering();
+        assert_eq!(rnode.get_node_type(), EnumNodeType::ERROR);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_reader()
+    {
+        let input = format!("{{\"items\": [{}{{}}], \"last\" 1}}", "{\"id\": 1, \"name\": \"café\"}, ".repeat(20000));
+        let error = Parser::new_reader(Cursor::new(input.clone().into_bytes()), &ParserOptions::default()).parse().err().unwrap();
+        assert_eq!(error, Parser::new_copy(&input, &ParserOptions::default()).parse().err().unwrap());
+        assert_eq!(error.get_start(), input.len() - 2);
+
+        let input = input.replace("\"last\" 1", "\"last\": 1");
+        let root = Parser::new_reader(Cursor::new(input.into_bytes()), &ParserOptions::default()).parse().unwrap();
+        let object = root.downcast_ref::<RNodeObject>().unwrap();
+        assert_eq!(object.get(&String::from("items")).unwrap().downcast_ref::<RNodeArray>().unwrap().len(), 20001);
+
+        // The same error as for an input in memory.
+        let options = ParserOptions::builder().max_input_size(Some(8)).build();
+        let error = Parser::new_reader(Cursor::new(b"[1, 2, 3]".to_vec()), &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 8 });
+        assert_eq!((error.get_start(), error.get_end()), (0, 9));
+    }
+
+    #[test]
+    fn parse_preserved_lone_surrogates()
+    {
+        let options = ParserOptions::builder().stringify(true).surrogate_policy(EnumSurrogatePolicy::PRESERVE).build();
+        let input = String::from("[\"a\\uD83D\", \"\\\\uD83D\"]");
+        let root = Parser::new_copy(&input, &options).parse().unwrap();
+        let node_array = root.downcast_ref::<RNodeArray>().unwrap();
+
+        let node0 = node_array.get(0).unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node0.get_value(), "a\u{FFFD}");
+        assert_eq!(node0.get_lone_surrogates(), &[ (1, 0xD83D) ]);
+        assert_eq!(node0.to_utf16(), vec![ 0x61, 0xD83D ]);
+
+        // An escaped '\' followed by 'uD83D' is just text.
+        let node1 = node_array.get(1).unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(node1.get_value(), "\\uD83D");
+        assert!(node1.get_lone_surrogates().is_empty());
+
+        // A key can't hold one.
+        let input = String::from("{\"a\": 1, \"b\\uDC00\": 2}");
+        let error = Parser::new_copy(&input, &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::LoneSurrogate(0xDC00));
+        assert_eq!((error.get_start(), error.get_end()), (9, 18));
+
+        let (_, errors) = Parser::new_copy(&input, &options).parse_recovering();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_keys_decode_kept_escapes()
+    {
+        // Without stringify a value keeps its '\uXXXX' escapes, but a key has them decoded.
+        let input = String::from("{\"\\u00e9\\uD83D\\uDE00\\\\u0041\": \"\\u00e9\"}");
+        let root = Parser::new_copy(&input, &ParserOptions::default()).parse().unwrap();
+        let node_object = root.downcast_ref::<RNodeObject>().unwrap();
+
+        let value = node_object.get("é😀\\u0041").unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
+        assert_eq!(value.get_value(), "\\u00E9");
+        assert_eq!(value.get_unicode_escapes(), &[ 0 ]);
+
+        // Like with stringify, a key can't hold a lone surrogate.
+        let options = ParserOptions::builder().surrogate_policy(EnumSurrogatePolicy::PRESERVE).build();
+        let input = String::from("{\"b\\uDC00\": 2}");
+        let error = Parser::new_copy(&input, &options).parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::LoneSurrogate(0xDC00));
+        assert_eq!((error.get_start(), error.get_end()), (1, 10));
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn parse_decimal_numbers()
+    {
+        use crate::rnodes::rnode_decimal::RNodeDecimal;
+
+        let input = String::from("[12345678901234567890.123456789, 12345678901234567890.1234567890, 1e99999999999999999999]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::DECIMAL).build());
+
+        let error = parser.parse().err().unwrap();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::InvalidNumber(String::from("the exponent is too large")));
+        assert_eq!(error.get_start(), 66);
+
+        let input = String::from("[12345678901234567890.123456789, 12345678901234567890.1234567890]");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::builder().number_mode(EnumNumberMode::DECIMAL).build());
+
+        let node_array = parser.parse().unwrap().downcast_rc::<RNodeArray>().map_err(|_| "Shouldn't happen").unwrap();
+        let node0 = node_array.get(0).unwrap().downcast_rc::<RNodeDecimal>().map_err(|_| "Shouldn't happen").unwrap();
+        let node1 = node_array.get(1).unwrap().downcast_rc::<RNodeDecimal>().map_err(|_| "Shouldn't happen").unwrap();
+
+        assert_eq!(node0.get_node_type(), EnumNodeType::DECIMAL);
+        assert_eq!(node0.get_value().to_string(), "12345678901234567890.123456789");
+        assert_eq!(node1.get_lexeme(), "12345678901234567890.1234567890");
+        assert!(node0 == node1);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::parser::dialect::EnumDialect;
+use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+use crate::parser::number_mode::EnumNumberMode;
+use crate::parser::surrogate_policy::EnumSurrogatePolicy;
+
+/// Everything that changes how input is parsed, shared by the Lexer, Parser and RusonReader.
+/// The defaults parse strict JSON (RFC 8259) without any limits.
+///
+/// ```text
+/// let options = ParserOptions::builder()
+///     .dialect(EnumDialect::JSON5)
+///     .number_mode(EnumNumberMode::EXACT)
+///     .build();
+/// let mut parser = Parser::new_move(input, &options);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParserOptions
+{
+    dialect: EnumDialect,
+    lenient_numbers: bool,
+    duplicate_key_policy: EnumDuplicateKeyPolicy,
+    stringify: bool,
+    surrogate_policy: EnumSurrogatePolicy,
+    number_mode: EnumNumberMode,
+    max_depth: Option<usize>,
+    max_string_length: Option<usize>,
+    max_members: Option<usize>,
+    max_elements: Option<usize>,
+    max_input_size: Option<usize>,
+}
+
+impl ParserOptions
+{
+    pub fn builder() -> ParserOptionsBuilder
+    {
+        return ParserOptionsBuilder { options: ParserOptions::default() };
+    }
+
+    /// Creates a builder starting from these options (ex. to change just one of them).
+    pub fn to_builder(&self) -> ParserOptionsBuilder
+    {
+        return ParserOptionsBuilder { options: self.clone() };
+    }
+
+    pub fn get_dialect(&self) -> EnumDialect
+    {
+        return self.dialect;
+    }
+
+    pub fn get_lenient_numbers(&self) -> bool
+    {
+        return self.lenient_numbers;
+    }
+
+    pub fn get_duplicate_key_policy(&self) -> EnumDuplicateKeyPolicy
+    {
+        return self.duplicate_key_policy;
+    }
+
+    pub fn get_stringify(&self) -> bool
+    {
+        return self.stringify;
+    }
+
+    pub fn get_surrogate_policy(&self) -> EnumSurrogatePolicy
+    {
+        return self.surrogate_policy;
+    }
+
+    pub fn get_number_mode(&self) -> EnumNumberMode
+    {
+        return self.number_mode;
+    }
+
+    pub fn get_max_depth(&self) -> Option<usize>
+    {
+        return self.max_depth;
+    }
+
+    pub fn get_max_string_length(&self) -> Option<usize>
+    {
+        return self.max_string_length;
+    }
+
+    pub fn get_max_members(&self) -> Option<usize>
+    {
+        return self.max_members;
+    }
+
+    pub fn get_max_elements(&self) -> Option<usize>
+    {
+        return self.max_elements;
+    }
+
+    pub fn get_max_input_size(&self) -> Option<usize>
+    {
+        return self.max_input_size;
+    }
+}
+
+pub struct ParserOptionsBuilder
+{
+    options: ParserOptions,
+}
+
+impl ParserOptionsBuilder
+{
+    /// The flavour of JSON to accept (defaults to strict JSON).
+    pub fn dialect(mut self, dialect: EnumDialect) -> Self
+    {
+        self.options.dialect = dialect;
+        self
+    }
+
+    /// Allows numbers JSON forbids, such as '.5', '5.' and '007' (off by default).
+    pub fn lenient_numbers(mut self, lenient_numbers: bool) -> Self
+    {
+        self.options.lenient_numbers = lenient_numbers;
+        self
+    }
+
+    /// What happens when an object has the same key more than once (defaults to keeping the last value).
+    pub fn duplicate_key_policy(mut self, policy: EnumDuplicateKeyPolicy) -> Self
+    {
+        self.options.duplicate_key_policy = policy;
+        self
+    }
+
+    /// Decodes '\uXXXX' escapes into the characters they stand for, rather than keeping them
+    /// as written (off by default).
+    pub fn stringify(mut self, stringify: bool) -> Self
+    {
+        self.options.stringify = stringify;
+        self
+    }
+
+    /// How '\uXXXX' escapes of lone UTF-16 surrogates are handled (defaults to reporting an error).
+    pub fn surrogate_policy(mut self, policy: EnumSurrogatePolicy) -> Self
+    {
+        self.options.surrogate_policy = policy;
+        self
+    }
+
+    /// Which RNode numbers are parsed into (defaults to RNodeDouble).
+    pub fn number_mode(mut self, mode: EnumNumberMode) -> Self
+    {
+        self.options.number_mode = mode;
+        self
+    }
+
+    /// How deeply arrays and objects may be nested (no limit by default). Parsing, building the CST,
+    /// writing and dropping trees don't recurse, so any depth parses (ex. generated data nested
+    /// hundreds of thousands of levels deep); a limit only bounds what untrusted input can ask for.
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self
+    {
+        self.options.max_depth = max_depth;
+        self
+    }
+
+    /// The longest string or key allowed, in bytes once unescaped (unlimited by default). Numbers are
+    /// limited to as many bytes too.
+    pub fn max_string_length(mut self, max_string_length: Option<usize>) -> Self
+    {
+        self.options.max_string_length = max_string_length;
+        self
+    }
+
+    /// The most members a single object may have, counting duplicate keys (unlimited by default).
+    pub fn max_members(mut self, max_members: Option<usize>) -> Self
+    {
+        self.options.max_members = max_members;
+        self
+    }
+
+    /// The most elements a single array may have (unlimited by default).
+    pub fn max_elements(mut self, max_elements: Option<usize>) -> Self
+    {
+        self.options.max_elements = max_elements;
+        self
+    }
+
+    /// The largest input allowed, in bytes (unlimited by default).
+    pub fn max_input_size(mut self, max_input_size: Option<usize>) -> Self
+    {
+        self.options.max_input_size = max_input_size;
+        self
+    }
+
+    pub fn build(self) -> ParserOptions
+    {
+        return self.options;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::number_mode::EnumNumberMode;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
+
+    #[test]
+    fn default_options_are_strict_json()
+    {
+        let options = ParserOptions::default();
+        assert_eq!(options.get_dialect(), EnumDialect::JSON);
+        assert!(!options.get_lenient_numbers());
+        assert_eq!(options.get_duplicate_key_policy(), EnumDuplicateKeyPolicy::LAST_WINS);
+        assert!(!options.get_stringify());
+        assert_eq!(options.get_surrogate_policy(), EnumSurrogatePolicy::ERROR);
+        assert_eq!(options.get_number_mode(), EnumNumberMode::DOUBLE);
+        assert_eq!(options.get_max_depth(), None);
+        assert_eq!(options.get_max_string_length(), None);
+        assert_eq!(options.get_max_members(), None);
+        assert_eq!(options.get_max_elements(), None);
+        assert_eq!(options.get_max_input_size(), None);
+        assert_eq!(ParserOptions::builder().build(), options);
+    }
+
+    #[test]
+    fn build_options()
+    {
+        let options = ParserOptions::builder()
+            .dialect(EnumDialect::JSON5)
+            .lenient_numbers(true)
+            .duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR)
+            .stringify(true)
+            .surrogate_policy(EnumSurrogatePolicy::REPLACE)
+            .number_mode(EnumNumberMode::EXACT)
+            .max_depth(Some(5))
+            .max_string_length(Some(1))
+            .max_members(Some(2))
+            .max_elements(Some(3))
+            .max_input_size(Some(4))
+            .build();
+
+        assert_eq!(options.get_dialect(), EnumDialect::JSON5);
+        assert!(options.get_lenient_numbers());
+        assert_eq!(options.get_duplicate_key_policy(), EnumDuplicateKeyPolicy::ERROR);
+        assert!(options.get_stringify());
+        assert_eq!(options.get_surrogate_policy(), EnumSurrogatePolicy::REPLACE);
+        assert_eq!(options.get_number_mode(), EnumNumberMode::EXACT);
+        assert_eq!(options.get_max_depth(), Some(5));
+        assert_eq!(options.get_max_string_length(), Some(1));
+        assert_eq!(options.get_max_members(), Some(2));
+        assert_eq!(options.get_max_elements(), Some(3));
+        assert_eq!(options.get_max_input_size(), Some(4));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::parser::feed_result::EnumFeedResult;
+use crate::parser::json_events::JsonEvents;
+use crate::parser::parse_error::ParseError;
+use crate::parser::parser_options::ParserOptions;
+use crate::parser::tree_builder::TreeBuilder;
+use crate::rnodes::rnode::RNode;
+
+use std::rc::Rc;
+
+/// Parses a single root value from input that arrives in chunks (ex. from a socket or a token stream),
+/// building the same RNode tree as 'TreeBuilder'. A chunk can end anywhere, including in the middle
+/// of a string, an escape or a UTF-8 sequence.
+///
+/// ```text
+/// let mut parser = PushParser::new(&options);
+///
+/// while let Some(chunk) = read_chunk()
+/// {
+///     parser.feed(&chunk)?;
+/// }
+///
+/// let root = parser.finish()?;
+/// ```
+///
+/// A root value is only complete once whatever follows it shows where it ends, so a root number
+/// (ex. '12') isn't returned by 'feed' until it is followed by whitespace or the input is finished.
+pub struct PushParser
+{
+    events: JsonEvents,
+    builder: TreeBuilder,
+    root: Option<Rc<dyn RNode>>,
+    /// The error that stopped the parse, returned again by any further 'feed' or 'finish'.
+    error: Option<ParseError>,
+}
+
+impl PushParser
+{
+    pub fn new(options: &ParserOptions) -> Self
+    {
+        Self { events: JsonEvents::new_push(options), builder: TreeBuilder::new_with_policy(options.get_duplicate_key_policy()), root: None, error: None }
+    }
+
+    pub fn get_options(&self) -> &ParserOptions
+    {
+        return self.events.get_options();
+    }
+
+    /// Parses as much as possible of the input fed so far along with the next chunk.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<EnumFeedResult, ParseError>
+    {
+        if let Some(error) = &self.error
+        {
+            return Err(error.clone());
+        }
+
+        self.events.feed(chunk);
+        return self.resume();
+    }
+
+    /// Marks the end of the input and returns the root value, which fails when the input
+    /// ended before the root value was complete.
+    pub fn finish(mut self) -> Result<Rc<dyn RNode>, ParseError>
+    {
+        if let Some(error) = self.error
+        {
+            return Err(error);
+        }
+
+        self.events.end_input();
+
+        match self.resume()?
+        {
+            EnumFeedResult::VALUE(root) => { return Ok(root); },
+            EnumFeedResult::NEED_MORE_DATA => { unreachable!("The whole input should have been parsed"); },
+        }
+    }
+
+    fn resume(&mut self) -> Result<EnumFeedResult, ParseError>
+    {
+        if let Err(error) = self.events.drive(&mut self.builder)
+        {
+            self.error = Some(error.clone());
+            return Err(error);
+        }
+
+        if self.root.is_none()
+        {
+            self.root = self.builder.take_root();
+        }
+
+        match &self.root
+        {
+            Some(root) => { return Ok(EnumFeedResult::VALUE(root.clone())); },
+            None => { return Ok(EnumFeedResult::NEED_MORE_DATA); },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::dialect::EnumDialect;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::feed_result::EnumFeedResult;
+    use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::push_parser::PushParser;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
+    use crate::rnodes::rnode::{EnumNodeType, RNode};
+    use crate::rnodes::rnode_array::RNodeArray;
+    use crate::rnodes::rnode_bool::RNodeBool;
+    use crate::rnodes::rnode_double::RNodeDouble;
+    use crate::rnodes::rnode_object::RNodeObject;
+    use crate::rnodes::rnode_string::RNodeString;
+
+    use std::rc::Rc;
+
+    /// Describes a tree compactly, to compare the trees built by different parsers.
+    fn describe(node: &Rc<dyn RNode>) -> String
+    {
+        match node.get_node_type()
+        {
+            EnumNodeType::ARRAY => { return format!("[{}]", node.downcast_ref::<RNodeArray>().unwrap().iter().map(describe).collect::<Vec<_>>().join(",")); },
+            EnumNodeType::OBJECT =>
+            {
+                let object = node.downcast_ref::<RNodeObject>().unwrap();
+                let members: Vec<String> = object.keys().zip(object.values()).map(|(key, value)| format!("{:?}:{}", key, describe(value))).collect();
+                return format!("{{{}}}", members.join(","));
+            },
+            EnumNodeType::BOOL => { return node.downcast_ref::<RNodeBool>().unwrap().value.to_string(); },
+            EnumNodeType::DOUBLE => { return node.downcast_ref::<RNodeDouble>().unwrap().value.to_string(); },
+            EnumNodeType::STRING => { return format!("{:?}", node.downcast_ref::<RNodeString>().unwrap().get_value()); },
+            node_type => { return node_type.to_string(); },
+        }
+    }
+
+    /// Feeds the chunks one after the other, expecting the root value to only be complete after the last one.
+    fn push_chunks(chunks: &[&[u8]], options: &ParserOptions) -> Result<Rc<dyn RNode>, ParseError>
+    {
+        let mut parser = PushParser::new(options);
+
+        for chunk in &chunks[..chunks.len() - 1]
+        {
+            assert!(matches!(parser.feed(chunk)?, EnumFeedResult::NEED_MORE_DATA));
+        }
+
+        let _ = parser.feed(chunks[chunks.len() - 1])?;
+        return parser.finish();
+    }
+
+    #[test]
+    fn push_parser_matches_parser_for_any_split()
+    {
+        let input = "{\"name\": \"caf\u{e9} \\u00e9\\uD83D\\uDE00\\n\", \"values\": [1, -2.5e3, true, null, [], {}], \"nested\": {\"a\": [\"x\"]}}";
+        let options = ParserOptions::builder().stringify(true).build();
+        let expected = describe(&Parser::new_copy(&String::from(input), &options).parse().unwrap());
+        let bytes = input.as_bytes();
+
+        for split in 1..bytes.len()
+        {
+            let root = push_chunks(&[ &bytes[..split], &bytes[split..] ], &options).unwrap();
+            assert_eq!(describe(&root), expected, "split at {}", split);
+        }
+
+        let chunks: Vec<&[u8]> = bytes.chunks(1).collect();
+        assert_eq!(describe(&push_chunks(&chunks, &options).unwrap()), expected);
+    }
+
+    #[test]
+    fn push_parser_returns_the_value_once_complete()
+    {
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert!(matches!(parser.feed(b"[1, [2").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert!(matches!(parser.feed(b"]").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert!(matches!(parser.feed(b"]").unwrap(), EnumFeedResult::VALUE(_)));
+        assert!(matches!(parser.feed(b"  \n").unwrap(), EnumFeedResult::VALUE(_)));
+        assert_eq!(describe(&parser.finish().unwrap()), "[1,[2]]");
+
+        // A number (or a literal) could still go on in the next chunk.
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert!(matches!(parser.feed(b"12").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert!(matches!(parser.feed(b"3").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert_eq!(describe(&parser.finish().unwrap()), "123");
+
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert!(matches!(parser.feed(b"tr").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert!(matches!(parser.feed(b"ue ").unwrap(), EnumFeedResult::VALUE(_)));
+    }
+
+    #[test]
+    fn push_parser_long_string_in_small_chunks()
+    {
+        let value = "abc\\u00e9\\\"".repeat(20000);
+        let input = format!("[\"{}\"]", value);
+        let chunks: Vec<&[u8]> = input.as_bytes().chunks(7).collect();
+
+        let root = push_chunks(&chunks, &ParserOptions::default()).unwrap();
+        let expected = Parser::new_copy(&input, &ParserOptions::default()).parse().unwrap();
+        assert_eq!(describe(&root), describe(&expected));
+
+        // The string length limit is checked as the string grows, across chunks too.
+        let options = ParserOptions::builder().max_string_length(Some(1000)).build();
+        let expected = Parser::new_copy(&input, &options).parse().err().unwrap();
+        assert_eq!(push_chunks(&chunks, &options).err().unwrap(), expected);
+
+        // 100 repetitions fit (10 bytes each, as the '\u00e9' is kept), the 'a' after them doesn't.
+        assert_eq!(expected.get_end(), "[\"".len() + 100 * "abc\\u00e9\\\"".len() + 1);
+    }
+
+    #[test]
+    fn push_parser_preserved_lone_surrogates()
+    {
+        let input = "[\"\u{e9}\\uD83D\\u0041\\uDC00\"]";
+        let options = ParserOptions::builder().stringify(true).surrogate_policy(EnumSurrogatePolicy::PRESERVE).build();
+        let bytes = input.as_bytes();
+
+        for split in 1..bytes.len()
+        {
+            let root = push_chunks(&[ &bytes[..split], &bytes[split..] ], &options).unwrap();
+            let node = root.downcast_ref::<RNodeArray>().unwrap().get(0).unwrap().downcast_rc::<RNodeString>().map_err(|_| "Shouldn't happen").unwrap();
+            assert_eq!(node.get_value(), "\u{e9}\u{FFFD}A\u{FFFD}", "split at {}", split);
+            assert_eq!(node.get_lone_surrogates(), &[ (2, 0xD83D), (6, 0xDC00) ], "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn push_parser_matches_parser_for_duplicate_keys()
+    {
+        let input = "{\"a\": 1, \"b\": {\"c\": 2, \"c\": 3}, \"a\": [4], \"a\": 5}";
+        let chunks: Vec<&[u8]> = input.as_bytes().chunks(3).collect();
+
+        for policy in [ EnumDuplicateKeyPolicy::FIRST_WINS, EnumDuplicateKeyPolicy::LAST_WINS, EnumDuplicateKeyPolicy::KEEP_ALL ]
+        {
+            let options = ParserOptions::builder().duplicate_key_policy(policy).build();
+            let expected = Parser::new_copy(input, &options).parse().unwrap();
+            let root = push_chunks(&chunks, &options).unwrap();
+            assert_eq!(describe(&root), describe(&expected), "{:?}", policy);
+
+            let (object, expected_object) = (root.downcast_ref::<RNodeObject>().unwrap(), expected.downcast_ref::<RNodeObject>().unwrap());
+            assert_eq!(object.has_duplicates(), expected_object.has_duplicates(), "{:?}", policy);
+            assert_eq!(object.get_all("a").iter().map(describe).collect::<Vec<_>>(), expected_object.get_all("a").iter().map(describe).collect::<Vec<_>>(), "{:?}", policy);
+        }
+
+        assert_eq!(describe(&push_chunks(&chunks, &ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::FIRST_WINS).build()).unwrap()), "{\"a\":1,\"b\":{\"c\":2}}");
+
+        let options = ParserOptions::builder().duplicate_key_policy(EnumDuplicateKeyPolicy::ERROR).build();
+        let expected = Parser::new_copy(input, &options).parse().err().unwrap();
+        let mut parser = PushParser::new(&options);
+        let error = chunks.iter().find_map(|chunk| parser.feed(chunk).err()).or_else(|| parser.finish().err()).unwrap();
+        assert_eq!(error, expected);
+    }
+
+    #[test]
+    fn push_parser_json5()
+    {
+        let input = "// comment\n{key: 'single', trailing: [0x1F, +.5, Infinity,], /* end */}";
+        let options = ParserOptions::builder().dialect(EnumDialect::JSON5).build();
+        let expected = describe(&Parser::new_copy(&String::from(input), &options).parse().unwrap());
+        let bytes = input.as_bytes();
+
+        for split in 1..bytes.len()
+        {
+            assert_eq!(describe(&push_chunks(&[ &bytes[..split], &bytes[split..] ], &options).unwrap()), expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn push_parser_errors()
+    {
+        // The same errors (at the same positions) as parsing all of the input at once.
+        for input in [ "[1, 2", "[1 2]", "{\"a\": 1,}", "[\"\\x\"]", "[1] 2", "", "[\"abc" ]
+        {
+            let expected = Parser::new_copy(&String::from(input), &ParserOptions::default()).parse().err().unwrap();
+            let chunks: Vec<&[u8]> = input.as_bytes().chunks(1).collect();
+            let mut parser = PushParser::new(&ParserOptions::default());
+            let error = chunks.iter().find_map(|chunk| parser.feed(chunk).err()).or_else(|| parser.finish().err()).unwrap();
+            assert_eq!(error, expected, "{}", input);
+        }
+
+        // The error is kept for anything fed after it.
+        let mut parser = PushParser::new(&ParserOptions::default());
+        let error = parser.feed(b"[1, @").unwrap_err();
+        assert_eq!(error.get_kind(), &EnumParseErrorKind::UnexpectedCharacter('@'));
+        assert_eq!(parser.feed(b"]").unwrap_err(), error);
+        assert_eq!(parser.finish().err().unwrap(), error);
+
+        // Invalid UTF-8 split across chunks.
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert!(matches!(parser.feed(b"[\"\xC3").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert_eq!(parser.feed(b"(\"]").unwrap_err().get_kind(), &EnumParseErrorKind::InvalidUtf8);
+
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert!(matches!(parser.feed(b"\"\xE2\x82").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert_eq!(parser.finish().err().unwrap().get_kind(), &EnumParseErrorKind::InvalidUtf8);
+
+        let options = ParserOptions::builder().max_input_size(Some(4)).build();
+        let mut parser = PushParser::new(&options);
+        assert!(matches!(parser.feed(b"[1, ").unwrap(), EnumFeedResult::NEED_MORE_DATA));
+        assert_eq!(parser.feed(b"2]").unwrap_err().get_kind(), &EnumParseErrorKind::InputTooLarge { limit: 4 });
+    }
+}
@@ -1,13 +1,18 @@
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug)]
 pub struct Snapshot
 {
     start: usize,
+    line: usize,
+    column: usize,
 }
 
 impl Snapshot
 {
-    pub fn new(start: usize) -> Self
+    pub fn new(start: usize, line: usize, column: usize) -> Self
     {
-        Self { start }
+        Self { start, line, column }
     }
 
     pub fn get_start_pos(&self) -> usize
@@ -15,9 +20,22 @@ impl Snapshot
         return self.start;
     }
 
-    pub fn to_string(&self) -> String
+    pub fn get_line(&self) -> usize
+    {
+        return self.line;
+    }
+
+    pub fn get_column(&self) -> usize
+    {
+        return self.column;
+    }
+}
+
+impl Display for Snapshot
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        return self.start.to_string();
+        write!(f, "{}:{}", self.line, self.column)
     }
 }
 
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+/// How a '\uXXXX' escape of a lone UTF-16 surrogate (one without its high/low partner) is handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EnumSurrogatePolicy
+{
+    /// Report the lone surrogate as a parse error.
+    #[default]
+    ERROR,
+    /// Replace the lone surrogate with U+FFFD (the replacement character).
+    REPLACE,
+    /// Keep the lone surrogate. Without stringify, its escape sequence is kept as is. Otherwise a String
+    /// can't store it, so it's decoded to a U+FFFD placeholder that the string node records the code unit
+    /// of (see 'RNodeString::get_lone_surrogates'). A key can't record one, so it's reported as an error.
+    PRESERVE,
+}
+
+pub fn get_surrogate_policy_from_string(string: &str) -> Result<EnumSurrogatePolicy, String>
+{
+    static LOOKUP_TABLE: std::sync::LazyLock<HashMap::<String, EnumSurrogatePolicy>> = std::sync::LazyLock::new(||
+    {
+        let mut table = HashMap::<String, EnumSurrogatePolicy>::new();
+        table.insert(String::from("ERROR"), EnumSurrogatePolicy::ERROR);
+        table.insert(String::from("PRESERVE"), EnumSurrogatePolicy::PRESERVE);
+        table.insert(String::from("REPLACE"), EnumSurrogatePolicy::REPLACE);
+
+        return table;
+    });
+
+    let string_upper = string.to_uppercase();
+    let opt_result = LOOKUP_TABLE.get(&string_upper);
+
+    if let Some(result) = opt_result
+    {
+        return Ok(*result);
+    }
+
+    return Err(String::from("String is not a EnumSurrogatePolicy"));
+}
+
+/// Checks if the UTF-16 code unit is the first half of a surrogate pair.
+pub fn is_high_surrogate(code_unit: u32) -> bool
+{
+    return (0xD800..=0xDBFF).contains(&code_unit);
+}
+
+/// Checks if the UTF-16 code unit is the second half of a surrogate pair.
+pub fn is_low_surrogate(code_unit: u32) -> bool
+{
+    return (0xDC00..=0xDFFF).contains(&code_unit);
+}
+
+/// Combines a high and low surrogate into the code point they encode.
+pub fn combine_surrogates(high: u32, low: u32) -> u32
+{
+    return 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::surrogate_policy::*;
+
+    #[test]
+    fn surrogate_policy_from_string()
+    {
+        assert_eq!(get_surrogate_policy_from_string(&String::from("error")), Ok(EnumSurrogatePolicy::ERROR));
+        assert_eq!(get_surrogate_policy_from_string(&String::from("Replace")), Ok(EnumSurrogatePolicy::REPLACE));
+        assert_eq!(get_surrogate_policy_from_string(&String::from("PRESERVE")), Ok(EnumSurrogatePolicy::PRESERVE));
+        assert!(get_surrogate_policy_from_string(&String::from("ignore")).is_err());
+    }
+
+    #[test]
+    fn combine_surrogate_pairs()
+    {
+        assert!(is_high_surrogate(0xD83D));
+        assert!(!is_high_surrogate(0xDE00));
+        assert!(is_low_surrogate(0xDE00));
+        assert!(!is_low_surrogate(0xD83D));
+
+        assert_eq!(combine_surrogates(0xD83D, 0xDE00), 0x1F600);
+        assert_eq!(combine_surrogates(0xD800, 0xDC00), 0x10000);
+        assert_eq!(combine_surrogates(0xDBFF, 0xDFFF), 0x10FFFF);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parser::token_double::TokenDouble;
+
 use downcast_rs::Downcast;
 
 #[derive(Debug, PartialEq)]
@@ -10,13 +12,11 @@ pub trait TokenTrait : Downcast
 {
     fn get_type(&self) -> EnumTokenType;
     fn as_bool(&self) -> Result<bool, String>;
-    #[allow(dead_code)]
     fn as_char(&self) -> Result<char, String>;
     fn as_double(&self) -> Result<f64, String>;
     fn as_string(&self) -> Result<&String, String>;
     fn as_symbol(&self) -> Result<&String, String>;
     fn is_bool(&self) -> bool;
-    #[allow(dead_code)]
     fn is_char(&self) -> bool;
     fn is_double(&self) -> bool;
     fn is_null(&self) -> bool;
@@ -26,3 +26,30 @@ pub trait TokenTrait : Downcast
 
 impl_downcast!(TokenTrait);
 
+
+/// Describes a token in a human readable form for error messages (ex. "'}'" or "string \"key\"").
+pub fn describe_token(token: &dyn TokenTrait) -> String
+{
+    match token.get_type()
+    {
+        EnumTokenType::BOOL => { return token.as_bool().unwrap().to_string(); },
+        EnumTokenType::CHAR => { return format!("'{}'", token.as_char().unwrap()); },
+        EnumTokenType::DOUBLE =>
+        {
+            match token.downcast_ref::<TokenDouble>()
+            {
+                Some(token_double) => { return format!("number {}", token_double.get_lexeme()); },
+                None => { return format!("number {}", token.as_double().unwrap()); },
+            }
+        },
+        EnumTokenType::NULL => { return String::from("null"); },
+        EnumTokenType::STRING => { return format!("string \"{}\"", token.as_string().unwrap()); },
+        EnumTokenType::SYMBOL => { return format!("'{}'", token.as_symbol().unwrap()); },
+    }
+}
+
+/// Checks whether the token is the given symbol (ex. "," or "}").
+pub fn is_symbol(token: &dyn TokenTrait, symbol: &str) -> bool
+{
+    return token.is_symbol() && token.as_symbol().unwrap() == symbol;
+}
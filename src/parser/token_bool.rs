@@ -7,22 +7,19 @@ pub struct TokenBool
 
 impl TokenBool
 {
-    #[allow(dead_code)]
     pub fn new(value: bool) -> Self
     {
         Self { value }
     }
 
-    #[allow(dead_code)]
     pub fn get_value(&self) -> bool
     {
-        return self.value.into();
+        return self.value;
     }
 
-    #[allow(dead_code)]
     pub fn set_value(&mut self, value: bool)
     {
-        self.value = value.into();
+        self.value = value;
     }
 }
 
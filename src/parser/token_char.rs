@@ -7,22 +7,19 @@ pub struct TokenChar
 
 impl TokenChar
 {
-    #[allow(dead_code)]
     pub fn new(value: char) -> Self
     {
         Self { value }
     }
 
-    #[allow(dead_code)]
     pub fn get_value(&self) -> char
     {
-        return self.value.into();
+        return self.value;
     }
 
-    #[allow(dead_code)]
     pub fn set_value(&mut self, value: char)
     {
-        self.value = value.into();
+        self.value = value;
     }
 }
 
@@ -3,26 +3,35 @@ use crate::parser::token::{EnumTokenType, TokenTrait};
 pub struct TokenDouble
 {
     value: f64,
+    /// The number exactly as it was written in the input.
+    lexeme: String,
 }
 
 impl TokenDouble
 {
-    #[allow(dead_code)]
     pub fn new(value: f64) -> Self
     {
-        Self { value }
+        Self { value, lexeme: value.to_string() }
+    }
+
+    pub fn new_lexeme(value: f64, lexeme: String) -> Self
+    {
+        Self { value, lexeme }
+    }
+
+    pub fn get_lexeme(&self) -> &String
+    {
+        return &self.lexeme;
     }
 
-    #[allow(dead_code)]
     pub fn get_value(&self) -> f64
     {
-        return self.value.into();
+        return self.value;
     }
 
-    #[allow(dead_code)]
     pub fn set_value(&mut self, value: f64)
     {
-        self.value = value.into();
+        self.value = value;
     }
 }
 
@@ -107,5 +116,14 @@ mod tests
         token.set_value(in_value);
         assert_eq!(token.get_value(), in_value);
     }
+
+    #[test]
+    fn create_token_double_keeps_lexeme()
+    {
+        let token = TokenDouble::new_lexeme(9007199254740992.0, String::from("9007199254740993"));
+        assert_eq!(token.get_value(), 9007199254740992.0);
+        assert_eq!(token.get_lexeme(), "9007199254740993");
+        assert_eq!(TokenDouble::new(42.0).get_lexeme(), "42");
+    }
 }
 
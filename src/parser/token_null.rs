@@ -4,13 +4,20 @@ pub struct TokenNull;
 
 impl TokenNull
 {
-    #[allow(dead_code)]
     pub fn new() -> Self
     {
         Self {}
     }
 }
 
+impl Default for TokenNull
+{
+    fn default() -> Self
+    {
+        return Self::new();
+    }
+}
+
 impl TokenTrait for TokenNull
 {
     fn get_type(&self) -> EnumTokenType
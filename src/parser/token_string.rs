@@ -3,35 +3,54 @@ use crate::parser::token::{EnumTokenType, TokenTrait};
 pub struct TokenString
 {
     value: String,
+    /// The lone surrogates kept in a decoded string, as (byte offset of their U+FFFD placeholder, code unit).
+    lone_surrogates: Vec<(usize, u16)>,
+    /// The byte offsets of the '\uXXXX' escapes kept in a string that wasn't decoded.
+    unicode_escapes: Vec<usize>,
 }
 
 impl TokenString
 {
-    #[allow(dead_code)]
     pub fn new(value: String) -> Self
     {
-        Self { value }
+        Self { value, lone_surrogates: Vec::new(), unicode_escapes: Vec::new() }
+    }
+
+    pub fn new_with_lone_surrogates(value: String, lone_surrogates: Vec<(usize, u16)>) -> Self
+    {
+        Self { value, lone_surrogates, unicode_escapes: Vec::new() }
+    }
+
+    pub fn new_with_unicode_escapes(value: String, unicode_escapes: Vec<usize>) -> Self
+    {
+        Self { value, lone_surrogates: Vec::new(), unicode_escapes }
     }
 
-    #[allow(dead_code)]
     pub fn get_value(&self) -> &String
     {
         return &self.value;
     }
 
-    #[allow(dead_code)]
+    pub fn get_lone_surrogates(&self) -> &[(usize, u16)]
+    {
+        return &self.lone_surrogates;
+    }
+
+    pub fn get_unicode_escapes(&self) -> &[usize]
+    {
+        return &self.unicode_escapes;
+    }
+
     pub fn get_value_mut(&mut self) -> &mut String
     {
         return &mut self.value;
     }
 
-    #[allow(dead_code)]
-    pub fn set_value_copy(&mut self, value: &String)
+    pub fn set_value_copy(&mut self, value: &str)
     {
-        self.value = value.clone();
+        self.value = String::from(value);
     }
 
-    #[allow(dead_code)]
     pub fn set_value_move(&mut self, value: String)
     {
         self.value = value;
@@ -62,7 +81,7 @@ impl TokenTrait for TokenString
 
     fn as_string(&self) -> Result<&String, String>
     {
-        return Ok(&self.get_value());
+        return Ok(self.get_value());
     }
 
     fn as_symbol(&self) -> Result<&String, String>
@@ -7,31 +7,26 @@ pub struct TokenSymbol
 
 impl TokenSymbol
 {
-    #[allow(dead_code)]
     pub fn new(symbol: String) -> Self
     {
         Self { symbol }
     }
 
-    #[allow(dead_code)]
     pub fn get_symbol(&self) -> &String
     {
         return &self.symbol;
     }
 
-    #[allow(dead_code)]
     pub fn get_sybmol_mut(&mut self) -> &mut String
     {
         return &mut self.symbol;
     }
 
-    #[allow(dead_code)]
-    pub fn set_symbol_copy(&mut self, symbol: &String)
+    pub fn set_symbol_copy(&mut self, symbol: &str)
     {
-        self.symbol = symbol.clone();
+        self.symbol = String::from(symbol);
     }
 
-    #[allow(dead_code)]
     pub fn set_symbol_move(&mut self, symbol: String)
     {
         self.symbol = symbol;
@@ -67,7 +62,7 @@ impl TokenTrait for TokenSymbol
 
     fn as_symbol(&self) -> Result<&String, String>
     {
-        return Ok(&self.get_symbol());
+        return Ok(self.get_symbol());
     }
 
     fn is_bool(&self) -> bool
@@ -0,0 +1,193 @@
+use crate::parser::control::EnumControl;
+use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+use crate::parser::json_handler::JsonHandler;
+use crate::parser::parser::insert_duplicate_value;
+use crate::rnodes::rnode::RNode;
+use crate::rnodes::rnode_array::RNodeArray;
+use crate::rnodes::rnode_object::RNodeObject;
+use crate::utils::ordered_map::OrderedMap;
+
+use std::rc::Rc;
+
+/// An array or object whose values are still being handled.
+#[allow(non_camel_case_types)]
+enum EnumPartial
+{
+    ARRAY(Vec<Rc<dyn RNode>>),
+    /// The members so far, the values of each duplicate key (for KEEP_ALL) and the key of the member
+    /// whose value is next.
+    OBJECT(OrderedMap<Rc<dyn RNode>>, OrderedMap<Vec<Rc<dyn RNode>>>, Option<String>),
+}
+
+/// A JsonHandler that builds the same RNode tree as the Parser, applying the same duplicate key policy
+/// (an ERROR is reported by 'JsonEvents' before the key is handled).
+///
+/// ```text
+/// let mut builder = TreeBuilder::new_with_policy(options.get_duplicate_key_policy());
+/// JsonEvents::new_move(input, &options).drive(&mut builder)?;
+/// let root = builder.take_root();
+/// ```
+pub struct TreeBuilder
+{
+    partials: Vec<EnumPartial>,
+    root: Option<Rc<dyn RNode>>,
+    duplicate_key_policy: EnumDuplicateKeyPolicy,
+}
+
+impl TreeBuilder
+{
+    pub fn new() -> Self
+    {
+        return Self::new_with_policy(EnumDuplicateKeyPolicy::default());
+    }
+
+    pub fn new_with_policy(duplicate_key_policy: EnumDuplicateKeyPolicy) -> Self
+    {
+        Self { partials: Vec::new(), root: None, duplicate_key_policy }
+    }
+
+    /// Takes the root value, once it has been handled completely.
+    pub fn take_root(&mut self) -> Option<Rc<dyn RNode>>
+    {
+        return self.root.take();
+    }
+
+    /// Adds a finished value to the enclosing array/object, or makes it the root.
+    fn add(&mut self, node: Rc<dyn RNode>)
+    {
+        match self.partials.last_mut()
+        {
+            Some(EnumPartial::ARRAY(nodes)) => { nodes.push(node); },
+            Some(EnumPartial::OBJECT(nodes, duplicates, key)) =>
+            {
+                let key = key.take().expect("A value should follow its key");
+
+                if nodes.contains_key(&key)
+                {
+                    insert_duplicate_value(self.duplicate_key_policy, nodes, duplicates, key, node);
+                }
+
+                else
+                {
+                    nodes.insert(key, node);
+                }
+            },
+            None => { self.root = Some(node); },
+        }
+    }
+}
+
+impl Default for TreeBuilder
+{
+    fn default() -> Self
+    {
+        return Self::new();
+    }
+}
+
+impl JsonHandler for TreeBuilder
+{
+    fn on_object_start(&mut self) -> EnumControl
+    {
+        self.partials.push(EnumPartial::OBJECT(OrderedMap::new(), OrderedMap::new(), None));
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_key(&mut self, key: &str) -> EnumControl
+    {
+        if let Some(EnumPartial::OBJECT(_, _, next_key)) = self.partials.last_mut()
+        {
+            *next_key = Some(String::from(key));
+        }
+
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_value(&mut self, value: Rc<dyn RNode>) -> EnumControl
+    {
+        self.add(value);
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_object_end(&mut self) -> EnumControl
+    {
+        if let Some(EnumPartial::OBJECT(nodes, duplicates, _)) = self.partials.pop()
+        {
+            self.add(Rc::new(RNodeObject::new_multi(nodes, duplicates)));
+        }
+
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_array_start(&mut self) -> EnumControl
+    {
+        self.partials.push(EnumPartial::ARRAY(Vec::new()));
+        return EnumControl::CONTINUE;
+    }
+
+    fn on_array_end(&mut self) -> EnumControl
+    {
+        if let Some(EnumPartial::ARRAY(nodes)) = self.partials.pop()
+        {
+            self.add(Rc::new(RNodeArray::new(nodes)));
+        }
+
+        return EnumControl::CONTINUE;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::json_events::JsonEvents;
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use crate::parser::tree_builder::TreeBuilder;
+    use crate::rnodes::rnode::{EnumNodeType, RNode};
+    use crate::rnodes::rnode_array::RNodeArray;
+    use crate::rnodes::rnode_bool::RNodeBool;
+    use crate::rnodes::rnode_double::RNodeDouble;
+    use crate::rnodes::rnode_object::RNodeObject;
+    use crate::rnodes::rnode_string::RNodeString;
+
+    use std::rc::Rc;
+
+    fn assert_same_tree(left: &Rc<dyn RNode>, right: &Rc<dyn RNode>)
+    {
+        assert_eq!(left.get_node_type(), right.get_node_type());
+
+        match left.get_node_type()
+        {
+            EnumNodeType::ARRAY =>
+            {
+                let (left, right) = (left.downcast_ref::<RNodeArray>().unwrap(), right.downcast_ref::<RNodeArray>().unwrap());
+                assert_eq!(left.len(), right.len());
+                left.iter().zip(right.iter()).for_each(|(left, right)| assert_same_tree(left, right));
+            },
+            EnumNodeType::OBJECT =>
+            {
+                let (left, right) = (left.downcast_ref::<RNodeObject>().unwrap(), right.downcast_ref::<RNodeObject>().unwrap());
+                assert_eq!(left.keys().collect::<Vec<_>>(), right.keys().collect::<Vec<_>>());
+                left.values().zip(right.values()).for_each(|(left, right)| assert_same_tree(left, right));
+            },
+            EnumNodeType::BOOL => { assert_eq!(left.downcast_ref::<RNodeBool>().unwrap().value, right.downcast_ref::<RNodeBool>().unwrap().value); },
+            EnumNodeType::DOUBLE => { assert_eq!(left.downcast_ref::<RNodeDouble>().unwrap().value, right.downcast_ref::<RNodeDouble>().unwrap().value); },
+            EnumNodeType::STRING => { assert_eq!(left.downcast_ref::<RNodeString>().unwrap().get_value(), right.downcast_ref::<RNodeString>().unwrap().get_value()); },
+            _ => {},
+        }
+    }
+
+    #[test]
+    fn tree_builder_matches_parser()
+    {
+        let input = String::from("{\"b\": [1, 2.5, {\"c\": null}, [], {}], \"a\": true, \"s\": \"x\", \"b\": \"last\"}");
+
+        let mut builder = TreeBuilder::new();
+        assert!(JsonEvents::new_copy(&input, &ParserOptions::default()).drive(&mut builder).unwrap());
+        let built = builder.take_root().unwrap();
+
+        let parsed = Parser::new_copy(&input, &ParserOptions::default()).parse().unwrap();
+        assert_same_tree(&built, &parsed);
+        assert!(builder.take_root().is_none());
+    }
+}
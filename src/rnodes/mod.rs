@@ -1,8 +1,12 @@
 pub mod rnode;
 pub mod rnode_array;
 pub mod rnode_bool;
+#[cfg(feature = "arbitrary_precision")]
+pub mod rnode_decimal;
 pub mod rnode_double;
+pub mod rnode_error;
 pub mod rnode_null;
+pub mod rnode_number;
 pub mod rnode_object;
 pub mod rnode_string;
 pub mod rnode_factory;
@@ -3,17 +3,41 @@ use downcast_rs::Downcast;
 use crate::visitor::visitor::Visitor;
 
 use std::fmt::{self, Display};
+use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
 pub enum EnumNodeType
 {
-    ARRAY = 0, BOOL, DOUBLE, NULL, OBJECT, STRING
+    ARRAY = 0, BOOL,
+    #[cfg(feature = "arbitrary_precision")]
+    DECIMAL,
+    DOUBLE, ERROR, NULL, NUMBER, OBJECT, STRING
 }
 
 pub trait RNode : Downcast
 {
     fn get_node_type(&self) -> EnumNodeType;
     fn accept(&self, visitor: &dyn Visitor);
+
+    /// Moves out the nodes held by this one (ex. the elements of an array). See 'drop_nodes'.
+    fn take_children(&mut self) -> Vec<Rc<dyn RNode>>
+    {
+        return Vec::new();
+    }
+}
+
+/// Drops the nodes along with everything nested in them using a loop rather than recursion, so that
+/// deeply nested documents don't overflow the stack. Arrays and objects call this when dropped.
+pub fn drop_nodes(mut nodes: Vec<Rc<dyn RNode>>)
+{
+    while let Some(mut node) = nodes.pop()
+    {
+        // Only the last reference to a node owns its children.
+        if let Some(node) = Rc::get_mut(&mut node)
+        {
+            nodes.append(&mut node.take_children());
+        }
+    }
 }
 
 impl Display for EnumNodeType
@@ -24,8 +48,12 @@ impl Display for EnumNodeType
         {
             EnumNodeType::ARRAY => write!(f, "ARRAY"),
             EnumNodeType::BOOL => write!(f, "BOOL"),
+            #[cfg(feature = "arbitrary_precision")]
+            EnumNodeType::DECIMAL => write!(f, "DECIMAL"),
             EnumNodeType::DOUBLE => write!(f, "DOUBLE"),
+            EnumNodeType::ERROR => write!(f, "ERROR"),
             EnumNodeType::NULL => write!(f, "NULL"),
+            EnumNodeType::NUMBER => write!(f, "NUMBER"),
             EnumNodeType::OBJECT => write!(f, "OBJECT"),
             EnumNodeType::STRING => write!(f, "STRING"),
         }
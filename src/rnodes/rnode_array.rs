@@ -1,4 +1,4 @@
-use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::rnodes::rnode::{drop_nodes, EnumNodeType, RNode};
 use crate::visitor::visitor::Visitor;
 
 use std::ops::Deref;
@@ -16,19 +16,16 @@ impl RNodeArray
         Self { arr }
     }
 
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool
     {
         return self.arr.is_empty();
     }
 
-    #[allow(dead_code)]
     pub fn len(&self) -> usize
     {
         return self.arr.len();
     }
 
-    #[allow(dead_code)]
     pub fn get(&self, index: usize) -> Option<Rc<dyn RNode>>
     {
         if index < self.arr.len()
@@ -51,6 +48,19 @@ impl RNode for RNodeArray
     {
         visitor.visit_array(self);
     }
+
+    fn take_children(&mut self) -> Vec<Rc<dyn RNode>>
+    {
+        return std::mem::take(&mut self.arr);
+    }
+}
+
+impl Drop for RNodeArray
+{
+    fn drop(&mut self)
+    {
+        drop_nodes(self.take_children());
+    }
 }
 
 impl Deref for RNodeArray
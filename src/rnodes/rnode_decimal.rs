@@ -0,0 +1,113 @@
+use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::utils::decimal::Decimal;
+use crate::visitor::visitor::Visitor;
+
+use std::cmp::Ordering;
+
+/// A number kept as an exact decimal along with its original lexeme.
+pub struct RNodeDecimal
+{
+    value: Decimal,
+    lexeme: String,
+}
+
+impl RNodeDecimal
+{
+    /// Creates a decimal from a JSON number (ex. "12345678901234567890.123456789").
+    /// Returns None if it isn't a number or its exponent doesn't fit in an i64.
+    pub fn from_lexeme(lexeme: String) -> Option<Self>
+    {
+        let value = Decimal::parse(&lexeme)?;
+        return Some(Self { value, lexeme });
+    }
+
+    pub fn new(value: Decimal) -> Self
+    {
+        let lexeme = value.to_string();
+        Self { value, lexeme }
+    }
+
+    pub fn get_value(&self) -> &Decimal
+    {
+        return &self.value;
+    }
+
+    /// The number exactly as it was written in the input.
+    pub fn get_lexeme(&self) -> &String
+    {
+        return &self.lexeme;
+    }
+}
+
+impl PartialEq for RNodeDecimal
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        return self.value == other.value;
+    }
+}
+
+impl Eq for RNodeDecimal {}
+
+impl PartialOrd for RNodeDecimal
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering>
+    {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for RNodeDecimal
+{
+    fn cmp(&self, other: &Self) -> Ordering
+    {
+        return self.value.cmp(&other.value);
+    }
+}
+
+impl RNode for RNodeDecimal
+{
+    fn get_node_type(&self) -> EnumNodeType
+    {
+        return EnumNodeType::DECIMAL;
+    }
+
+    fn accept(&self, visitor: &dyn Visitor)
+    {
+        visitor.visit_decimal(self);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::rnodes::rnode::{EnumNodeType, RNode};
+    use crate::rnodes::rnode_decimal::RNodeDecimal;
+    use crate::utils::decimal::Decimal;
+
+    #[test]
+    fn create_decimal()
+    {
+        let node = RNodeDecimal::from_lexeme(String::from("12345678901234567890.1234567890")).unwrap();
+        assert_eq!(node.get_node_type(), EnumNodeType::DECIMAL);
+        assert_eq!(node.get_lexeme(), "12345678901234567890.1234567890");
+        assert_eq!(node.get_value(), &Decimal::parse("12345678901234567890.123456789").unwrap());
+
+        let node = RNodeDecimal::new(Decimal::parse("1.50").unwrap());
+        assert_eq!(node.get_lexeme(), "1.5");
+
+        assert!(RNodeDecimal::from_lexeme(String::from("1e99999999999999999999")).is_none());
+    }
+
+    #[test]
+    fn compare_decimal_nodes()
+    {
+        let node0 = RNodeDecimal::from_lexeme(String::from("0.10")).unwrap();
+        let node1 = RNodeDecimal::from_lexeme(String::from("1e-1")).unwrap();
+        let node2 = RNodeDecimal::from_lexeme(String::from("0.1000000000000000000001")).unwrap();
+
+        assert!(node0 == node1);
+        assert!(node0 != node2);
+        assert!(node2 > node1);
+    }
+}
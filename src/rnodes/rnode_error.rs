@@ -0,0 +1,35 @@
+use crate::parser::parse_error::ParseError;
+use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::visitor::visitor::Visitor;
+
+/// Placeholder for a value that failed to parse when the parser is recovering from errors.
+pub struct RNodeError
+{
+    error: ParseError,
+}
+
+impl RNodeError
+{
+    pub fn new(error: ParseError) -> Self
+    {
+        Self { error }
+    }
+
+    pub fn get_error(&self) -> &ParseError
+    {
+        return &self.error;
+    }
+}
+
+impl RNode for RNodeError
+{
+    fn get_node_type(&self) -> EnumNodeType
+    {
+        return EnumNodeType::ERROR;
+    }
+
+    fn accept(&self, visitor: &dyn Visitor)
+    {
+        visitor.visit_error(self);
+    }
+}
@@ -3,10 +3,11 @@ use crate::rnodes::rnode_array::RNodeArray;
 use crate::rnodes::rnode_bool::RNodeBool;
 use crate::rnodes::rnode_double::RNodeDouble;
 use crate::rnodes::rnode_null::RNodeNull;
+use crate::rnodes::rnode_number::RNodeNumber;
 use crate::rnodes::rnode_object::RNodeObject;
 use crate::rnodes::rnode_string::RNodeString;
+use crate::utils::ordered_map::OrderedMap;
 
-use std::collections::BTreeMap;
 use std::rc::Rc;
 
 #[allow(unused)]
@@ -18,7 +19,8 @@ pub fn create_node(node_type: EnumNodeType) -> Result<Rc<dyn RNode>, String>
         EnumNodeType::BOOL => { return Ok(Rc::new(RNodeBool::new(false))); },
         EnumNodeType::DOUBLE => { return Ok(Rc::new(RNodeDouble::new(0.0))); },
         EnumNodeType::NULL => { return Ok(Rc::new(RNodeNull::new())); },
-        EnumNodeType::OBJECT => { return Ok(Rc::new(RNodeObject::new(BTreeMap::<String, Rc<dyn RNode>>::new()))); },
+        EnumNodeType::NUMBER => { return Ok(Rc::new(RNodeNumber::new_u64(0))); },
+        EnumNodeType::OBJECT => { return Ok(Rc::new(RNodeObject::new(OrderedMap::<Rc<dyn RNode>>::new()))); },
         EnumNodeType::STRING => { return Ok(Rc::new(RNodeString::new_move(String::new()))); },
         _ => { return Err(String::from("Could not find EnumNodeType")); }
     }
@@ -11,6 +11,14 @@ impl RNodeNull
     }
 }
 
+impl Default for RNodeNull
+{
+    fn default() -> Self
+    {
+        return Self::new();
+    }
+}
+
 impl RNode for RNodeNull
 {
     fn get_node_type(&self) -> EnumNodeType
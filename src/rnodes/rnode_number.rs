@@ -0,0 +1,325 @@
+use crate::io::non_finite_policy::get_non_finite_name;
+use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::utils::float_utils::format_f64;
+use crate::visitor::visitor::Visitor;
+
+use std::fmt::{self, Display};
+
+/// The value of a number, keeping integers exact when they fit in 64-bits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnumNumber
+{
+    /// A negative integer.
+    I64(i64),
+    /// A non-negative integer.
+    U64(u64),
+    /// Any number with a fraction or exponent, or an integer that doesn't fit in 64-bits.
+    F64(f64),
+}
+
+/// Why a number couldn't be converted to the requested type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EnumNumberError
+{
+    /// The value is outside of the range of the requested type.
+    OutOfRange,
+    /// The value has a fractional part, so it isn't an integer.
+    NotAnInteger,
+    /// The integer can't be represented exactly by an f64.
+    PrecisionLoss,
+}
+
+impl Display for EnumNumberError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            EnumNumberError::OutOfRange => write!(f, "number is out of range"),
+            EnumNumberError::NotAnInteger => write!(f, "number is not an integer"),
+            EnumNumberError::PrecisionLoss => write!(f, "number can't be represented exactly"),
+        }
+    }
+}
+
+impl std::error::Error for EnumNumberError {}
+
+/// A number that keeps its original lexeme so it can be written back out losslessly.
+pub struct RNodeNumber
+{
+    value: EnumNumber,
+    lexeme: String,
+}
+
+impl RNodeNumber
+{
+    /// Creates a number from its lexeme. 'fallback' is used when the lexeme is not an integer
+    /// that fits in 64-bits (ex. "1.5", "1e3" or "18446744073709551616").
+    pub fn from_lexeme(lexeme: String, fallback: f64) -> Self
+    {
+        let is_integer = !lexeme.contains(['.', 'e', 'E']);
+        let mut value = EnumNumber::F64(fallback);
+
+        if is_integer
+        {
+            if lexeme.starts_with('-')
+            {
+                if let Ok(num) = lexeme.parse::<i64>()
+                {
+                    // "-0" has no sign as an integer, so keep it non-negative.
+                    value = if num == 0 { EnumNumber::U64(0) } else { EnumNumber::I64(num) };
+                }
+            }
+
+            else if let Ok(num) = lexeme.parse::<u64>()
+            {
+                value = EnumNumber::U64(num);
+            }
+        }
+
+        Self { value, lexeme }
+    }
+
+    pub fn new_i64(value: i64) -> Self
+    {
+        if value >= 0
+        {
+            return Self::new_u64(value as u64);
+        }
+
+        Self { value: EnumNumber::I64(value), lexeme: value.to_string() }
+    }
+
+    pub fn new_u64(value: u64) -> Self
+    {
+        Self { value: EnumNumber::U64(value), lexeme: value.to_string() }
+    }
+
+    /// Creates a number from an f64, written in its shortest form that reads back as the same value.
+    /// Non-finite values have no JSON form, so they get a lexeme of "NaN", "Infinity" or "-Infinity".
+    pub fn new_f64(value: f64) -> Self
+    {
+        let lexeme = if value.is_finite() { format_f64(value) } else { String::from(get_non_finite_name(value)) };
+        Self { value: EnumNumber::F64(value), lexeme }
+    }
+
+    pub fn get_value(&self) -> EnumNumber
+    {
+        return self.value;
+    }
+
+    /// The number exactly as it was written in the input.
+    pub fn get_lexeme(&self) -> &String
+    {
+        return &self.lexeme;
+    }
+
+    pub fn is_integer(&self) -> bool
+    {
+        return !matches!(self.value, EnumNumber::F64(_));
+    }
+
+    pub fn as_i64(&self) -> Result<i64, EnumNumberError>
+    {
+        match self.value
+        {
+            EnumNumber::I64(num) => { return Ok(num); },
+            EnumNumber::U64(num) => { return i64::try_from(num).map_err(|_| EnumNumberError::OutOfRange); },
+            EnumNumber::F64(num) =>
+            {
+                check_integral(num)?;
+
+                // i64::MAX is not exact as an f64 so compare against 2^63 instead.
+                if !(-9223372036854775808.0..9223372036854775808.0).contains(&num)
+                {
+                    return Err(EnumNumberError::OutOfRange);
+                }
+
+                return Ok(num as i64);
+            },
+        }
+    }
+
+    pub fn as_u64(&self) -> Result<u64, EnumNumberError>
+    {
+        match self.value
+        {
+            EnumNumber::I64(_) => { return Err(EnumNumberError::OutOfRange); },
+            EnumNumber::U64(num) => { return Ok(num); },
+            EnumNumber::F64(num) =>
+            {
+                check_integral(num)?;
+
+                // u64::MAX is not exact as an f64 so compare against 2^64 instead.
+                if !(0.0..18446744073709551616.0).contains(&num)
+                {
+                    return Err(EnumNumberError::OutOfRange);
+                }
+
+                return Ok(num as u64);
+            },
+        }
+    }
+
+    /// Gets the value as an f64, reporting integers that would be rounded and
+    /// numbers too large to be finite.
+    pub fn as_f64(&self) -> Result<f64, EnumNumberError>
+    {
+        match self.value
+        {
+            EnumNumber::I64(num) =>
+            {
+                let result = num as f64;
+
+                if !(-9223372036854775808.0..9223372036854775808.0).contains(&result) || result as i64 != num
+                {
+                    return Err(EnumNumberError::PrecisionLoss);
+                }
+
+                return Ok(result);
+            },
+            EnumNumber::U64(num) =>
+            {
+                let result = num as f64;
+
+                if result >= 18446744073709551616.0 || result as u64 != num
+                {
+                    return Err(EnumNumberError::PrecisionLoss);
+                }
+
+                return Ok(result);
+            },
+            EnumNumber::F64(num) if !num.is_finite() => { return Err(EnumNumberError::OutOfRange); },
+            EnumNumber::F64(num) => { return Ok(num); },
+        }
+    }
+
+    /// Gets the closest f64 to the value (without any error checking).
+    pub fn to_f64_lossy(&self) -> f64
+    {
+        match self.value
+        {
+            EnumNumber::I64(num) => { return num as f64; },
+            EnumNumber::U64(num) => { return num as f64; },
+            EnumNumber::F64(num) => { return num; },
+        }
+    }
+}
+
+fn check_integral(num: f64) -> Result<(), EnumNumberError>
+{
+    if !num.is_finite()
+    {
+        return Err(EnumNumberError::OutOfRange);
+    }
+
+    else if num.fract() != 0.0
+    {
+        return Err(EnumNumberError::NotAnInteger);
+    }
+
+    return Ok(());
+}
+
+impl RNode for RNodeNumber
+{
+    fn get_node_type(&self) -> EnumNodeType
+    {
+        return EnumNodeType::NUMBER;
+    }
+
+    fn accept(&self, visitor: &dyn Visitor)
+    {
+        visitor.visit_number(self);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::rnodes::rnode::{EnumNodeType, RNode};
+    use crate::rnodes::rnode_number::{EnumNumber, EnumNumberError, RNodeNumber};
+
+    fn from_lexeme(lexeme: &str) -> RNodeNumber
+    {
+        let fallback = lexeme.parse::<f64>().unwrap();
+        return RNodeNumber::from_lexeme(String::from(lexeme), fallback);
+    }
+
+    #[test]
+    fn create_number_from_lexeme()
+    {
+        let node = from_lexeme("9007199254740993");
+        assert_eq!(node.get_node_type(), EnumNodeType::NUMBER);
+        assert_eq!(node.get_value(), EnumNumber::U64(9007199254740993));
+        assert_eq!(node.get_lexeme(), "9007199254740993");
+        assert!(node.is_integer());
+
+        assert_eq!(from_lexeme("-42").get_value(), EnumNumber::I64(-42));
+        assert_eq!(from_lexeme("-0").get_value(), EnumNumber::U64(0));
+        assert_eq!(from_lexeme("1.5").get_value(), EnumNumber::F64(1.5));
+        assert_eq!(from_lexeme("1e3").get_value(), EnumNumber::F64(1000.0));
+        assert_eq!(from_lexeme("-0.0").get_lexeme(), "-0.0");
+    }
+
+    #[test]
+    fn create_number_integer_limits()
+    {
+        assert_eq!(from_lexeme("18446744073709551615").get_value(), EnumNumber::U64(u64::MAX));
+        assert_eq!(from_lexeme("-9223372036854775808").get_value(), EnumNumber::I64(i64::MIN));
+
+        // Too large for any integer type so fall back to an f64 (but keep the exact lexeme).
+        let node = from_lexeme("18446744073709551616");
+        assert_eq!(node.get_value(), EnumNumber::F64(18446744073709551616.0));
+        assert_eq!(node.get_lexeme(), "18446744073709551616");
+        assert!(!node.is_integer());
+
+        assert_eq!(from_lexeme("-9223372036854775809").get_value(), EnumNumber::F64(-9223372036854775809.0));
+    }
+
+    #[test]
+    fn number_as_i64()
+    {
+        assert_eq!(from_lexeme("-9223372036854775808").as_i64(), Ok(i64::MIN));
+        assert_eq!(from_lexeme("9223372036854775807").as_i64(), Ok(i64::MAX));
+        assert_eq!(from_lexeme("9223372036854775808").as_i64(), Err(EnumNumberError::OutOfRange));
+        assert_eq!(from_lexeme("1e3").as_i64(), Ok(1000));
+        assert_eq!(from_lexeme("1.5").as_i64(), Err(EnumNumberError::NotAnInteger));
+        assert_eq!(from_lexeme("1e19").as_i64(), Err(EnumNumberError::OutOfRange));
+        assert_eq!(from_lexeme("1e400").as_i64(), Err(EnumNumberError::OutOfRange));
+    }
+
+    #[test]
+    fn number_as_u64()
+    {
+        assert_eq!(from_lexeme("18446744073709551615").as_u64(), Ok(u64::MAX));
+        assert_eq!(from_lexeme("0").as_u64(), Ok(0));
+        assert_eq!(from_lexeme("-1").as_u64(), Err(EnumNumberError::OutOfRange));
+        assert_eq!(from_lexeme("18446744073709551616").as_u64(), Err(EnumNumberError::OutOfRange));
+        assert_eq!(from_lexeme("2.0").as_u64(), Ok(2));
+        assert_eq!(from_lexeme("-2.5").as_u64(), Err(EnumNumberError::NotAnInteger));
+    }
+
+    #[test]
+    fn number_as_f64()
+    {
+        assert_eq!(from_lexeme("9007199254740992").as_f64(), Ok(9007199254740992.0));
+        assert_eq!(from_lexeme("9007199254740993").as_f64(), Err(EnumNumberError::PrecisionLoss));
+        assert_eq!(from_lexeme("-9007199254740993").as_f64(), Err(EnumNumberError::PrecisionLoss));
+        assert_eq!(from_lexeme("18446744073709551615").as_f64(), Err(EnumNumberError::PrecisionLoss));
+        assert_eq!(from_lexeme("-123.45").as_f64(), Ok(-123.45));
+        assert_eq!(from_lexeme("1e400").as_f64(), Err(EnumNumberError::OutOfRange));
+        assert_eq!(from_lexeme("9007199254740993").to_f64_lossy(), 9007199254740992.0);
+    }
+
+    #[test]
+    fn create_number_from_values()
+    {
+        assert_eq!(RNodeNumber::new_i64(-7).get_lexeme(), "-7");
+        assert_eq!(RNodeNumber::new_i64(7).get_value(), EnumNumber::U64(7));
+        assert_eq!(RNodeNumber::new_u64(u64::MAX).get_lexeme(), "18446744073709551615");
+        assert_eq!(RNodeNumber::new_f64(1.0).get_lexeme(), "1");
+        assert_eq!(RNodeNumber::new_f64(f64::NEG_INFINITY).get_lexeme(), "-Infinity");
+        assert_eq!(RNodeNumber::new_f64(1e300).get_lexeme(), "1e300");
+    }
+}
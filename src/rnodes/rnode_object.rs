@@ -1,73 +1,94 @@
-use crate::rnodes::rnode::{EnumNodeType, RNode};
+use crate::rnodes::rnode::{drop_nodes, EnumNodeType, RNode};
+use crate::utils::ordered_map::OrderedMap;
 use crate::visitor::visitor::Visitor;
 
-use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::rc::Rc;
 
+/// An object whose keys keep the order they were inserted (ie. the order they were read in).
 pub struct RNodeObject
 {
-    map: BTreeMap<String, Rc<dyn RNode>>,
+    map: OrderedMap<Rc<dyn RNode>>,
+    /// Every value of each key that appeared more than once (only kept with EnumDuplicateKeyPolicy::KEEP_ALL).
+    duplicates: OrderedMap<Vec<Rc<dyn RNode>>>,
 }
 
 impl RNodeObject
 {
-    pub fn new(map: BTreeMap<String, Rc<dyn RNode>>) -> Self
+    pub fn new(map: OrderedMap<Rc<dyn RNode>>) -> Self
     {
-        Self { map }
+        Self { map, duplicates: OrderedMap::new() }
+    }
+
+    /// Creates an object that also keeps every value of its duplicate keys (see 'get_all').
+    pub fn new_multi(map: OrderedMap<Rc<dyn RNode>>, duplicates: OrderedMap<Vec<Rc<dyn RNode>>>) -> Self
+    {
+        Self { map, duplicates }
     }
 
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool
     {
         return self.map.is_empty();
     }
 
-    #[allow(dead_code)]
     pub fn len(&self) -> usize
     {
         return self.map.len();
     }
 
-    #[allow(dead_code)]
-    pub fn add_copy(mut self, key: &String, value: Rc<dyn RNode>) -> Self
+    pub fn add_copy(mut self, key: &str, value: Rc<dyn RNode>) -> Self
     {
-        self.map.insert(key.clone(), value);
+        self.map.insert(String::from(key), value);
         self
     }
 
-    #[allow(dead_code)]
     pub fn add_move(mut self, key: String, value: Rc<dyn RNode>) -> Self
     {
         self.map.insert(key, value);
         self
     }
 
-    #[allow(dead_code)]
-    pub fn get(&self, key: &String) -> Option<Rc<dyn RNode>>
+    pub fn get(&self, key: &str) -> Option<Rc<dyn RNode>>
     {
         let opt_value = self.map.get(key);
 
         match opt_value
         {
-            Some(value) => { return Some(Rc::clone(&value)); },
+            Some(value) => { return Some(Rc::clone(value)); },
             None => { return None; },
         }
     }
 
-    #[allow(dead_code)]
-    pub fn get_mut(&mut self, key: &String) -> Option<Rc<dyn RNode>>
+    pub fn get_mut(&mut self, key: &str) -> Option<Rc<dyn RNode>>
     {
         let opt_value = self.map.get_mut(key);
 
         match opt_value
         {
-            Some(value) => { return Some(Rc::clone(&value)); },
+            Some(value) => { return Some(Rc::clone(value)); },
             None => { return None; },
         }
     }
 
-    pub fn get_map(&self) -> &BTreeMap<String, Rc<dyn RNode>>
+    /// Gets every value of the key in the order they were read. This is more than one value only
+    /// for a duplicate key kept by EnumDuplicateKeyPolicy::KEEP_ALL.
+    pub fn get_all(&self, key: &str) -> Vec<Rc<dyn RNode>>
+    {
+        if let Some(values) = self.duplicates.get(key)
+        {
+            return values.clone();
+        }
+
+        return self.get(key).into_iter().collect();
+    }
+
+    /// Checks if any key has more than one value (see 'get_all').
+    pub fn has_duplicates(&self) -> bool
+    {
+        return !self.duplicates.is_empty();
+    }
+
+    pub fn get_map(&self) -> &OrderedMap<Rc<dyn RNode>>
     {
         return &self.map;
     }
@@ -84,11 +105,27 @@ impl RNode for RNodeObject
     {
         visitor.visit_object(self);
     }
+
+    fn take_children(&mut self) -> Vec<Rc<dyn RNode>>
+    {
+        let mut children: Vec<Rc<dyn RNode>> = std::mem::take(&mut self.map).into_values().collect();
+        children.extend(std::mem::take(&mut self.duplicates).into_values().flatten());
+
+        return children;
+    }
+}
+
+impl Drop for RNodeObject
+{
+    fn drop(&mut self)
+    {
+        drop_nodes(self.take_children());
+    }
 }
 
 impl Deref for RNodeObject
 {
-    type Target = BTreeMap<String, Rc<dyn RNode>>;
+    type Target = OrderedMap<Rc<dyn RNode>>;
 
     fn deref(&self) -> &Self::Target
     {
@@ -102,7 +139,7 @@ mod tests
     use super::RNodeObject;
     use crate::rnodes::rnode::{EnumNodeType, RNode};
     use crate::rnodes::rnode_double::RNodeDouble;
-    use std::collections::BTreeMap;
+    use crate::utils::ordered_map::OrderedMap;
     use std::rc::Rc;
 
     #[test]
@@ -113,7 +150,7 @@ mod tests
         let val0 = Rc::new(RNodeDouble::new(123.45));
         let val1 = Rc::new(RNodeDouble::new(-123.45));
 
-        let mut obj = RNodeObject::new(BTreeMap::<String, Rc<dyn RNode>>::new());
+        let mut obj = RNodeObject::new(OrderedMap::<Rc<dyn RNode>>::new());
         obj = obj.add_copy(&key0, val0.clone()).add_copy(&key1, val1.clone());
         assert_eq!(obj.get_node_type(), EnumNodeType::OBJECT);
 
@@ -4,38 +4,91 @@ use crate::visitor::visitor::Visitor;
 pub struct RNodeString
 {
     value: String,
+    /// The lone surrogates the string holds, as (byte offset of their U+FFFD placeholder, code unit).
+    lone_surrogates: Vec<(usize, u16)>,
+    /// The byte offsets of the '\uXXXX' escapes the string holds as text (ie. when it wasn't decoded).
+    unicode_escapes: Vec<usize>,
 }
 
 impl RNodeString
 {
-    #[allow(dead_code)]
-    pub fn new_copy(value: &String) -> Self
+    pub fn new_copy(value: &str) -> Self
     {
-        Self { value: value.clone() }
+        Self { value: String::from(value), lone_surrogates: Vec::new(), unicode_escapes: Vec::new() }
     }
 
-    #[allow(dead_code)]
     pub fn new_move(value: String) -> Self
     {
-        Self { value }
+        Self { value, lone_surrogates: Vec::new(), unicode_escapes: Vec::new() }
+    }
+
+    /// Creates a string holding lone surrogates, each given as the byte offset of the U+FFFD standing
+    /// in for it in 'value' and its UTF-16 code unit.
+    pub fn new_with_lone_surrogates(value: String, lone_surrogates: Vec<(usize, u16)>) -> Self
+    {
+        debug_assert!(lone_surrogates.iter().all(|(offset, _)| value[*offset..].starts_with(char::REPLACEMENT_CHARACTER)));
+        Self { value, lone_surrogates, unicode_escapes: Vec::new() }
+    }
+
+    /// Creates a string that keeps '\uXXXX' escapes as text, each given as the byte offset of its '\'
+    /// in 'value'. Any other '\' is just a backslash.
+    pub fn new_with_unicode_escapes(value: String, unicode_escapes: Vec<usize>) -> Self
+    {
+        debug_assert!(unicode_escapes.iter().all(|offset| value.get(*offset..*offset + 6).is_some_and(is_unicode_escape)));
+        Self { value, lone_surrogates: Vec::new(), unicode_escapes }
     }
 
-    #[allow(dead_code)]
     pub fn get_value(&self) -> &String
     {
         return &self.value;
     }
 
-    #[allow(dead_code)]
-    pub fn set_value_copy(&mut self, value: &String)
+    pub fn get_lone_surrogates(&self) -> &[(usize, u16)]
     {
-        self.value = value.clone();
+        return &self.lone_surrogates;
+    }
+
+    pub fn get_unicode_escapes(&self) -> &[usize]
+    {
+        return &self.unicode_escapes;
+    }
+
+    /// Gets the lone surrogate whose placeholder is at the given byte offset (if any).
+    pub fn get_lone_surrogate_at(&self, offset: usize) -> Option<u16>
+    {
+        return self.lone_surrogates.binary_search_by_key(&offset, |(start, _)| *start).ok().map(|index| self.lone_surrogates[index].1);
+    }
+
+    /// Encodes the string as UTF-16, with its lone surrogates in place of their placeholders.
+    pub fn to_utf16(&self) -> Vec<u16>
+    {
+        let mut result = Vec::with_capacity(self.value.len());
+        let mut units = [0u16; 2];
+
+        for (offset, ch) in self.value.char_indices()
+        {
+            match self.get_lone_surrogate_at(offset)
+            {
+                Some(code_unit) => { result.push(code_unit); },
+                None => { result.extend_from_slice(ch.encode_utf16(&mut units)); },
+            }
+        }
+
+        return result;
+    }
+
+    pub fn set_value_copy(&mut self, value: &str)
+    {
+        self.value = String::from(value);
+        self.lone_surrogates.clear();
+        self.unicode_escapes.clear();
     }
 
-    #[allow(dead_code)]
     pub fn set_value_move(&mut self, value: String)
     {
         self.value = value;
+        self.lone_surrogates.clear();
+        self.unicode_escapes.clear();
     }
 }
 
@@ -52,3 +105,29 @@ impl RNode for RNodeString
     }
 }
 
+fn is_unicode_escape(text: &str) -> bool
+{
+    let bytes = text.as_bytes();
+    return bytes.starts_with(b"\\u") && bytes[2..].iter().all(|byte| byte.is_ascii_hexdigit());
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::rnodes::rnode_string::RNodeString;
+
+    #[test]
+    fn rnode_string_lone_surrogates()
+    {
+        let node = RNodeString::new_with_lone_surrogates(String::from("a\u{FFFD}\u{FFFD}b"), vec![ (1, 0xD83D), (4, 0xDC00) ]);
+        assert_eq!(node.get_value(), "a\u{FFFD}\u{FFFD}b");
+        assert_eq!(node.get_lone_surrogate_at(1), Some(0xD83D));
+        assert_eq!(node.get_lone_surrogate_at(4), Some(0xDC00));
+        assert_eq!(node.get_lone_surrogate_at(0), None);
+        assert_eq!(node.to_utf16(), vec![ 0x61, 0xD83D, 0xDC00, 0x62 ]);
+
+        // A real U+FFFD stays as is.
+        let node = RNodeString::new_move(String::from("\u{FFFD}"));
+        assert_eq!(node.to_utf16(), vec![ 0xFFFD ]);
+    }
+}
@@ -1,18 +1,40 @@
+use crate::io::input_format::{EnumInputFormat, get_input_format_from_string};
+use crate::io::output_format::{EnumOutputFormat, get_output_format_from_string};
 use crate::log::logger::{EnumLogLevel, get_log_level_from_string, get_std_logger, ILogger};
+use crate::parser::duplicate_key_policy::{EnumDuplicateKeyPolicy, get_duplicate_key_policy_from_string};
+use crate::parser::surrogate_policy::{EnumSurrogatePolicy, get_surrogate_policy_from_string};
 use crate::utils::string_utils::StringBuilder;
 
 pub struct CLIArgs
 {
+    #[cfg(feature = "arbitrary_precision")]
+    pub decimal_numbers: bool,
+    pub duplicate_key_policy: EnumDuplicateKeyPolicy,
+    pub exact_numbers: bool,
+    pub extract: bool,
     pub input_file: Option<String>,
+    pub input_format: EnumInputFormat,
+    pub json5: bool,
+    pub lenient_numbers: bool,
     pub log_level: EnumLogLevel,
+    pub max_depth: Option<usize>,
+    pub max_elements: Option<usize>,
+    pub max_input_size: Option<usize>,
+    pub max_members: Option<usize>,
+    pub max_string_length: Option<usize>,
+    pub output_format: Option<EnumOutputFormat>,
+    pub recover: bool,
+    pub skip_bad_lines: bool,
+    pub stream: bool,
     pub stringify: bool,
+    pub surrogate_policy: EnumSurrogatePolicy,
 }
 
 impl CLIArgs
 {
     pub fn new() -> Self
     {
-        Self { input_file: None, log_level: EnumLogLevel::WARN, stringify: false }
+        Self { #[cfg(feature = "arbitrary_precision")] decimal_numbers: false, duplicate_key_policy: EnumDuplicateKeyPolicy::default(), exact_numbers: false, extract: false, input_file: None, input_format: EnumInputFormat::default(), json5: false, lenient_numbers: false, log_level: EnumLogLevel::WARN, max_depth: None, max_elements: None, max_input_size: None, max_members: None, max_string_length: None, output_format: None, recover: false, skip_bad_lines: false, stream: false, stringify: false, surrogate_policy: EnumSurrogatePolicy::default() }
     }
 
     fn get_usage(&self) -> String
@@ -25,9 +47,27 @@ impl CLIArgs
         builder.append_char('\n');
 
         // Flags
+        #[cfg(feature = "arbitrary_precision")]
+        builder.append_str("--decimal-numbers                 Keeps the exact value of every number however large or precise (over '--exact-numbers').\n");
+        builder.append_str("--duplicate-keys <policy>         How repeated object keys are handled (error, first-wins, last-wins or keep-all).\n");
+        builder.append_str("-e,--exact-numbers                Keeps 64-bit integers exact along with the original text of every number.\n");
+        builder.append_str("--extract                         Writes the JSON objects and arrays found in the input text (ex. a log), as ndjson by default.\n");
         builder.append_str("-h,--help                         Prints this help menu.\n");
         builder.append_str("-i,--input <FILE>                 Specifies to read from a file rather than std input.\n");
+        builder.append_str("--input-format <format>           How the input is split into documents (json, ndjson or json-seq, defaults to json).\n");
+        builder.append_str("--json5                           Accepts JSON5 (comments, trailing commas, single-quoted strings, unquoted keys, etc).\n");
+        builder.append_str("--lenient-numbers                 Accepts numbers JSON doesn't allow (ex. '.5', '5.' and '007').\n");
         builder.append_str("-l,--log-level <log-level>        Sets the log level.\n");
+        builder.append_str("--max-depth <N>                   Rejects arrays/objects nested more than N deep (no limit by default, so any depth parses).\n");
+        builder.append_str("--max-elements <N>                Rejects arrays with more than N elements.\n");
+        builder.append_str("--max-input-size <N>              Rejects input larger than N bytes.\n");
+        builder.append_str("--max-members <N>                 Rejects objects with more than N members.\n");
+        builder.append_str("--max-string-length <N>           Rejects strings, keys and numbers longer than N bytes.\n");
+        builder.append_str("-o,--output-format <format>       Writes the documents read to std output (json, ndjson or json-seq).\n");
+        builder.append_str("-r,--recover                      Reports every error in the input instead of stopping at the first one.\n");
+        builder.append_str("--skip-bad-lines                  With '--input-format ndjson' or 'json-seq', reports lines/records that fail to parse as warnings and carries on.\n");
+        builder.append_str("--stream                          Accepts a stream of concatenated JSON values instead of a single root value.\n");
+        builder.append_str("--surrogates <policy>             How lone UTF-16 surrogate escapes are handled (error, replace or preserve).\n");
 
         builder.to_string()
     }
@@ -53,6 +93,40 @@ impl CLIArgs
                 return Some((0, msg));
             }
 
+            else if arg == "--duplicate-keys"
+            {
+                let opt_next_arg = args.get(i + 1);
+
+                if opt_next_arg.is_none()
+                {
+                    return Some((-1, String::from("Expected a policy after the argument '--duplicate-keys'")));
+                }
+
+                match get_duplicate_key_policy_from_string(opt_next_arg.unwrap())
+                {
+                    Ok(policy) => { self.duplicate_key_policy = policy; },
+                    Err(msg) => { return Some((-1, msg)); },
+                }
+
+                skip_next = true;
+            }
+
+            else if cfg!(feature = "arbitrary_precision") && arg == "--decimal-numbers"
+            {
+                #[cfg(feature = "arbitrary_precision")]
+                {
+                    self.decimal_numbers = true;
+                }
+
+                skip_next = false;
+            }
+
+            else if arg == "-e" || arg == "--exact-numbers"
+            {
+                self.exact_numbers = true;
+                skip_next = false;
+            }
+
             else if arg == "-i" || arg == "--input"
             {
                 let opt_next_arg = args.get(i + 1);
@@ -66,6 +140,42 @@ impl CLIArgs
                 skip_next = true;
             }
 
+            else if arg == "--extract"
+            {
+                self.extract = true;
+                skip_next = false;
+            }
+
+            else if arg == "--input-format"
+            {
+                let opt_next_arg = args.get(i + 1);
+
+                if opt_next_arg.is_none()
+                {
+                    return Some((-1, String::from("Expected a format after the argument '--input-format'")));
+                }
+
+                match get_input_format_from_string(opt_next_arg.unwrap())
+                {
+                    Ok(input_format) => { self.input_format = input_format; },
+                    Err(msg) => { return Some((-1, msg)); },
+                }
+
+                skip_next = true;
+            }
+
+            else if arg == "--json5"
+            {
+                self.json5 = true;
+                skip_next = false;
+            }
+
+            else if arg == "--lenient-numbers"
+            {
+                self.lenient_numbers = true;
+                skip_next = false;
+            }
+
             else if arg == "-l" || arg == "--log-level"
             {
                 let opt_next_arg = args.get(i + 1);
@@ -95,6 +205,80 @@ impl CLIArgs
                 skip_next = true;
             }
 
+            else if arg == "--max-depth" || arg == "--max-elements" || arg == "--max-input-size" || arg == "--max-members" || arg == "--max-string-length"
+            {
+                let limit = match get_limit_from_string(args.get(i + 1), arg)
+                {
+                    Ok(limit) => limit,
+                    Err(msg) => { return Some((-1, msg)); },
+                };
+
+                match arg.as_str()
+                {
+                    "--max-depth" => { self.max_depth = limit; },
+                    "--max-elements" => { self.max_elements = limit; },
+                    "--max-input-size" => { self.max_input_size = limit; },
+                    "--max-members" => { self.max_members = limit; },
+                    _ => { self.max_string_length = limit; },
+                }
+
+                skip_next = true;
+            }
+
+            else if arg == "-o" || arg == "--output-format"
+            {
+                let opt_next_arg = args.get(i + 1);
+
+                if opt_next_arg.is_none()
+                {
+                    return Some((-1, String::from("Expected a format after the argument '--output-format'")));
+                }
+
+                match get_output_format_from_string(opt_next_arg.unwrap())
+                {
+                    Ok(output_format) => { self.output_format = Some(output_format); },
+                    Err(msg) => { return Some((-1, msg)); },
+                }
+
+                skip_next = true;
+            }
+
+            else if arg == "-r" || arg == "--recover"
+            {
+                self.recover = true;
+                skip_next = false;
+            }
+
+            else if arg == "--skip-bad-lines"
+            {
+                self.skip_bad_lines = true;
+                skip_next = false;
+            }
+
+            else if arg == "--stream"
+            {
+                self.stream = true;
+                skip_next = false;
+            }
+
+            else if arg == "--surrogates"
+            {
+                let opt_next_arg = args.get(i + 1);
+
+                if opt_next_arg.is_none()
+                {
+                    return Some((-1, String::from("Expected a policy after the argument '--surrogates'")));
+                }
+
+                match get_surrogate_policy_from_string(opt_next_arg.unwrap())
+                {
+                    Ok(policy) => { self.surrogate_policy = policy; },
+                    Err(msg) => { return Some((-1, msg)); },
+                }
+
+                skip_next = true;
+            }
+
             else if arg == "-s" || arg == "--stringify"
             {
                 self.stringify = true;
@@ -115,11 +299,41 @@ impl CLIArgs
     }
 }
 
+impl Default for CLIArgs
+{
+    fn default() -> Self
+    {
+        return Self::new();
+    }
+}
+
+/// Parses the value of a '--max-*' argument, which is a number or 'none' for no limit.
+fn get_limit_from_string(opt_value: Option<&String>, arg: &String) -> Result<Option<usize>, String>
+{
+    match opt_value
+    {
+        None => { return Err(format!("Expected a limit after the argument '{}'", arg)); },
+        Some(value) if value.eq_ignore_ascii_case("none") => { return Ok(None); },
+        Some(value) =>
+        {
+            match value.parse::<usize>()
+            {
+                Ok(limit) => { return Ok(Some(limit)); },
+                Err(_) => { return Err(format!("Invalid limit ('{}') for the argument '{}'", value, arg)); },
+            }
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests
 {
     use crate::utils::cli_args::CLIArgs;
+    use crate::io::input_format::EnumInputFormat;
+    use crate::io::output_format::EnumOutputFormat;
     use crate::log::logger::EnumLogLevel;
+    use crate::parser::duplicate_key_policy::EnumDuplicateKeyPolicy;
+    use crate::parser::surrogate_policy::EnumSurrogatePolicy;
 
     fn cli_assert_fail(opt_err_pair: Option<(i32, String)>)
     {
@@ -281,21 +495,171 @@ mod tests
     fn parse_all_input_expect_valid()
     {
         let file = String::from("myfile.json");
-        let mut args = Vec::<String>::with_capacity(8);
+        let mut args = Vec::<String>::with_capacity(12);
         args.push(String::from("ruson"));
         args.push(String::from("--log-level"));
         args.push(String::from("DEBUG"));
         args.push(String::from("--input"));
         args.push(file.clone());
         args.push(String::from("--stringify"));
+        args.push(String::from("--recover"));
+        args.push(String::from("--stream"));
+        args.push(String::from("--exact-numbers"));
+        args.push(String::from("--lenient-numbers"));
+        args.push(String::from("--json5"));
 
         let mut cli_args = CLIArgs::new();
 
         let opt_err_pair = cli_args.parse(&args);
         assert!(opt_err_pair.is_none());
         assert_eq!(cli_args.log_level, EnumLogLevel::DEBUG);
+        assert!(cli_args.stringify);
+        assert!(cli_args.recover);
+        assert!(cli_args.stream);
+        assert!(cli_args.exact_numbers);
+        assert!(cli_args.lenient_numbers);
+        assert!(cli_args.json5);
         assert!(cli_args.input_file.is_some());
         assert_eq!(&cli_args.input_file.unwrap(), &file);
     }
-}
 
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn parse_decimal_numbers()
+    {
+        let args: Vec<String> = vec![ String::from("ruson"), String::from("--decimal-numbers") ];
+
+        let mut cli_args = CLIArgs::new();
+        assert!(!cli_args.decimal_numbers);
+
+        let opt_err_pair = cli_args.parse(&args);
+        assert!(opt_err_pair.is_none());
+        assert!(cli_args.decimal_numbers);
+    }
+
+    #[test]
+    fn parse_surrogate_policy()
+    {
+        let mut args = Vec::<String>::with_capacity(3);
+        args.push(String::from("ruson"));
+        args.push(String::from("--surrogates"));
+        args.push(String::from("replace"));
+
+        let mut cli_args = CLIArgs::new();
+        assert_eq!(cli_args.surrogate_policy, EnumSurrogatePolicy::ERROR);
+
+        let opt_err_pair = cli_args.parse(&args);
+        assert!(opt_err_pair.is_none());
+        assert_eq!(cli_args.surrogate_policy, EnumSurrogatePolicy::REPLACE);
+    }
+
+    #[test]
+    fn parse_surrogate_policy_invalid_fail()
+    {
+        let mut args = Vec::<String>::with_capacity(3);
+        args.push(String::from("ruson"));
+        args.push(String::from("--surrogates"));
+        args.push(String::from("ignore"));
+
+        let mut cli_args = CLIArgs::new();
+        cli_assert_fail(cli_args.parse(&args));
+
+        args.pop();
+        cli_assert_fail(cli_args.parse(&args));
+    }
+
+    #[test]
+    fn parse_duplicate_key_policy()
+    {
+        let mut args = Vec::<String>::with_capacity(3);
+        args.push(String::from("ruson"));
+        args.push(String::from("--duplicate-keys"));
+        args.push(String::from("keep-all"));
+
+        let mut cli_args = CLIArgs::new();
+        assert_eq!(cli_args.duplicate_key_policy, EnumDuplicateKeyPolicy::LAST_WINS);
+
+        let opt_err_pair = cli_args.parse(&args);
+        assert!(opt_err_pair.is_none());
+        assert_eq!(cli_args.duplicate_key_policy, EnumDuplicateKeyPolicy::KEEP_ALL);
+    }
+
+    #[test]
+    fn parse_duplicate_key_policy_invalid_fail()
+    {
+        let mut args = Vec::<String>::with_capacity(3);
+        args.push(String::from("ruson"));
+        args.push(String::from("--duplicate-keys"));
+        args.push(String::from("merge"));
+
+        let mut cli_args = CLIArgs::new();
+        cli_assert_fail(cli_args.parse(&args));
+
+        args.pop();
+        cli_assert_fail(cli_args.parse(&args));
+    }
+
+    #[test]
+    fn parse_limits()
+    {
+        let args: Vec<String> = [ "ruson", "--max-depth", "128", "--max-elements", "10", "--max-input-size", "1024", "--max-members", "20", "--max-string-length", "64" ]
+            .iter().map(|arg| String::from(*arg)).collect();
+
+        let mut cli_args = CLIArgs::new();
+        assert_eq!(cli_args.max_depth, None);
+
+        let opt_err_pair = cli_args.parse(&args);
+        assert!(opt_err_pair.is_none());
+        assert_eq!(cli_args.max_depth, Some(128));
+        assert_eq!(cli_args.max_elements, Some(10));
+        assert_eq!(cli_args.max_input_size, Some(1024));
+        assert_eq!(cli_args.max_members, Some(20));
+        assert_eq!(cli_args.max_string_length, Some(64));
+    }
+
+    #[test]
+    fn parse_limit_invalid_fail()
+    {
+        let mut args = Vec::<String>::with_capacity(3);
+        args.push(String::from("ruson"));
+        args.push(String::from("--max-depth"));
+        args.push(String::from("-1"));
+
+        let mut cli_args = CLIArgs::new();
+        cli_assert_fail(cli_args.parse(&args));
+
+        args.pop();
+        cli_assert_fail(cli_args.parse(&args));
+    }
+
+    #[test]
+    fn parse_input_format()
+    {
+        let args: Vec<String> = [ "ruson", "--input-format", "ndjson", "--skip-bad-lines" ].iter().map(|arg| String::from(*arg)).collect();
+        let mut cli_args = CLIArgs::new();
+        assert_eq!(cli_args.input_format, EnumInputFormat::JSON);
+
+        assert!(cli_args.parse(&args).is_none());
+        assert_eq!(cli_args.input_format, EnumInputFormat::NDJSON);
+        assert!(cli_args.skip_bad_lines);
+
+        let args: Vec<String> = [ "ruson", "--input-format", "json-seq", "-o", "ndjson" ].iter().map(|arg| String::from(*arg)).collect();
+        let mut cli_args = CLIArgs::new();
+        assert!(cli_args.output_format.is_none());
+
+        assert!(cli_args.parse(&args).is_none());
+        assert_eq!(cli_args.input_format, EnumInputFormat::JSON_SEQ);
+        assert_eq!(cli_args.output_format, Some(EnumOutputFormat::NDJSON));
+
+        let args: Vec<String> = [ "ruson", "--extract" ].iter().map(|arg| String::from(*arg)).collect();
+        let mut cli_args = CLIArgs::new();
+        assert!(cli_args.parse(&args).is_none());
+        assert!(cli_args.extract);
+
+        let args: Vec<String> = [ "ruson", "--output-format" ].iter().map(|arg| String::from(*arg)).collect();
+        cli_assert_fail(CLIArgs::new().parse(&args));
+
+        let args: Vec<String> = [ "ruson", "--input-format", "yaml" ].iter().map(|arg| String::from(*arg)).collect();
+        cli_assert_fail(CLIArgs::new().parse(&args));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::utils::float_utils::parse_f64;
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
+/// An exact decimal number of any size or precision (ex. "12345678901234567890.123456789").
+///
+/// Numbers are compared by value, so "1", "1.0" and "0.1e1" are all equal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Decimal
+{
+    negative: bool,
+    /// Significant digits (0-9) without any leading or trailing zeros (empty for zero).
+    digits: Vec<u8>,
+    /// The value is 'digits * 10^exponent'.
+    exponent: i64,
+}
+
+impl Decimal
+{
+    /// Parses a JSON number (ex. "-1.5e-3"). Returns None if it isn't a number or
+    /// its exponent doesn't fit in an i64.
+    pub fn parse(input: &str) -> Option<Self>
+    {
+        let (negative, rest) = match input.strip_prefix('-')
+        {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+
+        let (mantissa, exp_str) = match rest.find(['e', 'E'])
+        {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+
+        let (int_part, frac_part) = match mantissa.split_once('.')
+        {
+            Some((int_part, frac_part)) => (int_part, frac_part),
+            None => (mantissa, ""),
+        };
+
+        let all_digits = int_part.bytes().chain(frac_part.bytes());
+
+        if int_part.len() + frac_part.len() == 0 || !all_digits.clone().all(|byte| byte.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let mut exponent: i64 = match exp_str
+        {
+            Some(exp_str) if exp_str.starts_with('+') && exp_str.len() > 1 => exp_str[1..].parse::<i64>().ok()?,
+            Some(exp_str) if !exp_str.starts_with('+') => exp_str.parse::<i64>().ok()?,
+            Some(_) => { return None; },
+            None => 0,
+        };
+
+        exponent = exponent.checked_sub(frac_part.len() as i64)?;
+
+        let mut digits: Vec<u8> = all_digits.map(|byte| byte - b'0').skip_while(|digit| *digit == 0).collect();
+
+        while digits.last() == Some(&0)
+        {
+            digits.pop();
+            exponent = exponent.checked_add(1)?;
+        }
+
+        if digits.is_empty()
+        {
+            return Some(Self::zero());
+        }
+
+        return Some(Self { negative, digits, exponent });
+    }
+
+    pub fn zero() -> Self
+    {
+        Self { negative: false, digits: Vec::new(), exponent: 0 }
+    }
+
+    pub fn is_zero(&self) -> bool
+    {
+        return self.digits.is_empty();
+    }
+
+    pub fn is_negative(&self) -> bool
+    {
+        return self.negative;
+    }
+
+    /// Checks if the number has no fractional part.
+    pub fn is_integer(&self) -> bool
+    {
+        return self.exponent >= 0;
+    }
+
+    /// Gets the nearest f64 to the number.
+    pub fn to_f64(&self) -> f64
+    {
+        return parse_f64(&self.to_string()).expect("A Decimal should always format as a valid number");
+    }
+
+    /// The power of 10 just above the number's magnitude, ie. the number is in [10^(n - 1), 10^n).
+    fn magnitude(&self) -> i128
+    {
+        return self.digits.len() as i128 + self.exponent as i128;
+    }
+
+    fn compare_magnitude(&self, other: &Self) -> Ordering
+    {
+        // Without leading/trailing zeros, the digits only need comparing when the magnitudes match.
+        return self.magnitude().cmp(&other.magnitude()).then_with(|| self.digits.cmp(&other.digits));
+    }
+}
+
+impl Ord for Decimal
+{
+    fn cmp(&self, other: &Self) -> Ordering
+    {
+        match (self.is_zero(), other.is_zero())
+        {
+            (true, true) => { return Ordering::Equal; },
+            (true, false) => { return if other.negative { Ordering::Greater } else { Ordering::Less }; },
+            (false, true) => { return if self.negative { Ordering::Less } else { Ordering::Greater }; },
+            (false, false) => {},
+        }
+
+        match (self.negative, other.negative)
+        {
+            (false, false) => { return self.compare_magnitude(other); },
+            (true, true) => { return other.compare_magnitude(self); },
+            (false, true) => { return Ordering::Greater; },
+            (true, false) => { return Ordering::Less; },
+        }
+    }
+}
+
+impl PartialOrd for Decimal
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering>
+    {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Display for Decimal
+{
+    /// Writes the number in plain notation when it's reasonably sized (ex. "-0.0015"),
+    /// otherwise in scientific notation (ex. "1.5e-30").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        if self.is_zero()
+        {
+            return write!(f, "0");
+        }
+
+        let digits: String = self.digits.iter().map(|digit| char::from(b'0' + digit)).collect();
+        let magnitude = self.magnitude();
+        let sign = if self.negative { "-" } else { "" };
+
+        if self.exponent >= 0 && magnitude <= 21
+        {
+            return write!(f, "{}{}{}", sign, digits, "0".repeat(self.exponent as usize));
+        }
+
+        else if self.exponent < 0 && magnitude > 0
+        {
+            let (int_part, frac_part) = digits.split_at(magnitude as usize);
+            return write!(f, "{}{}.{}", sign, int_part, frac_part);
+        }
+
+        else if self.exponent < 0 && magnitude > -6
+        {
+            return write!(f, "{}0.{}{}", sign, "0".repeat(magnitude.unsigned_abs() as usize), digits);
+        }
+
+        let (first, rest) = digits.split_at(1);
+        let dot = if rest.is_empty() { "" } else { "." };
+
+        return write!(f, "{}{}{}{}e{}", sign, first, dot, rest, magnitude - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::Decimal;
+
+    fn decimal(input: &str) -> Decimal
+    {
+        return Decimal::parse(input).expect(input);
+    }
+
+    #[test]
+    fn parse_decimal()
+    {
+        assert_eq!(decimal("12345678901234567890.123456789").to_string(), "12345678901234567890.123456789");
+        assert_eq!(decimal("-0.00150").to_string(), "-0.0015");
+        assert_eq!(decimal("1.5E+3").to_string(), "1500");
+        assert_eq!(decimal("-0").to_string(), "0");
+        assert_eq!(decimal("1e-30").to_string(), "1e-30");
+        assert_eq!(decimal("12.5e40").to_string(), "1.25e41");
+        assert!(decimal("100").is_integer());
+        assert!(!decimal("0.5").is_integer());
+        assert!(decimal("-0.5").is_negative());
+
+        for input in [ "", "-", ".", "1e", "1e+", "abc", "1.2.3", "1e99999999999999999999" ]
+        {
+            assert!(Decimal::parse(input).is_none(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn compare_decimals_exactly()
+    {
+        assert_eq!(decimal("1"), decimal("1.000"));
+        assert_eq!(decimal("0.1e1"), decimal("1"));
+        assert_eq!(decimal("-0"), decimal("0.0e5"));
+
+        // Both round to the same f64, but they aren't equal.
+        assert_ne!(decimal("9007199254740993"), decimal("9007199254740992"));
+        assert!(decimal("9007199254740993") > decimal("9007199254740992"));
+        assert!(decimal("0.30000000000000000001") > decimal("0.3"));
+
+        let mut values = [ decimal("10"), decimal("-1.5"), decimal("0"), decimal("9.99"), decimal("-20"), decimal("1e-5"), decimal("-1e-5") ];
+        values.sort();
+
+        let sorted: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        assert_eq!(sorted, vec![ "-20", "-1.5", "-0.00001", "0", "0.00001", "9.99", "10" ]);
+    }
+
+    #[test]
+    fn decimal_to_f64()
+    {
+        assert_eq!(decimal("12345678901234567890.123456789").to_f64(), 12345678901234567890.123456789);
+        assert_eq!(decimal("-1.5e-3").to_f64(), -0.0015);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::parser::parse_error::{EnumParseErrorKind, ParseError};
+use crate::utils::string_utils::StringBuilder;
+
+/// Renders a rustc-style diagnostic for a ParseError, ex:
+///
+/// ```text
+/// error: expected ',' or '}' but found string "b"
+///  --> config.json:3:5
+///   |
+/// 3 |     "b": 2
+///   |     ^^^
+///   = hint: missing ',' between object members
+/// ```
+///
+/// 'source' is the input text starting at the byte offset 'source_start', which is 0 unless only
+/// the last part of a streamed input is still held. The source line is left out when it isn't there.
+pub fn render_diagnostic(source: &str, source_start: usize, file_name: Option<&String>, error: &ParseError) -> String
+{
+    return render(source, source_start, file_name, error, "error");
+}
+
+/// Renders the same diagnostic as 'render_diagnostic' for a problem that didn't stop the parse
+/// (ex. a duplicate key), labelled as a warning.
+pub fn render_warning(source: &str, source_start: usize, file_name: Option<&String>, warning: &ParseError) -> String
+{
+    return render(source, source_start, file_name, warning, "warning");
+}
+
+fn render(source: &str, source_start: usize, file_name: Option<&String>, error: &ParseError, severity: &str) -> String
+{
+    let mut builder = StringBuilder::new(256);
+    let line_number = error.get_line().to_string();
+    let gutter = " ".repeat(line_number.len());
+
+    builder.append_str(severity);
+    builder.append_str(": ");
+    builder.append_string(&error.get_message());
+    builder.append_char('\n');
+
+    builder.append_string(&gutter);
+    builder.append_str("--> ");
+    builder.append_str(file_name.map(|name| name.as_str()).unwrap_or("<stdin>"));
+    builder.append_string(&format!(":{}:{}\n", error.get_line(), error.get_column()));
+
+    if error.get_start() >= source_start
+    {
+        let (line_start, line_text) = get_line_at(source, error.get_start() - source_start);
+
+        builder.append_string(&gutter);
+        builder.append_str(" |\n");
+        builder.append_string(&line_number);
+        builder.append_str(" | ");
+        builder.append_str(line_text);
+        builder.append_char('\n');
+
+        // Mirror any tabs of the source line so the carets line up with the offending text.
+        let start = (error.get_start() - source_start).min(line_start + line_text.len());
+        let end = (error.get_end() - source_start).min(line_start + line_text.len());
+        let prefix: String = source[line_start..start].chars().map(|ch| if ch == '\t' { '\t' } else { ' ' }).collect();
+        let caret_count = source[start..end].chars().count().max(1);
+
+        builder.append_string(&gutter);
+        builder.append_str(" | ");
+        builder.append_string(&prefix);
+        builder.append_string(&"^".repeat(caret_count));
+        builder.append_char('\n');
+    }
+
+    if let Some(hint) = get_hint(error.get_kind())
+    {
+        builder.append_string(&gutter);
+        builder.append_str(" = hint: ");
+        builder.append_str(hint);
+        builder.append_char('\n');
+    }
+
+    return builder.to_string();
+}
+
+/// Gets a short suggestion on how to fix the given kind of error (if there is one).
+pub fn get_hint(kind: &EnumParseErrorKind) -> Option<&'static str>
+{
+    match kind
+    {
+        EnumParseErrorKind::UnexpectedCharacter('\'') => { return Some("strings must be enclosed in double-quotes ('\"')"); },
+        EnumParseErrorKind::UnexpectedCharacter(_) => { return None; },
+        EnumParseErrorKind::InvalidUtf8 => { return Some("the input must be encoded as UTF-8"); },
+        EnumParseErrorKind::ReadFailed(_) => { return None; },
+        EnumParseErrorKind::UnexpectedEOF => { return Some("the input ended before every '[' and '{' was closed"); },
+        EnumParseErrorKind::UnterminatedString => { return Some("add a '\"' at the end of the string"); },
+        EnumParseErrorKind::UnterminatedComment => { return Some("add a '*/' at the end of the comment"); },
+        EnumParseErrorKind::InvalidEscape(_) => { return Some("valid escapes are \\\" \\\\ \\/ \\b \\f \\n \\r \\t and \\uXXXX"); },
+        EnumParseErrorKind::InvalidUnicodeEscape(_) => { return Some("a unicode escape is '\\u' followed by exactly 4 hexadecimal digits"); },
+        EnumParseErrorKind::LoneSurrogate(_) => { return Some("characters above U+FFFF are escaped as a high/low surrogate pair, ex. \\uD83D\\uDE00"); },
+        EnumParseErrorKind::InvalidNumber(_) => { return None; },
+        EnumParseErrorKind::NumberOutOfRange => { return Some("'--exact-numbers' keeps the number as written"); },
+        EnumParseErrorKind::ExpectedValue(found) if found == "']'" => { return Some("trailing commas are not allowed after the last array element"); },
+        EnumParseErrorKind::ExpectedValue(_) => { return None; },
+        EnumParseErrorKind::ExpectedKey(found) if found == "'}'" => { return Some("trailing commas are not allowed after the last object member"); },
+        EnumParseErrorKind::ExpectedKey(_) => { return Some("object keys must be strings enclosed in double-quotes ('\"')"); },
+        EnumParseErrorKind::ExpectedColon(_) => { return Some("missing ':' between the key and its value"); },
+        EnumParseErrorKind::ExpectedCommaOrEnd { close: '}', .. } => { return Some("missing ',' between object members"); },
+        EnumParseErrorKind::ExpectedCommaOrEnd { .. } => { return Some("missing ',' between array elements"); },
+        EnumParseErrorKind::DuplicateKey { .. } => { return Some("each key should appear once; '--duplicate-keys' picks which value is kept"); },
+        EnumParseErrorKind::DepthLimitExceeded { .. } => { return Some("'--max-depth' raises the limit"); },
+        EnumParseErrorKind::StringTooLong { .. } => { return Some("'--max-string-length' raises the limit"); },
+        EnumParseErrorKind::NumberTooLong { .. } => { return Some("'--max-string-length' raises the limit, which also applies to numbers"); },
+        EnumParseErrorKind::TooManyMembers { .. } => { return Some("'--max-members' raises the limit"); },
+        EnumParseErrorKind::TooManyElements { .. } => { return Some("'--max-elements' raises the limit"); },
+        EnumParseErrorKind::InputTooLarge { .. } => { return Some("'--max-input-size' raises the limit"); },
+        EnumParseErrorKind::TrailingData(_) => { return Some("a document holds a single root value; wrap multiple values in an array"); },
+        EnumParseErrorKind::MissingRecordSeparator => { return Some("each record of a JSON text sequence starts with an RS (0x1E) character"); },
+        EnumParseErrorKind::TruncatedRecord => { return Some("each record of a JSON text sequence ends with a line feed"); },
+    }
+}
+
+/// Finds the line containing the byte offset 'pos' and returns the byte offset of the line's start
+/// along with the line (without the line terminator).
+fn get_line_at(source: &str, pos: usize) -> (usize, &str)
+{
+    let pos = pos.min(source.len());
+    let line_start = source[..pos].rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let line_end = source[line_start..].find('\n').map(|index| line_start + index).unwrap_or(source.len());
+    let line = &source[line_start..line_end];
+
+    return (line_start, line.strip_suffix('\r').unwrap_or(line));
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::parser::Parser;
+    use crate::parser::parser_options::ParserOptions;
+    use super::{render_diagnostic, render_warning};
+
+    fn render(input: &str) -> String
+    {
+        let input = String::from(input);
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        let error = parser.parse().err().expect("Expected a parse error");
+
+        return render_diagnostic(&input, 0, Some(&String::from("config.json")), &error);
+    }
+
+    #[test]
+    fn render_missing_comma()
+    {
+        let expected = "error: expected ',' or '}' but found string \"b\"\n \
+                        --> config.json:3:5\n  \
+                        |\n\
+                        3 |     \"b\": 2\n  \
+                        |     ^^^\n  \
+                        = hint: missing ',' between object members\n";
+
+        assert_eq!(render("{\n    \"a\": 1\n    \"b\": 2\n}"), expected);
+    }
+
+    #[test]
+    fn render_trailing_comma_in_array()
+    {
+        let expected = "error: expected a value but found ']'\n \
+                        --> config.json:1:8\n  \
+                        |\n\
+                        1 | [1, 2, ]\n  \
+                        |        ^\n  \
+                        = hint: trailing commas are not allowed after the last array element\n";
+
+        assert_eq!(render("[1, 2, ]"), expected);
+    }
+
+    #[test]
+    fn render_keeps_tabs_aligned()
+    {
+        let output = render("{\n\t\"a\" 1\n}");
+        assert!(output.contains("2 | \t\"a\" 1\n"));
+        assert!(output.contains("  | \t    ^\n"));
+    }
+
+    #[test]
+    fn render_unexpected_eof()
+    {
+        let output = render("[1,");
+        assert!(output.contains("--> config.json:1:4\n"));
+        assert!(output.contains("  |    ^\n"));
+    }
+
+    #[test]
+    fn render_duplicate_key_warning()
+    {
+        let input = String::from("{\"a\": 1, \"a\": 2}");
+        let mut parser = Parser::new_copy(&input, &ParserOptions::default());
+        assert!(parser.parse().is_ok());
+
+        let warnings = parser.take_warnings();
+        assert_eq!(warnings.len(), 1);
+
+        let expected = "warning: duplicate key \"a\" (first defined at line 1, column 2)\n \
+                        --> config.json:1:10\n  \
+                        |\n\
+                        1 | {\"a\": 1, \"a\": 2}\n  \
+                        |          ^^^\n  \
+                        = hint: each key should appear once; '--duplicate-keys' picks which value is kept\n";
+        assert_eq!(render_warning(&input, 0, Some(&String::from("config.json")), &warnings[0]), expected);
+    }
+
+    #[test]
+    fn render_from_part_of_the_input()
+    {
+        let input = String::from("[\n  1,\n  2 3\n]");
+        let error = Parser::new_copy(&input, &ParserOptions::default()).parse().err().expect("Expected a parse error");
+
+        // Only the text from the start of the second line is held.
+        let output = render_diagnostic(&input[2..], 2, None, &error);
+        assert!(output.contains("3 |   2 3\n"));
+        assert!(output.contains("  |     ^\n"));
+
+        // The line with the error was already discarded.
+        let output = render_diagnostic("]", 14, None, &error);
+        assert_eq!(output, "error: expected ',' or ']' but found number 3\n --> <stdin>:3:5\n  = hint: missing ',' between array elements\n");
+    }
+}
@@ -0,0 +1,723 @@
+use std::cmp::Ordering;
+
+/// Any more significant digits than this can't change how a decimal rounds to an f64
+/// (the longest exactly representable f64 has 767 significant digits).
+const MAX_DIGITS: usize = 800;
+
+/// Powers of 10 that are exactly representable as an f64.
+const EXACT_POWERS_OF_TEN: [f64; 23] =
+[
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11,
+    1e12, 1e13, 1e14, 1e15, 1e16, 1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Converts a decimal number (ex. "-123.45E10") to the nearest f64 (ties to even), the same as
+/// 'str::parse::<f64>' would. Numbers too large for an f64 become an infinity.
+///
+/// Accepts an optional '-', digits with an optional '.' (at least one digit overall) and an
+/// optional exponent. Returns None if the input is not a number in this form.
+pub fn parse_f64(input: &str) -> Option<f64>
+{
+    let decimal = Decimal::parse(input)?;
+    let magnitude = decimal.to_f64();
+
+    return Some(if decimal.negative { -magnitude } else { magnitude });
+}
+
+/// Formats a finite f64 with the fewest significant digits that parse back to the exact same value.
+/// Plain notation is used for moderate magnitudes (ex. "123.45" or "0.001") and scientific notation
+/// otherwise (ex. "1e21" or "1.5e-8"), so the output is always a valid JSON number.
+pub fn format_f64(value: f64) -> String
+{
+    debug_assert!(value.is_finite(), "Non-finite values have no JSON representation");
+
+    // The standard library's '{:e}' already produces the shortest round-trip digits (ex. "-1.2345e10").
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_once('e').expect("'{:e}' should always have an exponent");
+    let exponent: i32 = exponent.parse().expect("'{:e}' should always have an integer exponent");
+    let (sign, mantissa) = match mantissa.strip_prefix('-')
+    {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+
+    let digits: String = mantissa.chars().filter(|ch| *ch != '.').collect();
+    let digit_count = digits.len() as i32;
+
+    // Same thresholds as JavaScript's 'Number.prototype.toString'.
+    if (0..21).contains(&exponent)
+    {
+        if digit_count <= exponent + 1
+        {
+            return format!("{}{}{}", sign, digits, "0".repeat((exponent + 1 - digit_count) as usize));
+        }
+
+        let (int_part, frac_part) = digits.split_at((exponent + 1) as usize);
+        return format!("{}{}.{}", sign, int_part, frac_part);
+    }
+
+    else if (-6..0).contains(&exponent)
+    {
+        return format!("{}0.{}{}", sign, "0".repeat((-exponent - 1) as usize), digits);
+    }
+
+    let (first, rest) = digits.split_at(1);
+    let dot = if rest.is_empty() { "" } else { "." };
+
+    return format!("{}{}{}{}e{}", sign, first, dot, rest, exponent);
+}
+
+/// A decimal number of the form 'digits * 10^exponent'.
+struct Decimal
+{
+    negative: bool,
+    /// Significant digits (0-9) without any leading zeros.
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Decimal
+{
+    fn parse(input: &str) -> Option<Self>
+    {
+        let bytes = input.as_bytes();
+        let mut index: usize = 0;
+        let negative = bytes.first() == Some(&b'-');
+
+        if negative
+        {
+            index += 1;
+        }
+
+        let mut digits = Vec::<u8>::new();
+        let mut exponent: i64 = 0;
+        let mut seen_digit = false;
+        let mut seen_dot = false;
+        let mut truncated_non_zero = false;
+
+        while index < bytes.len()
+        {
+            let byte = bytes[index];
+
+            if byte.is_ascii_digit()
+            {
+                seen_digit = true;
+
+                if seen_dot
+                {
+                    exponent -= 1;
+                }
+
+                // Skip leading zeros.
+                if digits.is_empty() && byte == b'0'
+                {
+                    index += 1;
+                    continue;
+                }
+
+                if digits.len() < MAX_DIGITS
+                {
+                    digits.push(byte - b'0');
+                }
+
+                // The digit is dropped so it only scales the number.
+                else
+                {
+                    truncated_non_zero |= byte != b'0';
+                    exponent += 1;
+                }
+            }
+
+            else if byte == b'.' && !seen_dot
+            {
+                seen_dot = true;
+            }
+
+            else
+            {
+                break;
+            }
+
+            index += 1;
+        }
+
+        if !seen_digit
+        {
+            return None;
+        }
+
+        if index < bytes.len()
+        {
+            if bytes[index] != b'e' && bytes[index] != b'E'
+            {
+                return None;
+            }
+
+            index += 1;
+            let exp_negative = bytes.get(index) == Some(&b'-');
+
+            if exp_negative || bytes.get(index) == Some(&b'+')
+            {
+                index += 1;
+            }
+
+            if index >= bytes.len()
+            {
+                return None;
+            }
+
+            let mut exp_value: i64 = 0;
+
+            for &byte in &bytes[index..]
+            {
+                if !byte.is_ascii_digit()
+                {
+                    return None;
+                }
+
+                // Anything this large is an infinity or zero anyways.
+                exp_value = (exp_value * 10 + (byte - b'0') as i64).min(1_000_000_000);
+            }
+
+            exponent += if exp_negative { -exp_value } else { exp_value };
+        }
+
+        // A dropped non-zero digit only matters when the rest of the digits are exactly halfway
+        // between two f64s, so a trailing '1' is enough to break the tie.
+        if truncated_non_zero
+        {
+            digits.push(1);
+            exponent -= 1;
+        }
+
+        while digits.last() == Some(&0)
+        {
+            digits.pop();
+            exponent += 1;
+        }
+
+        return Some(Self { negative, digits, exponent });
+    }
+
+    /// Gets the nearest f64 to the magnitude of the number.
+    fn to_f64(&self) -> f64
+    {
+        if self.digits.is_empty()
+        {
+            return 0.0;
+        }
+
+        // The number is in [10^(decimal_point - 1), 10^decimal_point).
+        let decimal_point = self.digits.len() as i64 + self.exponent;
+
+        if decimal_point > 310
+        {
+            return f64::INFINITY;
+        }
+
+        else if decimal_point < -324
+        {
+            return 0.0;
+        }
+
+        if let Some(result) = self.try_fast_path()
+        {
+            return result;
+        }
+
+        return self.to_f64_slow();
+    }
+
+    /// When both the digits and the power of 10 are exact as f64s, a single multiplication or
+    /// division is correctly rounded (Clinger's fast path).
+    fn try_fast_path(&self) -> Option<f64>
+    {
+        const MAX_MANTISSA: u64 = 1 << 53;
+
+        if self.digits.len() > 19 || self.exponent.abs() > 22
+        {
+            return None;
+        }
+
+        let mantissa = self.digits.iter().fold(0u64, |acc, &digit| acc * 10 + digit as u64);
+
+        if mantissa > MAX_MANTISSA
+        {
+            return None;
+        }
+
+        let power = EXACT_POWERS_OF_TEN[self.exponent.unsigned_abs() as usize];
+
+        if self.exponent >= 0
+        {
+            return Some(mantissa as f64 * power);
+        }
+
+        return Some(mantissa as f64 / power);
+    }
+
+    /// Exactly computes 'numerator / denominator * 2^binary_exponent' with big integers and
+    /// rounds the result once.
+    fn to_f64_slow(&self) -> f64
+    {
+        let mut numerator = BigUint::from_digits(&self.digits);
+        let mut denominator = BigUint::from_u64(1);
+
+        // 10^n = 5^n * 2^n, where the 2^n is tracked separately as a binary exponent.
+        let binary_exponent = self.exponent;
+
+        if self.exponent >= 0
+        {
+            numerator.mul_pow5(self.exponent as u64);
+        }
+
+        else
+        {
+            denominator.mul_pow5(self.exponent.unsigned_abs());
+        }
+
+        // Pick a scale so the quotient has exactly 53 bits, unless the result is subnormal.
+        let mut scale: i64 = 53 - (numerator.bit_len() as i64 - denominator.bit_len() as i64);
+
+        if scaled_compare(&numerator, &denominator, scale - 53) != Ordering::Less
+        {
+            scale -= 1;
+        }
+
+        // The smallest subnormal is 2^-1074.
+        scale = scale.min(binary_exponent + 1074);
+
+        let (mut quotient, remainder_cmp) = divide_scaled(&numerator, &denominator, scale);
+
+        // Round half to even.
+        if remainder_cmp == Ordering::Greater || (remainder_cmp == Ordering::Equal && quotient & 1 == 1)
+        {
+            quotient += 1;
+        }
+
+        return make_f64(quotient, binary_exponent - scale);
+    }
+}
+
+/// Compares 'numerator * 2^scale' with 'denominator'.
+fn scaled_compare(numerator: &BigUint, denominator: &BigUint, scale: i64) -> Ordering
+{
+    let mut lhs = numerator.clone();
+    let mut rhs = denominator.clone();
+
+    if scale >= 0
+    {
+        lhs.shl(scale as u64);
+    }
+
+    else
+    {
+        rhs.shl(scale.unsigned_abs());
+    }
+
+    return lhs.compare(&rhs);
+}
+
+/// Computes 'floor(numerator * 2^scale / denominator)' (which must fit in a u64) and how twice the
+/// remainder compares to the (scaled) denominator, ie. whether the rest is below, at or above one half.
+fn divide_scaled(numerator: &BigUint, denominator: &BigUint, scale: i64) -> (u64, Ordering)
+{
+    let mut remainder = numerator.clone();
+    let mut divisor = denominator.clone();
+
+    if scale >= 0
+    {
+        remainder.shl(scale as u64);
+    }
+
+    else
+    {
+        divisor.shl(scale.unsigned_abs());
+    }
+
+    // Binary long division since the quotient only has a handful of bits.
+    const QUOTIENT_BITS: u64 = 55;
+    let mut quotient: u64 = 0;
+    let mut shifted = divisor.clone();
+    shifted.shl(QUOTIENT_BITS - 1);
+
+    for bit in (0..QUOTIENT_BITS).rev()
+    {
+        if remainder.compare(&shifted) != Ordering::Less
+        {
+            remainder.sub(&shifted);
+            quotient |= 1 << bit;
+        }
+
+        shifted.shr1();
+    }
+
+    remainder.shl(1);
+    return (quotient, remainder.compare(&divisor));
+}
+
+/// Builds 'mantissa * 2^exponent', which must be exactly representable unless it overflows.
+fn make_f64(mantissa: u64, exponent: i64) -> f64
+{
+    if mantissa == 0
+    {
+        return 0.0;
+    }
+
+    let bit_len = 64 - mantissa.leading_zeros() as i64;
+
+    if bit_len + exponent > 1024
+    {
+        return f64::INFINITY;
+    }
+
+    // Both the mantissa and power of 2 are exact, so is their product.
+    return mantissa as f64 * pow2(exponent - (exponent / 2)) * pow2(exponent / 2);
+}
+
+/// Gets 2^exponent for any exponent in the range of f64s (including subnormals).
+fn pow2(exponent: i64) -> f64
+{
+    if exponent >= -1022
+    {
+        return f64::from_bits(((exponent + 1023) as u64) << 52);
+    }
+
+    return f64::from_bits(1u64 << (exponent + 1074));
+}
+
+/// A minimal arbitrary sized unsigned integer (little-endian 32-bit limbs).
+#[derive(Clone)]
+struct BigUint
+{
+    limbs: Vec<u32>,
+}
+
+impl BigUint
+{
+    fn from_u64(value: u64) -> Self
+    {
+        let mut result = Self { limbs: vec![value as u32, (value >> 32) as u32] };
+        result.trim();
+        return result;
+    }
+
+    fn from_digits(digits: &[u8]) -> Self
+    {
+        let mut result = Self { limbs: Vec::new() };
+
+        // Add digits 9 at a time.
+        for chunk in digits.chunks(9)
+        {
+            let value = chunk.iter().fold(0u32, |acc, &digit| acc * 10 + digit as u32);
+            result.mul_add_small(10u32.pow(chunk.len() as u32), value);
+        }
+
+        return result;
+    }
+
+    fn trim(&mut self)
+    {
+        while self.limbs.last() == Some(&0)
+        {
+            self.limbs.pop();
+        }
+    }
+
+    fn mul_add_small(&mut self, multiplier: u32, addend: u32)
+    {
+        let mut carry = addend as u64;
+
+        for limb in self.limbs.iter_mut()
+        {
+            let product = (*limb as u64) * (multiplier as u64) + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+
+        if carry > 0
+        {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    fn mul_pow5(&mut self, mut power: u64)
+    {
+        // 5^13 is the largest power of 5 that fits in a u32.
+        const POW5_13: u32 = 1220703125;
+
+        while power >= 13
+        {
+            self.mul_add_small(POW5_13, 0);
+            power -= 13;
+        }
+
+        self.mul_add_small(5u32.pow(power as u32), 0);
+    }
+
+    fn shl(&mut self, bits: u64)
+    {
+        if self.limbs.is_empty()
+        {
+            return;
+        }
+
+        let limb_shift = (bits / 32) as usize;
+        let bit_shift = (bits % 32) as u32;
+
+        if bit_shift > 0
+        {
+            let mut carry: u32 = 0;
+
+            for limb in self.limbs.iter_mut()
+            {
+                let next_carry = *limb >> (32 - bit_shift);
+                *limb = (*limb << bit_shift) | carry;
+                carry = next_carry;
+            }
+
+            if carry > 0
+            {
+                self.limbs.push(carry);
+            }
+        }
+
+        self.limbs.splice(0..0, std::iter::repeat_n(0, limb_shift));
+    }
+
+    fn shr1(&mut self)
+    {
+        let mut carry: u32 = 0;
+
+        for limb in self.limbs.iter_mut().rev()
+        {
+            let next_carry = *limb << 31;
+            *limb = (*limb >> 1) | carry;
+            carry = next_carry;
+        }
+
+        self.trim();
+    }
+
+    /// Subtracts 'other', which must not be larger than self.
+    fn sub(&mut self, other: &BigUint)
+    {
+        let mut borrow = false;
+
+        for (index, limb) in self.limbs.iter_mut().enumerate()
+        {
+            let rhs = other.limbs.get(index).copied().unwrap_or(0);
+
+            if rhs == 0 && !borrow && index >= other.limbs.len()
+            {
+                break;
+            }
+
+            let (result, borrow1) = limb.overflowing_sub(rhs);
+            let (result, borrow2) = result.overflowing_sub(borrow as u32);
+            *limb = result;
+            borrow = borrow1 || borrow2;
+        }
+
+        self.trim();
+    }
+
+    fn bit_len(&self) -> u64
+    {
+        match self.limbs.last()
+        {
+            Some(last) => { return self.limbs.len() as u64 * 32 - last.leading_zeros() as u64; },
+            None => { return 0; },
+        }
+    }
+
+    fn compare(&self, other: &BigUint) -> Ordering
+    {
+        if self.limbs.len() != other.limbs.len()
+        {
+            return self.limbs.len().cmp(&other.limbs.len());
+        }
+
+        return self.limbs.iter().rev().cmp(other.limbs.iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::{format_f64, parse_f64};
+
+    fn assert_same_as_std(input: &str)
+    {
+        let expected = input.parse::<f64>().unwrap();
+        let actual = parse_f64(input).unwrap();
+        assert_eq!(actual.to_bits(), expected.to_bits(), "'{}' parsed to {:e} instead of {:e}", input, actual, expected);
+    }
+
+    /// A tiny deterministic PRNG (xorshift) so the randomized tests are reproducible.
+    struct XorShift
+    {
+        state: u64,
+    }
+
+    impl XorShift
+    {
+        fn next(&mut self) -> u64
+        {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            return self.state;
+        }
+    }
+
+    #[test]
+    fn parse_simple_numbers()
+    {
+        let inputs = [ "0", "-0", "0.0", "1", "-1", "0.1", "0.2", "0.3", "123.45E10", "123.45e-10", "1e0", "1E22", "1e23",
+                       "9007199254740993", "18446744073709551615", "18446744073709551616", "3.14159265358979323846264338327950288",
+                       "2.2250738585072014e-308", "2.2250738585072011e-308", "4.9406564584124654e-324", "2.4703282292062327e-324",
+                       "2.4703282292062328e-324", "1.7976931348623157e308", "1.7976931348623158e308", "1.7976931348623159e308",
+                       "1e309", "1e-400", "0.000000000000000000000000000000000001", "00012.5000", "1e+5" ];
+
+        for input in inputs
+        {
+            assert_same_as_std(input);
+        }
+
+        assert_eq!(parse_f64("-0").unwrap().to_bits(), (-0.0f64).to_bits());
+        assert_eq!(parse_f64(".5"), Some(0.5));
+        assert_eq!(parse_f64("5."), Some(5.0));
+    }
+
+    #[test]
+    fn parse_halfway_cases()
+    {
+        // Exactly halfway between two f64s, so these round to even.
+        assert_same_as_std("9007199254740993");
+        assert_same_as_std("9007199254740995");
+        assert_same_as_std("9007199254740993.0000000000000000000000000000000000001");
+
+        // 2^-1075 is halfway between 0 and the smallest subnormal.
+        let half_min_subnormal = "2.470328229206232720882538326711547050006402758566407838337245880758893543478186480e-324";
+        assert_same_as_std(half_min_subnormal);
+        assert_same_as_std(&half_min_subnormal.replace("e-324", "0000000000000000000000000000000001e-324"));
+
+        // A halfway point that only the 800th+ digit resolves.
+        let mut long = String::from("9007199254740993");
+        long.push('.');
+        long.push_str(&"0".repeat(1000));
+        assert_same_as_std(&long);
+        long.push('1');
+        assert_same_as_std(&long);
+    }
+
+    #[test]
+    fn parse_invalid_numbers()
+    {
+        let inputs = [ "", "-", ".", "-.", "e5", "1e", "1e-", "1e+", "1.2.3", "1x", "--1", "1e5.5" ];
+
+        for input in inputs
+        {
+            assert!(parse_f64(input).is_none(), "'{}' should not parse", input);
+        }
+    }
+
+    #[test]
+    fn round_trip_random_bit_patterns()
+    {
+        let mut rng = XorShift { state: 0x9E3779B97F4A7C15 };
+
+        for _ in 0..5000
+        {
+            let value = f64::from_bits(rng.next());
+
+            if !value.is_finite()
+            {
+                continue;
+            }
+
+            // Shortest round-trip, scientific and long fixed forms of the same value.
+            for input in [ format!("{:?}", value), format!("{:e}", value), format!("{:.30e}", value) ]
+            {
+                let actual = parse_f64(&input).unwrap();
+                assert_eq!(actual.to_bits(), value.to_bits(), "'{}' did not round trip", input);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_random_decimals_same_as_std()
+    {
+        let mut rng = XorShift { state: 0x2545F4914F6CDD1D };
+
+        for _ in 0..20000
+        {
+            let digit_count = 1 + (rng.next() % 40) as usize;
+            let digits: String = (0..digit_count).map(|_| char::from(b'0' + (rng.next() % 10) as u8)).collect();
+            let exponent = (rng.next() % 700) as i64 - 350;
+            let dot = (rng.next() % (digit_count as u64 + 1)) as usize;
+
+            assert_same_as_std(&format!("{}.{}e{}", &digits[..dot], &digits[dot..], exponent));
+        }
+    }
+
+    #[test]
+    fn parse_exhaustive_short_decimals_same_as_std()
+    {
+        // Every 1 to 3 digit mantissa (and then some) over a range of exponents that covers the fast and slow paths.
+        for mantissa in 0..2500
+        {
+            for exponent in [ -330, -325, -324, -310, -308, -307, -40, -23, -22, -5, -1, 0, 1, 15, 22, 23, 40, 300, 305, 308, 309 ]
+            {
+                assert_same_as_std(&format!("{}e{}", mantissa, exponent));
+            }
+        }
+    }
+
+    #[test]
+    fn format_shortest()
+    {
+        let values =
+        [
+            (0.0, "0"), (-0.0, "-0"), (1.0, "1"), (-1.5, "-1.5"), (0.1, "0.1"), (0.1 + 0.2, "0.30000000000000004"),
+            (123.45E10, "1234500000000"), (1e20, "100000000000000000000"), (1e21, "1e21"), (1.5e300, "1.5e300"),
+            (0.000001, "0.000001"), (1e-7, "1e-7"), (-1.2345e-8, "-1.2345e-8"), (f64::MAX, "1.7976931348623157e308"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"), (5e-324, "5e-324"), (9007199254740993.0, "9007199254740992"),
+        ];
+
+        for (value, expected) in values
+        {
+            assert_eq!(format_f64(value), expected);
+        }
+    }
+
+    #[test]
+    fn format_round_trips()
+    {
+        let mut rng = XorShift { state: 0xD1B54A32D192ED03 };
+
+        for _ in 0..5000
+        {
+            let value = f64::from_bits(rng.next());
+
+            if !value.is_finite()
+            {
+                continue;
+            }
+
+            let output = format_f64(value);
+            assert_eq!(parse_f64(&output).unwrap().to_bits(), value.to_bits(), "'{}' did not round trip", output);
+            assert_eq!(output.parse::<f64>().unwrap().to_bits(), value.to_bits(), "'{}' did not round trip", output);
+
+            // The same significant digits as the shortest representation from the standard library.
+            let significant_digits = |text: &str| -> String
+            {
+                let digits: String = text.split('e').next().unwrap().chars().filter(|ch| ch.is_ascii_digit()).collect();
+                return String::from(digits.trim_matches('0'));
+            };
+
+            assert_eq!(significant_digits(&output), significant_digits(&format!("{:e}", value)), "'{}' is not the shortest representation", output);
+        }
+    }
+}
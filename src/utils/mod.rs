@@ -1,3 +1,8 @@
 pub mod cli_args;
+#[cfg(feature = "arbitrary_precision")]
+pub mod decimal;
+pub mod diagnostic;
+pub mod float_utils;
+pub mod ordered_map;
 pub mod string_utils;
 
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+/// A map with String keys that iterates in insertion order, with O(1) lookup by key.
+///
+/// Inserting a key that already exists replaces its value but keeps its original position.
+#[derive(Clone, Debug)]
+pub struct OrderedMap<V>
+{
+    entries: Vec<(String, V)>,
+    /// Maps each key to its position in 'entries'.
+    indices: HashMap<String, usize>,
+}
+
+impl<V> OrderedMap<V>
+{
+    pub fn new() -> Self
+    {
+        Self { entries: Vec::new(), indices: HashMap::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self
+    {
+        Self { entries: Vec::with_capacity(capacity), indices: HashMap::with_capacity(capacity) }
+    }
+
+    pub fn len(&self) -> usize
+    {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.entries.is_empty();
+    }
+
+    /// Inserts the value, returning the previous value of the key (if any).
+    pub fn insert(&mut self, key: String, value: V) -> Option<V>
+    {
+        if let Some(index) = self.indices.get(&key)
+        {
+            return Some(std::mem::replace(&mut self.entries[*index].1, value));
+        }
+
+        self.indices.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+
+        return None;
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V>
+    {
+        return self.indices.get(key).map(|index| &self.entries[*index].1);
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V>
+    {
+        return self.indices.get(key).map(|index| &mut self.entries[*index].1);
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool
+    {
+        return self.indices.contains_key(key);
+    }
+
+    /// Removes the key while keeping the order of the remaining keys. This is O(n) since
+    /// every key after it moves up a position.
+    pub fn remove(&mut self, key: &str) -> Option<V>
+    {
+        let index = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(index);
+
+        for (key, _) in &self.entries[index..]
+        {
+            *self.indices.get_mut(key).expect("Every entry should be indexed") -= 1;
+        }
+
+        return Some(value);
+    }
+
+    /// Iterates over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &V)>
+    {
+        return self.entries.iter().map(|(key, value)| (key, value));
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String>
+    {
+        return self.entries.iter().map(|(key, _)| key);
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V>
+    {
+        return self.entries.iter().map(|(_, value)| value);
+    }
+
+    /// Consumes the map, yielding its values in insertion order.
+    pub fn into_values(self) -> impl Iterator<Item = V>
+    {
+        return self.entries.into_iter().map(|(_, value)| value);
+    }
+
+    /// Gets the entries sorted by key (rather than in insertion order).
+    pub fn sorted(&self) -> Vec<(&String, &V)>
+    {
+        let mut result: Vec<(&String, &V)> = self.iter().collect();
+        result.sort_by(|left, right| left.0.cmp(right.0));
+
+        return result;
+    }
+}
+
+impl<V> Default for OrderedMap<V>
+{
+    fn default() -> Self
+    {
+        return Self::new();
+    }
+}
+
+impl<V> FromIterator<(String, V)> for OrderedMap<V>
+{
+    fn from_iter<T: IntoIterator<Item = (String, V)>>(iter: T) -> Self
+    {
+        let mut result = Self::new();
+
+        for (key, value) in iter
+        {
+            result.insert(key, value);
+        }
+
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::OrderedMap;
+
+    #[test]
+    fn ordered_map_keeps_insertion_order()
+    {
+        let mut map = OrderedMap::<i32>::new();
+        assert!(map.is_empty());
+
+        assert_eq!(map.insert(String::from("zebra"), 1), None);
+        assert_eq!(map.insert(String::from("apple"), 2), None);
+        assert_eq!(map.insert(String::from("mango"), 3), None);
+
+        // Replacing a value keeps the key where it was.
+        assert_eq!(map.insert(String::from("zebra"), 4), Some(1));
+
+        let keys: Vec<&String> = map.keys().collect();
+        assert_eq!(keys, vec![ "zebra", "apple", "mango" ]);
+        assert_eq!(map.values().copied().collect::<Vec<i32>>(), vec![ 4, 2, 3 ]);
+        assert_eq!(map.len(), 3);
+
+        let sorted: Vec<&String> = map.sorted().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(sorted, vec![ "apple", "mango", "zebra" ]);
+    }
+
+    #[test]
+    fn ordered_map_lookup_and_remove()
+    {
+        let mut map: OrderedMap<i32> = (0..5).map(|i| (format!("key{}", i), i)).collect();
+        assert_eq!(map.get("key3"), Some(&3));
+        assert!(map.contains_key("key0"));
+        assert_eq!(map.get("missing"), None);
+
+        *map.get_mut("key3").unwrap() = 30;
+        assert_eq!(map.get("key3"), Some(&30));
+
+        assert_eq!(map.remove("key1"), Some(1));
+        assert_eq!(map.remove("key1"), None);
+        assert_eq!(map.get("key4"), Some(&4));
+        assert_eq!(map.get("key3"), Some(&30));
+
+        let keys: Vec<&String> = map.keys().collect();
+        assert_eq!(keys, vec![ "key0", "key2", "key3", "key4" ]);
+
+        map.insert(String::from("key1"), 10);
+        assert_eq!(map.iter().last(), Some((&String::from("key1"), &10)));
+    }
+}
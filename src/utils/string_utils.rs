@@ -16,7 +16,6 @@ impl StringBuilder
         return self.buffer.get(index);
     }
 
-    #[allow(dead_code)]
     #[inline]
     pub fn get_capacity(&self) -> usize
     {
@@ -35,6 +34,13 @@ impl StringBuilder
         self.buffer.clear();
     }
 
+    /// Keeps only the first 'len' characters.
+    #[inline]
+    pub fn truncate(&mut self, len: usize)
+    {
+        self.buffer.truncate(len);
+    }
+
     #[inline]
     pub fn len(&self) -> usize
     {
@@ -47,7 +53,6 @@ impl StringBuilder
         self.buffer.push(value);
     }
 
-    #[allow(dead_code)]
     pub fn append_chars(&mut self, value: &Vec<char>)
     {
         for ch in value
@@ -72,7 +77,6 @@ impl StringBuilder
         }
     }
 
-    #[allow(dead_code)]
     #[inline]
     pub fn as_slice(&self) -> &[char]
     {
@@ -1,7 +1,11 @@
 use crate::rnodes::rnode_array::RNodeArray;
 use crate::rnodes::rnode_bool::RNodeBool;
+#[cfg(feature = "arbitrary_precision")]
+use crate::rnodes::rnode_decimal::RNodeDecimal;
 use crate::rnodes::rnode_double::RNodeDouble;
+use crate::rnodes::rnode_error::RNodeError;
 use crate::rnodes::rnode_null::RNodeNull;
+use crate::rnodes::rnode_number::RNodeNumber;
 use crate::rnodes::rnode_object::RNodeObject;
 use crate::rnodes::rnode_string::RNodeString;
 
@@ -9,8 +13,12 @@ pub trait Visitor
 {
     fn visit_array(&self, node: &RNodeArray);
     fn visit_bool(&self, node: &RNodeBool);
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_decimal(&self, node: &RNodeDecimal);
     fn visit_double(&self, node: &RNodeDouble);
+    fn visit_error(&self, node: &RNodeError);
     fn visit_null(&self, node: &RNodeNull);
+    fn visit_number(&self, node: &RNodeNumber);
     fn visit_object(&self, node: &RNodeObject);
     fn visit_string(&self, node: &RNodeString);
 }